@@ -0,0 +1,83 @@
+//! On-disk cache for slow-changing API lists such as `/coins`.
+//!
+//! Each entry is a JSON file in the cache directory (`$XDG_CACHE_HOME/coinpaprika`
+//! or `~/.coinpaprika/cache/`) that records when it was fetched, so callers
+//! can decide how stale is too stale.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::client::{FREE_BASE_URL, PRO_BASE_URL};
+use crate::{config, secret};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cached<T> {
+    pub fetched_at: DateTime<Utc>,
+    pub data: T,
+}
+
+impl<T> Cached<T> {
+    pub fn age(&self) -> Duration {
+        Utc::now() - self.fetched_at
+    }
+
+    pub fn is_fresh(&self, max_age: Duration) -> bool {
+        self.age() < max_age
+    }
+}
+
+pub fn cache_dir() -> Result<PathBuf> {
+    config::cache_dir()
+}
+
+/// `name` for data from the API at `base_url`, so entries filled from a
+/// mock server or another host never serve runs against the real API. The
+/// free and pro hosts serve the same data and share entries.
+pub fn scoped(name: &str, base_url: &str) -> String {
+    let base = base_url.trim_end_matches('/');
+    let api = if base == PRO_BASE_URL { FREE_BASE_URL } else { base };
+    format!("{name}-{}", &secret::fingerprint(api)[..8])
+}
+
+fn entry_path(name: &str) -> Result<PathBuf> {
+    Ok(cache_dir()?.join(format!("{name}.json")))
+}
+
+/// Read a cache entry. Missing or unreadable entries are treated as absent.
+pub fn load<T: DeserializeOwned>(name: &str) -> Option<Cached<T>> {
+    let contents = fs::read_to_string(entry_path(name).ok()?).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+pub fn store<T: Serialize>(name: &str, data: &T) -> Result<()> {
+    fs::create_dir_all(cache_dir()?)?;
+    let entry = Cached { fetched_at: Utc::now(), data };
+    fs::write(entry_path(name)?, serde_json::to_string(&entry)?)?;
+    Ok(())
+}
+
+/// Return the cached entry if it is younger than `max_age`, otherwise fetch,
+/// store and return fresh data. A stale entry is still used if the fetch fails.
+pub async fn get_or_fetch<T, F, Fut>(name: &str, max_age: Duration, fetch: F) -> Result<Cached<T>>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let stale = match load::<T>(name) {
+        Some(entry) if entry.is_fresh(max_age) => return Ok(entry),
+        other => other,
+    };
+    match fetch().await {
+        Ok(data) => {
+            // A read-only cache dir should not break the command itself.
+            let _ = store(name, &data);
+            Ok(Cached { fetched_at: Utc::now(), data })
+        }
+        Err(e) => stale.ok_or(e),
+    }
+}
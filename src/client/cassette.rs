@@ -0,0 +1,236 @@
+//! Record/replay of API traffic for deterministic offline runs.
+//!
+//! Each request is keyed by its path, sorted query params and whether an API
+//! key was sent, and stored as one JSON fixture file in the cassette
+//! directory. `clock.json` keeps the time of the first recording, which
+//! relative dates such as `--start 7d` are counted from.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::RawResponse;
+
+#[derive(Debug, Clone)]
+pub enum CassetteMode {
+    /// Hit the network and save every response into the directory.
+    Record(PathBuf),
+    /// Serve responses from the directory and never touch the network.
+    Replay(PathBuf),
+}
+
+const CLOCK_FILE: &str = "clock.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Clock {
+    now: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Fixture {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedRequest {
+    path: String,
+    params: Vec<(String, String)>,
+    authenticated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    retry_after: Option<u64>,
+    /// Parsed body, when it was valid JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<serde_json::Value>,
+    /// Verbatim body, when it was not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body_text: Option<String>,
+}
+
+impl CassetteMode {
+    fn dir(&self) -> &Path {
+        match self {
+            CassetteMode::Record(dir) | CassetteMode::Replay(dir) => dir,
+        }
+    }
+
+    pub fn is_replay(&self) -> bool {
+        matches!(self, CassetteMode::Replay(_))
+    }
+
+    /// The cassette's "now": when it was first recorded. Recording into a
+    /// cassette without one starts the clock; replaying one falls back to
+    /// the real time.
+    pub(crate) fn now(&self) -> Result<DateTime<Utc>> {
+        let file = self.dir().join(CLOCK_FILE);
+        if file.exists() {
+            let contents = fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+            let clock: Clock = serde_json::from_str(&contents).with_context(|| format!("Failed to parse {}", file.display()))?;
+            return Ok(clock.now);
+        }
+        let now = Utc::now();
+        if let CassetteMode::Record(dir) = self {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create cassette directory {}", dir.display()))?;
+            fs::write(&file, serde_json::to_string_pretty(&Clock { now })?)
+                .with_context(|| format!("Failed to write {}", file.display()))?;
+        }
+        Ok(now)
+    }
+
+    pub(crate) fn record(&self, path: &str, params: &[(&str, &str)], authenticated: bool, response: &RawResponse) -> Result<()> {
+        let dir = self.dir();
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create cassette directory {}", dir.display()))?;
+        let parsed = serde_json::from_str::<serde_json::Value>(&response.body).ok();
+        let fixture = Fixture {
+            request: RecordedRequest {
+                path: path.to_string(),
+                params: sorted_params(params),
+                authenticated,
+            },
+            response: RecordedResponse {
+                status: response.status,
+                retry_after: response.retry_after,
+                body_text: if parsed.is_none() { Some(response.body.clone()) } else { None },
+                body: parsed,
+            },
+        };
+        let file = dir.join(fixture_name(path, params, authenticated));
+        fs::write(&file, serde_json::to_string_pretty(&fixture)?)
+            .with_context(|| format!("Failed to write fixture {}", file.display()))?;
+        Ok(())
+    }
+
+    pub(crate) fn replay(&self, path: &str, params: &[(&str, &str)], authenticated: bool) -> Result<RawResponse> {
+        let name = fixture_name(path, params, authenticated);
+        let file = self.dir().join(&name);
+        if !file.exists() {
+            let query = sorted_params(params)
+                .iter()
+                .map(|(k, v)| format!("{k}={v}"))
+                .collect::<Vec<_>>()
+                .join("&");
+            bail!(
+                "No recorded response for GET {path}{}{query} ({}) in {}.\n\
+                 Record it first with --record {}",
+                if query.is_empty() { "" } else { "?" },
+                if authenticated { "with API key" } else { "no API key" },
+                self.dir().display(),
+                self.dir().display(),
+            );
+        }
+        let contents = fs::read_to_string(&file).with_context(|| format!("Failed to read fixture {}", file.display()))?;
+        let fixture: Fixture =
+            serde_json::from_str(&contents).with_context(|| format!("Failed to parse fixture {}", file.display()))?;
+        let body = match (fixture.response.body, fixture.response.body_text) {
+            (Some(json), _) => json.to_string(),
+            (None, Some(text)) => text,
+            (None, None) => String::new(),
+        };
+        Ok(RawResponse {
+            status: fixture.response.status,
+            body,
+            retry_after: fixture.response.retry_after,
+        })
+    }
+}
+
+fn sorted_params(params: &[(&str, &str)]) -> Vec<(String, String)> {
+    let mut sorted: Vec<(String, String)> = params.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    sorted.sort();
+    sorted
+}
+
+/// `tickers_btc-bitcoin-<hash>.json`: readable prefix, collision-free suffix.
+fn fixture_name(path: &str, params: &[(&str, &str)], authenticated: bool) -> String {
+    let mut key = path.to_string();
+    for (k, v) in sorted_params(params) {
+        key.push_str(&format!("&{k}={v}"));
+    }
+    key.push_str(if authenticated { "#auth" } else { "#anon" });
+
+    let slug: String = path
+        .trim_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .take(60)
+        .collect();
+    format!("{slug}-{:016x}.json", fnv1a(key.as_bytes()))
+}
+
+/// FNV-1a: stable across Rust versions, unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in bytes {
+        hash ^= u64::from(*b);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("coinpaprika-cassette-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn response(status: u16, body: &str) -> RawResponse {
+        RawResponse {
+            status,
+            body: body.to_string(),
+            retry_after: None,
+        }
+    }
+
+    #[test]
+    fn fixture_name_ignores_param_order() {
+        let a = fixture_name("/tickers", &[("quotes", "USD"), ("limit", "5")], false);
+        let b = fixture_name("/tickers", &[("limit", "5"), ("quotes", "USD")], false);
+        assert_eq!(a, b);
+        assert!(a.starts_with("tickers-"));
+    }
+
+    #[test]
+    fn fixture_name_depends_on_auth() {
+        assert_ne!(fixture_name("/global", &[], false), fixture_name("/global", &[], true));
+    }
+
+    #[test]
+    fn record_then_replay_round_trips() {
+        let dir = temp_dir("roundtrip");
+        let params = [("quotes", "USD")];
+        CassetteMode::Record(dir.clone())
+            .record("/tickers/btc-bitcoin", &params, false, &response(200, r#"{"id":"btc-bitcoin"}"#))
+            .unwrap();
+        let replayed = CassetteMode::Replay(dir.clone()).replay("/tickers/btc-bitcoin", &params, false).unwrap();
+        assert_eq!(replayed.status, 200);
+        assert_eq!(replayed.body, r#"{"id":"btc-bitcoin"}"#);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn non_json_bodies_are_kept_verbatim() {
+        let dir = temp_dir("text");
+        CassetteMode::Record(dir.clone()).record("/global", &[], false, &response(502, "<html>bad gateway")).unwrap();
+        let replayed = CassetteMode::Replay(dir.clone()).replay("/global", &[], false).unwrap();
+        assert_eq!((replayed.status, replayed.body.as_str()), (502, "<html>bad gateway"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn replay_fails_on_unmatched_request() {
+        let dir = temp_dir("miss");
+        let err = CassetteMode::Replay(dir).replay("/global", &[], false).unwrap_err();
+        assert!(err.to_string().contains("No recorded response for GET /global"));
+    }
+}
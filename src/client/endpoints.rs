@@ -0,0 +1,201 @@
+use anyhow::Result;
+
+use super::ApiClient;
+use crate::params::{HistoricalParams, PageParams, SearchParams, TickersParams};
+use crate::plans::{History, Need};
+use crate::types::{
+    ChangelogEntry, CoinDetail, CoinEvent, CoinExchange, CoinListItem, CoinMapping, CoinMarket, Contract,
+    ContractHistoryPoint, ContractTicker, ConvertResult, Exchange, ExchangeMarket, GlobalData, KeyInfo, OhlcvData,
+    Person, SearchResult, Tag, Ticker, TickerHistoryPoint,
+};
+
+/// One method per CoinPaprika endpoint the CLI uses.
+impl ApiClient {
+    async fn get_query<T: serde::de::DeserializeOwned>(&self, path: &str, query: &[(&'static str, String)]) -> Result<T> {
+        let params: Vec<(&str, &str)> = query.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        self.coinpaprika_get(path, &params).await
+    }
+
+    /// `params` as sent. With a cassette, relative dates are counted from its
+    /// clock, so a replay asks for what the recording did.
+    fn historical_query(&self, params: &HistoricalParams) -> Result<Vec<(&'static str, String)>> {
+        Ok(match &self.cassette {
+            Some(cassette) => params.at(cassette.now()?).query(),
+            None => params.query(),
+        })
+    }
+
+    /// `GET /global`
+    pub async fn global(&self) -> Result<GlobalData> {
+        self.coinpaprika_get("/global", &[]).await
+    }
+
+    /// `GET /coins`
+    pub async fn coins(&self) -> Result<Vec<CoinListItem>> {
+        self.coinpaprika_get("/coins", &[]).await
+    }
+
+    /// `GET /coins/{coin_id}`
+    pub async fn coin(&self, coin_id: &str) -> Result<CoinDetail> {
+        self.coinpaprika_get(&format!("/coins/{coin_id}"), &[]).await
+    }
+
+    /// `GET /coins/{coin_id}/events`
+    pub async fn coin_events(&self, coin_id: &str) -> Result<Vec<CoinEvent>> {
+        self.coinpaprika_get(&format!("/coins/{coin_id}/events"), &[]).await
+    }
+
+    /// `GET /coins/{coin_id}/exchanges`
+    pub async fn coin_exchanges(&self, coin_id: &str) -> Result<Vec<CoinExchange>> {
+        self.coinpaprika_get(&format!("/coins/{coin_id}/exchanges"), &[]).await
+    }
+
+    /// `GET /coins/{coin_id}/markets`
+    pub async fn coin_markets(&self, coin_id: &str, quotes: &str) -> Result<Vec<CoinMarket>> {
+        self.coinpaprika_get(&format!("/coins/{coin_id}/markets"), &[("quotes", quotes)]).await
+    }
+
+    /// `GET /tickers`
+    pub async fn tickers(&self, params: &TickersParams) -> Result<Vec<Ticker>> {
+        self.get_query("/tickers", &params.query()).await
+    }
+
+    /// `GET /tickers/{coin_id}`
+    pub async fn ticker(&self, coin_id: &str, quotes: &str) -> Result<Ticker> {
+        self.coinpaprika_get(&format!("/tickers/{coin_id}"), &[("quotes", quotes)]).await
+    }
+
+    /// `GET /tickers/{coin_id}/historical` [PAID: Starter+]
+    pub async fn ticker_history(&self, coin_id: &str, params: &HistoricalParams) -> Result<Vec<TickerHistoryPoint>> {
+        params.check_range()?;
+        self.check_plan(params.need(History::Ticker)).await?;
+        self.get_query(&format!("/tickers/{coin_id}/historical"), &self.historical_query(params)?).await
+    }
+
+    /// `GET /coins/{coin_id}/ohlcv/historical` [FREE: 24h within a year; PAID: Starter+ for the rest]
+    pub async fn ohlcv_historical(&self, coin_id: &str, params: &HistoricalParams) -> Result<Vec<OhlcvData>> {
+        params.check_range()?;
+        self.check_plan(params.need(History::Ohlcv)).await?;
+        self.get_query(&format!("/coins/{coin_id}/ohlcv/historical"), &self.historical_query(params)?).await
+    }
+
+    /// `GET /coins/{coin_id}/ohlcv/latest`
+    pub async fn ohlcv_latest(&self, coin_id: &str, quote: &str) -> Result<Vec<OhlcvData>> {
+        self.coinpaprika_get(&format!("/coins/{coin_id}/ohlcv/latest"), &[("quote", quote)]).await
+    }
+
+    /// `GET /coins/{coin_id}/ohlcv/today`
+    pub async fn ohlcv_today(&self, coin_id: &str, quote: &str) -> Result<Vec<OhlcvData>> {
+        self.coinpaprika_get(&format!("/coins/{coin_id}/ohlcv/today"), &[("quote", quote)]).await
+    }
+
+    /// `GET /exchanges`
+    pub async fn exchanges(&self, quotes: &str) -> Result<Vec<Exchange>> {
+        self.coinpaprika_get("/exchanges", &[("quotes", quotes)]).await
+    }
+
+    /// `GET /exchanges/{exchange_id}`
+    pub async fn exchange(&self, exchange_id: &str, quotes: &str) -> Result<Exchange> {
+        self.coinpaprika_get(&format!("/exchanges/{exchange_id}"), &[("quotes", quotes)]).await
+    }
+
+    /// `GET /exchanges/{exchange_id}/markets`
+    pub async fn exchange_markets(&self, exchange_id: &str, quotes: &str) -> Result<Vec<ExchangeMarket>> {
+        self.coinpaprika_get(&format!("/exchanges/{exchange_id}/markets"), &[("quotes", quotes)]).await
+    }
+
+    /// `GET /tags`
+    pub async fn tags(&self) -> Result<Vec<Tag>> {
+        self.coinpaprika_get("/tags", &[]).await
+    }
+
+    /// `GET /tags/{tag_id}`
+    pub async fn tag(&self, tag_id: &str) -> Result<Tag> {
+        self.coinpaprika_get(&format!("/tags/{tag_id}"), &[]).await
+    }
+
+    /// `GET /people/{person_id}`
+    pub async fn person(&self, person_id: &str) -> Result<Person> {
+        self.coinpaprika_get(&format!("/people/{person_id}"), &[]).await
+    }
+
+    /// `GET /search`
+    pub async fn search(&self, params: &SearchParams) -> Result<SearchResult> {
+        self.get_query("/search", &params.query()).await
+    }
+
+    /// `GET /price-converter`
+    pub async fn price_converter(&self, base_id: &str, quote_id: &str, amount: f64) -> Result<ConvertResult> {
+        let amount = amount.to_string();
+        self.coinpaprika_get(
+            "/price-converter",
+            &[("base_currency_id", base_id), ("quote_currency_id", quote_id), ("amount", &amount)],
+        )
+        .await
+    }
+
+    /// `GET /contracts`
+    pub async fn platforms(&self) -> Result<Vec<String>> {
+        self.coinpaprika_get("/contracts", &[]).await
+    }
+
+    /// `GET /contracts/{platform_id}`
+    pub async fn contracts(&self, platform_id: &str) -> Result<Vec<Contract>> {
+        self.coinpaprika_get(&format!("/contracts/{platform_id}"), &[]).await
+    }
+
+    /// `GET /contracts/{platform_id}/{address}`
+    pub async fn contract_ticker(&self, platform_id: &str, address: &str) -> Result<ContractTicker> {
+        self.coinpaprika_get(&format!("/contracts/{platform_id}/{address}"), &[]).await
+    }
+
+    /// `GET /contracts/{platform_id}/{address}/historical` [PAID: Starter+]
+    pub async fn contract_history(
+        &self,
+        platform_id: &str,
+        address: &str,
+        params: &HistoricalParams,
+    ) -> Result<Vec<ContractHistoryPoint>> {
+        params.check_range()?;
+        self.check_plan(params.need(History::Contract)).await?;
+        self.get_query(&format!("/contracts/{platform_id}/{address}/historical"), &self.historical_query(params)?).await
+    }
+
+    /// `GET /key/info` [PAID: requires API key]
+    pub async fn key_info(&self) -> Result<KeyInfo> {
+        self.coinpaprika_get("/key/info", &[]).await
+    }
+
+    /// `GET /coins/mappings` [PAID: Business+]
+    pub async fn mappings(&self) -> Result<Vec<CoinMapping>> {
+        self.check_plan(Need::Mappings).await?;
+        self.coinpaprika_get("/coins/mappings", &[]).await
+    }
+
+    /// `GET /changelog/ids` [PAID: Starter+]
+    pub async fn changelog(&self, params: &PageParams) -> Result<Vec<ChangelogEntry>> {
+        self.check_plan(Need::Changelog).await?;
+        self.get_query("/changelog/ids", &params.query()).await
+    }
+
+    /// Every page of `/changelog/ids`, newest first. Stops at the first page
+    /// that is empty or shorter than the one before it, which also copes with
+    /// the server capping `limit` below what was asked for.
+    pub async fn changelog_all(&self) -> Result<Vec<ChangelogEntry>> {
+        const PAGE_SIZE: usize = 1000;
+        const MAX_PAGES: usize = 500;
+
+        let mut entries = Vec::new();
+        let mut previous_len = 0;
+        for page in 1..=MAX_PAGES {
+            let batch = self.changelog(&PageParams::new().limit(PAGE_SIZE).page(page)).await?;
+            let len = batch.len();
+            entries.extend(batch);
+            if len == 0 || (page > 1 && len < previous_len) {
+                break;
+            }
+            previous_len = len;
+        }
+        Ok(entries)
+    }
+}
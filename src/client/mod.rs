@@ -0,0 +1,349 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::plans::{self, Need, Plan};
+use crate::{cache, secret, usage};
+
+mod cassette;
+mod endpoints;
+
+pub use cassette::CassetteMode;
+
+pub const FREE_BASE_URL: &str = "https://api.coinpaprika.com/v1";
+pub const PRO_BASE_URL: &str = "https://api-pro.coinpaprika.com/v1";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Retries after a 5xx, timeout or connection error, with exponential
+/// backoff from [`RETRY_BACKOFF`].
+const DEFAULT_RETRIES: u32 = 2;
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+/// A 429 is retried only when `Retry-After` asks for at most this long.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(10);
+
+/// How long the plan from `/key/info` is trusted before asking again.
+const PLAN_MAX_AGE: chrono::Duration = chrono::Duration::hours(24);
+
+/// An HTTP response before status handling and deserialization.
+#[derive(Debug, Clone)]
+pub(crate) struct RawResponse {
+    pub status: u16,
+    pub body: String,
+    /// Seconds from the `Retry-After` header, if any.
+    pub retry_after: Option<u64>,
+}
+
+/// The outcome of [`ApiClient::probe`].
+#[derive(Debug, Clone)]
+pub struct Probe {
+    pub status: u16,
+    pub elapsed: Duration,
+    /// Local time minus the response's `Date` header, when it has one.
+    pub clock_skew: Option<chrono::TimeDelta>,
+}
+
+/// HTTP client for the CoinPaprika API.
+///
+/// Uses the pro host when an API key is set and the free host otherwise.
+pub struct ApiClient {
+    http: reqwest::Client,
+    coinpaprika_base: String,
+    api_key: Option<String>,
+    cassette: Option<CassetteMode>,
+    /// Successful responses by request and when they arrived, when enabled.
+    responses: Option<Mutex<HashMap<String, (Instant, RawResponse)>>>,
+    /// How long a cached response is served; `None` is the life of the client.
+    response_max_age: Option<Duration>,
+    retries: u32,
+    /// Calls by endpoint (last status, count), written to the usage log on drop.
+    usage: Option<Mutex<BTreeMap<String, (u16, u64)>>>,
+    plan_checks: bool,
+    /// The key's plan once looked up; `Some(None)` when it couldn't be.
+    plan: Mutex<Option<Option<&'static Plan>>>,
+}
+
+impl ApiClient {
+    pub fn new(api_key: Option<String>) -> Self {
+        let coinpaprika_base = if api_key.is_some() { PRO_BASE_URL } else { FREE_BASE_URL }.to_string();
+
+        Self {
+            http: reqwest::Client::builder()
+                .user_agent("coinpaprika-cli/0.1.0")
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("failed to build HTTP client"),
+            coinpaprika_base,
+            api_key,
+            cassette: None,
+            responses: None,
+            response_max_age: None,
+            retries: DEFAULT_RETRIES,
+            usage: None,
+            plan_checks: true,
+            plan: Mutex::new(None),
+        }
+    }
+
+    /// Send requests to another host, e.g. `coinpaprika-cli mock-server`.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.coinpaprika_base = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.coinpaprika_base
+    }
+
+    /// The [`cache`] entry `name` for this client's API; see [`cache::scoped`].
+    pub fn cache_name(&self, name: &str) -> String {
+        cache::scoped(name, &self.coinpaprika_base)
+    }
+
+    /// Record responses to, or replay them from, a fixture directory.
+    pub fn with_cassette(mut self, mode: CassetteMode) -> Self {
+        self.cassette = Some(mode);
+        self
+    }
+
+    /// How many times to retry a transient failure (default 2; 0 disables).
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Remember successful responses for the life of the client, so repeated
+    /// requests (e.g. across the lines of a `batch` file) hit the network once.
+    pub fn with_response_cache(mut self) -> Self {
+        self.responses = Some(Mutex::new(HashMap::new()));
+        self
+    }
+
+    /// Like [`with_response_cache`](Self::with_response_cache), but ask again
+    /// once a response is `max_age` old, for long sessions such as `shell`.
+    pub fn with_response_cache_for(mut self, max_age: Duration) -> Self {
+        self.response_max_age = Some(max_age);
+        self.with_response_cache()
+    }
+
+    /// Log which endpoints this client calls, for `doctor`; see [`usage`].
+    pub fn with_usage_log(mut self) -> Self {
+        self.usage = Some(Mutex::new(BTreeMap::new()));
+        self
+    }
+
+    /// Send every request and let the API decide, instead of rejecting
+    /// ones the plan can't serve up front; see [`plans`].
+    pub fn without_plan_checks(mut self) -> Self {
+        self.plan_checks = false;
+        self
+    }
+
+    /// The plan requests are made under: free without a key, otherwise the
+    /// one `/key/info` names, cached for a day. `None` if it can't be told.
+    pub async fn plan(&self) -> Option<&'static Plan> {
+        if let Some(plan) = *self.plan.lock().unwrap_or_else(|e| e.into_inner()) {
+            return plan;
+        }
+        let plan = match &self.api_key {
+            None => Some(&plans::FREE),
+            Some(key) => {
+                // Named after a hash so the key itself never lands on disk.
+                let id = secret::fingerprint(key);
+                let fetch = || async { Ok(self.key_info().await?.plan.unwrap_or_default()) };
+                let name = cache::get_or_fetch::<String, _, _>(&self.cache_name(&format!("plan-{id}")), PLAN_MAX_AGE, fetch).await;
+                name.ok().and_then(|cached| plans::find(&cached.data))
+            }
+        };
+        *self.plan.lock().unwrap_or_else(|e| e.into_inner()) = Some(plan);
+        plan
+    }
+
+    /// Fail before sending a request the plan can't serve.
+    pub(crate) async fn check_plan(&self, need: Need) -> Result<()> {
+        if !self.plan_checks {
+            return Ok(());
+        }
+        match self.plan().await {
+            Some(plan) => plan.check(need),
+            None => Ok(()),
+        }
+    }
+
+    /// One timed GET of `path`, with no retries, cassette or status
+    /// handling. For health checks.
+    pub async fn probe(&self, path: &str) -> Result<Probe> {
+        let start = Instant::now();
+        let resp = self.request(path, &[]).timeout(PROBE_TIMEOUT).send().await?;
+        let elapsed = start.elapsed();
+        let clock_skew = resp
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+            .map(|date| Utc::now() - date.with_timezone(&Utc));
+        Ok(Probe { status: resp.status().as_u16(), elapsed, clock_skew })
+    }
+
+    /// GET an arbitrary API path and deserialize the JSON body, mapping
+    /// error statuses to actionable messages.
+    pub async fn coinpaprika_get<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T> {
+        let RawResponse { status, body, retry_after } = self.fetch(path, params).await?;
+        let status = StatusCode::from_u16(status)?;
+
+        if !status.is_success() {
+            match status {
+                StatusCode::PAYMENT_REQUIRED => {
+                    let has_key = self.api_key.is_some();
+                    if has_key {
+                        bail!(
+                            "This endpoint requires a higher-tier plan.\n\n\
+                             Check your plan:   coinpaprika-cli key-info\n\
+                             Upgrade:           https://coinpaprika.com/api/pricing\n\n\
+                             Available plans:\n\
+                             \x20 Starter    — Historical data, ticker history, changelog\n\
+                             \x20 Business   — All Starter features + ID mappings, priority support\n\
+                             \x20 Enterprise — Custom limits, dedicated support"
+                        );
+                    } else {
+                        bail!(
+                            "Free tier rate limit reached, or this endpoint requires a paid plan.\n\n\
+                             If you just hit the rate limit (20,000 calls/mo), wait and retry.\n\
+                             Run coinpaprika-cli plans to see free tier limits.\n\n\
+                             To unlock higher limits and all endpoints:\n\
+                             \x20 Get your API key:  https://coinpaprika.com/api/pricing\n\
+                             \x20 Set your key:      coinpaprika-cli config set-key <YOUR_KEY>"
+                        );
+                    }
+                }
+                StatusCode::TOO_MANY_REQUESTS => match retry_after {
+                    Some(secs) => bail!("Rate limit exceeded. Retry after {secs}s."),
+                    None => bail!("Rate limit exceeded. Wait a moment and try again."),
+                },
+                StatusCode::FORBIDDEN => {
+                    bail!("Invalid API key. Check your key with `coinpaprika-cli config show`");
+                }
+                StatusCode::NOT_FOUND => {
+                    bail!("Not found. Check the ID format (e.g., btc-bitcoin for Bitcoin). API response: {body}");
+                }
+                s if s.is_server_error() => {
+                    bail!("CoinPaprika API is temporarily unavailable. Try again shortly. ({status})");
+                }
+                _ => {
+                    bail!("CoinPaprika API error {status}: {body}");
+                }
+            }
+        }
+
+        serde_json::from_str(&body).with_context(|| format!("Failed to parse API response from {path}"))
+    }
+
+    /// Status and body for a GET, from the response cache, the cassette or
+    /// the network.
+    async fn fetch(&self, path: &str, params: &[(&str, &str)]) -> Result<RawResponse> {
+        let Some(responses) = &self.responses else {
+            return self.fetch_uncached(path, params).await;
+        };
+        let key = format!("{path}?{}", params.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join("&"));
+        if let Some((at, raw)) = responses.lock().unwrap_or_else(|e| e.into_inner()).get(&key) {
+            if self.response_max_age.is_none_or(|max| at.elapsed() < max) {
+                return Ok(raw.clone());
+            }
+        }
+        let raw = self.fetch_uncached(path, params).await?;
+        if (200..300).contains(&raw.status) {
+            responses.lock().unwrap_or_else(|e| e.into_inner()).insert(key, (Instant::now(), raw.clone()));
+        }
+        Ok(raw)
+    }
+
+    async fn fetch_uncached(&self, path: &str, params: &[(&str, &str)]) -> Result<RawResponse> {
+        let authenticated = self.api_key.is_some();
+        if let Some(cassette) = self.cassette.as_ref().filter(|c| c.is_replay()) {
+            return cassette.replay(path, params, authenticated);
+        }
+
+        let mut attempt = 0;
+        let raw = loop {
+            let result = self.send(path, params).await;
+            let delay = match &result {
+                Ok(raw) if raw.status == 429 => {
+                    raw.retry_after.map(Duration::from_secs).filter(|d| *d <= MAX_RETRY_AFTER)
+                }
+                Ok(raw) if raw.status >= 500 => Some(RETRY_BACKOFF * 2u32.pow(attempt)),
+                Ok(_) => None,
+                Err(e) if e.is_timeout() || e.is_connect() => Some(RETRY_BACKOFF * 2u32.pow(attempt)),
+                Err(_) => None,
+            };
+            match delay {
+                Some(delay) if attempt < self.retries => {
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                _ => break result,
+            }
+        };
+        let raw = raw.map_err(|e| {
+            if e.is_timeout() {
+                anyhow!("CoinPaprika API did not respond within {}s. Try again shortly.", REQUEST_TIMEOUT.as_secs())
+            } else {
+                e.into()
+            }
+        })?;
+
+        if let Some(cassette) = &self.cassette {
+            cassette.record(path, params, authenticated, &raw)?;
+        }
+        if let Some(usage) = &self.usage {
+            let mut calls = usage.lock().unwrap_or_else(|e| e.into_inner());
+            let entry = calls.entry(usage::endpoint(path)).or_default();
+            *entry = (raw.status, entry.1 + 1);
+        }
+        Ok(raw)
+    }
+
+    fn request(&self, path: &str, params: &[(&str, &str)]) -> reqwest::RequestBuilder {
+        let url = format!("{}{}", self.coinpaprika_base, path);
+        let mut req = self.http.get(&url);
+
+        if let Some(key) = &self.api_key {
+            req = req.header("Authorization", key.as_str());
+        }
+
+        if !params.is_empty() {
+            req = req.query(params);
+        }
+        req
+    }
+
+    /// One GET over the network.
+    async fn send(&self, path: &str, params: &[(&str, &str)]) -> reqwest::Result<RawResponse> {
+        let resp = self.request(path, params).send().await?;
+        let status = resp.status().as_u16();
+        let retry_after = resp
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok());
+        let body = resp.text().await?;
+        Ok(RawResponse { status, body, retry_after })
+    }
+}
+
+impl Drop for ApiClient {
+    fn drop(&mut self) {
+        if let Some(usage) = self.usage.take() {
+            // The log only feeds `doctor`; failing to write it isn't worth an error.
+            let calls = usage.into_inner().unwrap_or_else(|e| e.into_inner());
+            let _ = usage::record(&calls, &usage::key_label(self.api_key.as_deref()));
+        }
+    }
+}
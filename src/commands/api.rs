@@ -0,0 +1,157 @@
+//! `api GET /path`: call any endpoint through the client, for endpoints the
+//! CLI has no command for yet. Auth, retries and error messages are the same
+//! as for every other command.
+
+use anyhow::{bail, Result};
+use clap::Args;
+use coinpaprika_cli::ApiClient;
+use serde_json::Value;
+
+use super::{CommandOutput, OutputData};
+
+/// Pages fetched by `--paginate` when no `limit` parameter is given.
+const DEFAULT_PAGE_SIZE: usize = 1000;
+
+#[derive(Args)]
+pub struct ApiArgs {
+    /// HTTP method; the API is read-only, so only GET is supported
+    #[arg(value_name = "METHOD")]
+    method: String,
+    /// Endpoint path, e.g. /tickers/btc-bitcoin (a leading /v1 is optional)
+    #[arg(value_name = "PATH")]
+    path: String,
+    /// Query parameter, repeatable
+    #[arg(short = 'p', long = "param", value_name = "KEY=VALUE")]
+    params: Vec<String>,
+    /// Follow `page` until the last page and concatenate the results
+    #[arg(long)]
+    paginate: bool,
+    /// Stop --paginate after this many pages
+    #[arg(long, default_value = "500", requires = "paginate")]
+    max_pages: usize,
+}
+
+pub async fn execute(client: &ApiClient, args: ApiArgs) -> Result<CommandOutput> {
+    if !args.method.eq_ignore_ascii_case("GET") {
+        bail!("Only GET is supported: the CoinPaprika API is read-only.");
+    }
+    let (path, mut params) = parse_path(&args.path)?;
+    for param in &args.params {
+        let Some((key, value)) = param.split_once('=') else {
+            bail!("Invalid parameter '{param}'. Use -p KEY=VALUE, e.g. -p quotes=USD,EUR");
+        };
+        params.push((key.to_string(), value.to_string()));
+    }
+
+    let body = if args.paginate {
+        paginate(client, &path, params, args.max_pages).await?
+    } else {
+        let params: Vec<(&str, &str)> = params.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        client.coinpaprika_get::<Value>(&path, &params).await?
+    };
+    Ok(CommandOutput::new(OutputData::Api(body), &path))
+}
+
+/// Fetch pages from `page` (default 1) on and join the arrays, stopping at a
+/// page shorter than `limit`, or at a repeated page from an endpoint that
+/// ignores `page`.
+async fn paginate(client: &ApiClient, path: &str, mut params: Vec<(String, String)>, max_pages: usize) -> Result<Value> {
+    let first = positive(&params, "page")?.unwrap_or(1);
+    let limit = match positive(&params, "limit")? {
+        Some(limit) => limit,
+        None => {
+            params.push(("limit".into(), DEFAULT_PAGE_SIZE.to_string()));
+            DEFAULT_PAGE_SIZE
+        }
+    };
+    params.retain(|(k, _)| k != "page");
+
+    let mut items = Vec::new();
+    let mut previous: Vec<Value> = Vec::new();
+    for page in first..first.saturating_add(max_pages) {
+        let page_param = page.to_string();
+        let mut query: Vec<(&str, &str)> = params.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        query.push(("page", &page_param));
+        let Value::Array(batch) = client.coinpaprika_get::<Value>(path, &query).await? else {
+            bail!("{path} did not return an array on page {page}, so it cannot be paginated. Drop --paginate.");
+        };
+        if page != first && batch == previous {
+            break;
+        }
+        let last_page = batch.len() < limit;
+        items.extend(batch.iter().cloned());
+        if last_page {
+            break;
+        }
+        previous = batch;
+    }
+    Ok(Value::Array(items))
+}
+
+/// The last `key` parameter, which must be a positive integer.
+fn positive(params: &[(String, String)], key: &str) -> Result<Option<usize>> {
+    let Some((_, value)) = params.iter().rev().find(|(k, _)| k == key) else {
+        return Ok(None);
+    };
+    match value.parse() {
+        Ok(n) if n > 0 => Ok(Some(n)),
+        _ => bail!("Invalid {key} '{value}': --paginate needs a positive whole number."),
+    }
+}
+
+/// Split `/v1/tickers?quotes=EUR` into `/tickers` and its query parameters.
+fn parse_path(input: &str) -> Result<(String, Vec<(String, String)>)> {
+    if input.contains("://") {
+        bail!("Give the endpoint path, not a URL, e.g. coinpaprika-cli api GET /global. Use --base-url to change the host.");
+    }
+    let (path, query) = input.split_once('?').unwrap_or((input, ""));
+    let path = format!("/{}", path.trim_start_matches('/'));
+    let path = match path.strip_prefix("/v1") {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => rest.to_string(),
+        _ => path,
+    };
+    if path.is_empty() || path == "/" {
+        bail!("No endpoint given, e.g. coinpaprika-cli api GET /global");
+    }
+    let params = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (k.to_string(), v.to_string())
+        })
+        .collect();
+    Ok((path, params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_paths_and_query_strings() {
+        let (path, params) = parse_path("/v1/tickers?quotes=EUR&limit=5").unwrap();
+        assert_eq!(path, "/tickers");
+        assert_eq!(params, [("quotes".to_string(), "EUR".to_string()), ("limit".to_string(), "5".to_string())]);
+        assert_eq!(parse_path("global").unwrap().0, "/global");
+        assert_eq!(parse_path("/v1abc").unwrap().0, "/v1abc");
+        assert!(parse_path("/v1").is_err());
+        assert!(parse_path("https://api.coinpaprika.com/v1/global").is_err());
+    }
+
+    #[tokio::test]
+    async fn paginate_stops_at_a_short_page() {
+        let mock = crate::mock_server::TestServer::start(&[]).await;
+        let client = ApiClient::new(None).with_base_url(&mock.base_url);
+        let params = |pairs: &[(&str, &str)]| pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<_>>();
+
+        // The mock serves 120 changelog entries.
+        let all = paginate(&client, "/changelog/ids", params(&[("limit", "50")]), 10).await.unwrap();
+        assert_eq!((all.as_array().unwrap().len(), mock.requests()), (120, 3));
+        let one_page = paginate(&client, "/changelog/ids", params(&[("limit", "200")]), 10).await.unwrap();
+        assert_eq!((one_page.as_array().unwrap().len(), mock.requests()), (120, 4));
+        let from_page_2 = paginate(&client, "/changelog/ids", params(&[("limit", "50"), ("page", "2")]), 10).await.unwrap();
+        assert_eq!(from_page_2.as_array().unwrap().len(), 70);
+        assert!(paginate(&client, "/changelog/ids", params(&[("page", "two")]), 10).await.is_err());
+    }
+}
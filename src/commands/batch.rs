@@ -0,0 +1,169 @@
+//! `batch <file>`: run many CLI commands with one client, so they share a
+//! connection pool and response cache.
+
+use anyhow::{Context, Result};
+use clap::parser::ValueSource;
+use clap::{Args, FromArgMatches};
+use coinpaprika_cli::config::Settings;
+use coinpaprika_cli::ApiClient;
+use futures::stream::{self, StreamExt};
+use serde_json::{json, Map, Value};
+use std::io::Read;
+use std::path::PathBuf;
+
+use super::{AlreadyReported, CommandOutput};
+use crate::output::OutputFormat;
+use crate::{shell, Cli, Commands};
+
+#[derive(Args)]
+pub struct BatchFileArgs {
+    /// File with one command per line (as typed in `shell`), or `-` for stdin
+    #[arg(value_name = "FILE|-")]
+    file: String,
+    /// How many commands to run at once
+    #[arg(long, default_value = "1", value_parser = clap::value_parser!(u16).range(1..=64))]
+    parallel: u16,
+    /// Print one JSON object per line instead of a single document
+    #[arg(long, conflicts_with = "out_dir")]
+    ndjson: bool,
+    /// Write each command's output to DIR/line-NNN.json (or .csv with -o csv)
+    #[arg(long, value_name = "DIR")]
+    out_dir: Option<PathBuf>,
+}
+
+/// Global flags that set up the client or the output, which all lines
+/// share, so a line can't set them.
+const BATCH_WIDE: &[&str] = &["output", "raw", "table_style", "api_key", "profile", "base_url", "record", "replay"];
+
+struct Line {
+    number: usize,
+    text: String,
+}
+
+pub async fn run(
+    args: BatchFileArgs,
+    client: &ApiClient,
+    settings: &Settings,
+    strict: bool,
+    format: OutputFormat,
+    raw: bool,
+) -> Result<()> {
+    let text = if args.file == "-" {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).context("Failed to read commands from stdin")?;
+        text
+    } else {
+        std::fs::read_to_string(&args.file).with_context(|| format!("Failed to read {}", args.file))?
+    };
+    let lines = parse_lines(&text);
+
+    // Built up front but not started until polled, so at most `parallel`
+    // commands run at a time.
+    let runs: Vec<_> = lines.iter().map(|line| async move { (line, run_line(line, client, settings, strict).await) }).collect();
+    let results: Vec<(&Line, Result<CommandOutput>)> = stream::iter(runs).buffered(usize::from(args.parallel)).collect().await;
+
+    let mut failed = Vec::new();
+    let mut document = Map::new();
+    let mut written = 0;
+    for (line, result) in &results {
+        let mut entry = Map::new();
+        if args.ndjson {
+            entry.insert("line".into(), json!(line.number));
+        }
+        entry.insert("command".into(), json!(line.text));
+        match result {
+            Ok(output) => {
+                entry.insert("data".into(), serde_json::to_value(&output.data)?);
+                if !raw {
+                    entry.insert("_meta".into(), serde_json::to_value(&output.meta)?);
+                }
+                if let Some(dir) = &args.out_dir {
+                    write_output(dir, line.number, output, format, raw)?;
+                    written += 1;
+                }
+            }
+            Err(e) => {
+                eprintln!("Line {}: {e}", line.number);
+                entry.insert("error".into(), json!(e.to_string()));
+                failed.push(line.number);
+            }
+        }
+        if args.ndjson {
+            println!("{}", Value::Object(entry));
+        } else {
+            document.insert(line.number.to_string(), Value::Object(entry));
+        }
+    }
+
+    match &args.out_dir {
+        Some(dir) => println!("Wrote {written} of {} results to {}", results.len(), dir.display()),
+        None if !args.ndjson => println!("{}", serde_json::to_string_pretty(&document)?),
+        None => {}
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        let lines: Vec<String> = failed.iter().map(usize::to_string).collect();
+        let summary = format!("{} of {} commands failed (lines {})", failed.len(), results.len(), lines.join(", "));
+        Err(AlreadyReported(summary).into())
+    }
+}
+
+/// Non-empty, non-comment lines with their 1-based line numbers. A leading
+/// `coinpaprika-cli` is optional.
+fn parse_lines(text: &str) -> Vec<Line> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| Line {
+            number,
+            text: line.strip_prefix("coinpaprika-cli ").unwrap_or(line).trim().to_string(),
+        })
+        .collect()
+}
+
+async fn run_line(line: &Line, client: &ApiClient, settings: &Settings, strict: bool) -> Result<CommandOutput> {
+    let mut argv = vec!["coinpaprika-cli".to_string()];
+    argv.extend(shell::split_args(&line.text));
+    let clap_error = |e: clap::Error| anyhow::anyhow!(e.to_string().trim_end().to_string());
+    let matches = crate::cli_command(settings).try_get_matches_from(&argv).map_err(clap_error)?;
+    if let Some(id) = BATCH_WIDE.iter().find(|id| matches.value_source(id) == Some(ValueSource::CommandLine)) {
+        anyhow::bail!("--{} applies to the whole batch; pass it to `batch` instead", id.replace('_', "-"));
+    }
+    let cli = Cli::from_arg_matches(&matches).map_err(clap_error)?;
+    if matches!(cli.command, Commands::Batch(_)) {
+        anyhow::bail!("batch files cannot run batch");
+    }
+    crate::execute(cli.command, client, settings, strict || cli.strict).await
+}
+
+fn write_output(dir: &std::path::Path, number: usize, output: &CommandOutput, format: OutputFormat, raw: bool) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let (ext, contents) = crate::output::to_file(output, format, raw)?;
+    let path = dir.join(format!("line-{number:03}.{ext}"));
+    std::fs::write(&path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_lines_and_skips_comments() {
+        let lines = parse_lines("# nightly\nticker btc-bitcoin\n\ncoinpaprika-cli global -o json\n");
+        let parsed: Vec<(usize, &str)> = lines.iter().map(|l| (l.number, l.text.as_str())).collect();
+        assert_eq!(parsed, [(2, "ticker btc-bitcoin"), (4, "global -o json")]);
+    }
+
+    #[tokio::test]
+    async fn lines_cannot_set_batch_wide_flags() {
+        let client = ApiClient::new(None);
+        for (text, flag) in [("global -o csv", "--output"), ("global --api-key=x", "--api-key"), ("ticker btc-bitcoin --table-style ascii", "--table-style")] {
+            let line = Line { number: 1, text: text.into() };
+            let err = run_line(&line, &client, &Settings::default(), false).await.unwrap_err().to_string();
+            assert!(err.starts_with(&format!("{flag} applies to the whole batch")), "{err}");
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use coinpaprika_cli::types::ChangelogEntry;
+use coinpaprika_cli::{ApiClient, PageParams};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{CommandOutput, OutputData};
+
+#[derive(Subcommand)]
+pub enum ChangelogCommand {
+    /// Rewrite stale coin IDs in a CSV, JSON or TOML file
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli changelog apply portfolio.csv --dry-run\n  coinpaprika-cli changelog apply watchlist.json")]
+    Apply {
+        /// File to update in place
+        file: PathBuf,
+        /// Show the changes without writing the file
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// One rewritten line of the target file.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct LineChange {
+    pub line: usize,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Rename {
+    pub old_id: String,
+    pub new_id: String,
+    pub occurrences: usize,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ApplyReport {
+    pub file: String,
+    pub dry_run: bool,
+    pub written: bool,
+    pub renames: Vec<Rename>,
+    pub changes: Vec<LineChange>,
+}
+
+/// All changelog entries, or a single page when `page` is given.
+pub async fn execute_list(client: &ApiClient, limit: Option<usize>, page: Option<usize>) -> Result<CommandOutput> {
+    let mut entries = match page {
+        Some(page) => client.changelog(&PageParams::new().limit(limit.unwrap_or(50)).page(page)).await?,
+        None => client.changelog_all().await?,
+    };
+    if let Some(limit) = limit {
+        entries.truncate(limit);
+    }
+    Ok(CommandOutput::new(OutputData::Changelog(entries), "/changelog"))
+}
+
+pub async fn execute(client: &ApiClient, cmd: ChangelogCommand) -> Result<CommandOutput> {
+    match cmd {
+        ChangelogCommand::Apply { file, dry_run } => {
+            check_format(&file)?;
+            let text = fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+            let migrations = migrations(&client.changelog_all().await?);
+            let (rewritten, renames, changes) = rewrite(&text, &migrations);
+
+            let written = !dry_run && !changes.is_empty();
+            if written {
+                write_in_place(&file, &rewritten)?;
+            }
+            let report = ApplyReport { file: file.display().to_string(), dry_run, written, renames, changes };
+            Ok(CommandOutput::new(OutputData::ChangelogApply(report), "/changelog"))
+        }
+    }
+}
+
+fn check_format(file: &Path) -> Result<()> {
+    let ext = file.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    if !["csv", "json", "toml"].contains(&ext.as_str()) {
+        bail!(
+            "Unsupported file type: {}\n\nchangelog apply works on .csv, .json and .toml files.",
+            file.display()
+        );
+    }
+    Ok(())
+}
+
+/// Map each retired ID to its current one, following chains of renames
+/// (a → b, later b → c, gives a → c).
+fn migrations(entries: &[ChangelogEntry]) -> HashMap<String, String> {
+    let direct: HashMap<&str, &str> = entries
+        .iter()
+        .filter(|e| e.old_id != e.new_id)
+        .map(|e| (e.old_id.as_str(), e.new_id.as_str()))
+        .collect();
+    direct
+        .keys()
+        .map(|old| {
+            let mut current = direct[old];
+            // Bounded in case the changelog ever contains a cycle.
+            for _ in 0..direct.len() {
+                match direct.get(current) {
+                    Some(next) if next != old => current = next,
+                    _ => break,
+                }
+            }
+            (old.to_string(), current.to_string())
+        })
+        .collect()
+}
+
+/// Replace whole-token occurrences of retired IDs. A token is a run of ID
+/// characters (letters, digits, `-`, `_`), so `btc-bitcoin` inside
+/// `"btc-bitcoin"`, a CSV cell or a bare TOML key matches, but not inside
+/// `xbtc-bitcoin2`. Formatting, comments and ordering are untouched.
+fn rewrite(text: &str, migrations: &HashMap<String, String>) -> (String, Vec<Rename>, Vec<LineChange>) {
+    let mut counts: HashMap<(String, String), usize> = HashMap::new();
+    let mut changes = Vec::new();
+    let mut out = String::with_capacity(text.len());
+
+    for (i, line) in text.split_inclusive('\n').enumerate() {
+        let mut new_line = String::with_capacity(line.len());
+        let mut token = String::new();
+        let mut flush = |token: &mut String, new_line: &mut String| {
+            match migrations.get(token.as_str()) {
+                Some(new_id) => {
+                    *counts.entry((token.clone(), new_id.clone())).or_default() += 1;
+                    new_line.push_str(new_id);
+                }
+                None => new_line.push_str(token),
+            }
+            token.clear();
+        };
+        for c in line.chars() {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                token.push(c);
+            } else {
+                flush(&mut token, &mut new_line);
+                new_line.push(c);
+            }
+        }
+        flush(&mut token, &mut new_line);
+
+        if new_line != line {
+            changes.push(LineChange {
+                line: i + 1,
+                before: line.trim_end_matches(['\r', '\n']).to_string(),
+                after: new_line.trim_end_matches(['\r', '\n']).to_string(),
+            });
+        }
+        out.push_str(&new_line);
+    }
+
+    let mut renames: Vec<Rename> = counts
+        .into_iter()
+        .map(|((old_id, new_id), occurrences)| Rename { old_id, new_id, occurrences })
+        .collect();
+    renames.sort_by(|a, b| a.old_id.cmp(&b.old_id));
+    (out, renames, changes)
+}
+
+/// Write via a temporary file in the same directory so an interrupted run
+/// never leaves a half-written file behind.
+fn write_in_place(file: &Path, contents: &str) -> Result<()> {
+    let name = file.file_name().and_then(|n| n.to_str()).unwrap_or("file");
+    let tmp = file.with_file_name(format!(".{name}.coinpaprika-tmp"));
+    fs::write(&tmp, contents).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    if let Ok(meta) = fs::metadata(file) {
+        let _ = fs::set_permissions(&tmp, meta.permissions());
+    }
+    fs::rename(&tmp, file).with_context(|| format!("Failed to replace {}", file.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(old_id: &str, new_id: &str) -> ChangelogEntry {
+        ChangelogEntry { currency_id: None, old_id: old_id.into(), new_id: new_id.into(), changed_at: None }
+    }
+
+    #[test]
+    fn follows_rename_chains() {
+        let map = migrations(&[entry("b-new", "c-newer"), entry("a-old", "b-new")]);
+        assert_eq!(map["a-old"], "c-newer");
+        assert_eq!(map["b-new"], "c-newer");
+    }
+
+    #[test]
+    fn rewrites_whole_tokens_only() {
+        let map = migrations(&[entry("matic-polygon", "pol-polygon")]);
+        let text = "id,amount\nmatic-polygon,10\nxmatic-polygon,1\n";
+        let (out, renames, changes) = rewrite(text, &map);
+        assert_eq!(out, "id,amount\npol-polygon,10\nxmatic-polygon,1\n");
+        assert_eq!(renames[0].occurrences, 1);
+        assert_eq!(changes[0].line, 2);
+    }
+
+    #[test]
+    fn rewrites_json_and_toml_keys() {
+        let map = migrations(&[entry("old-coin", "new-coin")]);
+        let (json, _, _) = rewrite("{\"old-coin\": [\"old-coin\"]}", &map);
+        assert_eq!(json, "{\"new-coin\": [\"new-coin\"]}");
+        let (toml, _, _) = rewrite("[holdings]\nold-coin = 1.5 # keep\n", &map);
+        assert_eq!(toml, "[holdings]\nnew-coin = 1.5 # keep\n");
+    }
+}
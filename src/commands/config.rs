@@ -1,71 +1,462 @@
-use anyhow::Result;
-use clap::Subcommand;
+use anyhow::{anyhow, bail, Result};
+use clap::{Arg, Command, CommandFactory, Subcommand, ValueEnum};
+use coinpaprika_cli::client::{FREE_BASE_URL, PRO_BASE_URL};
+use coinpaprika_cli::config::{self as cfg, Settings, StoredKey, DEFAULT_PROFILE};
+use coinpaprika_cli::secret::{self, PASSPHRASE_ENV};
+use schemars::JsonSchema;
 use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
+use super::{CommandOutput, OutputData};
 use crate::output::OutputFormat;
 
 #[derive(Subcommand)]
 pub enum ConfigCommand {
     /// Show current configuration
     Show,
-    /// Set CoinPaprika API key
-    #[command(name = "set-key")]
+    /// Set CoinPaprika API key (for the selected profile, if any)
+    #[command(name = "set-key", after_help = "EXAMPLES:\n  coinpaprika-cli config set-key <KEY>\n  coinpaprika-cli config set-key --encrypt\n  coinpaprika-cli config set-key --command 'pass show coinpaprika'")]
     SetKey {
-        /// Your CoinPaprika API key
+        /// Your CoinPaprika API key (asked for if omitted with --encrypt)
+        #[arg(required_unless_present_any = ["encrypt", "command"])]
+        key: Option<String>,
+        /// Store the key encrypted with a passphrase (from COINPAPRIKA_PASSPHRASE or a prompt)
+        #[arg(long, conflicts_with = "command")]
+        encrypt: bool,
+        /// Get the key by running COMMAND instead of storing it, e.g. 'pass show coinpaprika'
+        #[arg(long, value_name = "COMMAND", conflicts_with = "key")]
+        command: Option<String>,
+    },
+    /// Set a default for a flag, for all commands or one (`tickers.limit`)
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli config set quotes EUR\n  coinpaprika-cli config set output json\n  coinpaprika-cli config set tickers.limit 20\n  coinpaprika-cli config set table_style markdown")]
+    Set {
+        /// Flag name, optionally prefixed by a command: quotes, limit, output, table_style, ohlcv.interval
+        key: String,
+        /// Value, checked like the flag's own value
+        value: String,
+    },
+    /// Show the default for a flag
+    Get {
+        /// Flag name, optionally prefixed by a command
         key: String,
     },
-    /// Reset configuration (delete config file)
+    /// Remove a default, restoring the built-in one
+    Unset {
+        /// Flag name, optionally prefixed by a command
+        key: String,
+    },
+    /// Manage named profiles
+    #[command(subcommand)]
+    Profile(ProfileCommand),
+    /// Check the config file for unknown keys and bad values
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli config validate\n  coinpaprika-cli config validate ./team-config.json")]
+    Validate {
+        /// Config file to check (default: the one in use)
+        file: Option<PathBuf>,
+    },
+    /// Reset configuration (delete the config, cache and state directories)
     Reset,
 }
 
-#[derive(Debug, Serialize)]
-struct ConfigInfo {
-    config_file: String,
-    api_key: Option<String>,
-    key_source: String,
-    coinpaprika_url: String,
+#[derive(Subcommand)]
+pub enum ProfileCommand {
+    /// Add a profile, or change settings of an existing one
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli config profile add team --key <KEY> --quotes USD,EUR\n  coinpaprika-cli config profile add local --url http://127.0.0.1:8080/v1 --format json\n  coinpaprika-cli --profile team ticker btc-bitcoin")]
+    Add {
+        /// Profile name
+        name: String,
+        /// API key (omit for a free-tier profile)
+        #[arg(long)]
+        key: Option<String>,
+        /// API base URL
+        #[arg(long, value_name = "URL")]
+        url: Option<String>,
+        /// Default for --quotes, comma-separated
+        #[arg(long)]
+        quotes: Option<String>,
+        /// Default for --output
+        #[arg(long)]
+        format: Option<OutputFormat>,
+    },
+    /// Use a profile by default (`default` for the top-level settings)
+    Use {
+        /// Profile name
+        name: String,
+    },
+    /// List profiles
+    List,
+    /// Remove a profile
+    Remove {
+        /// Profile name
+        name: String,
+    },
 }
 
-pub async fn execute(cmd: ConfigCommand, output: OutputFormat, raw: bool) -> Result<()> {
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ConfigInfo {
+    pub config_file: String,
+    pub profile: Option<String>,
+    pub profile_source: Option<String>,
+    pub api_key: Option<String>,
+    pub key_source: String,
+    pub coinpaprika_url: String,
+    pub url_source: String,
+    pub quotes: Option<String>,
+    pub quotes_source: Option<String>,
+    pub output: Option<String>,
+    pub output_source: Option<String>,
+    pub defaults: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ProfileInfo {
+    pub name: String,
+    /// Whether this profile is the one in effect.
+    pub active: bool,
+    pub api_key: Option<String>,
+    pub base_url: Option<String>,
+    pub quotes: Option<String>,
+    pub output: Option<String>,
+}
+
+pub fn execute(cmd: ConfigCommand, settings: &Settings) -> Result<CommandOutput> {
     match cmd {
         ConfigCommand::Show => {
-            let key_source = crate::config::key_source(None);
-            let api_key = crate::config::resolve_api_key(None);
-            let config_path = crate::config::config_path()?;
-
-            match output {
-                OutputFormat::Table => {
-                    crate::output::config::print_config_show(
-                        &config_path.display().to_string(),
-                        api_key.as_deref(),
-                        key_source,
-                    );
-                }
-                OutputFormat::Json => {
-                    let has_key = api_key.is_some();
-                    let info = ConfigInfo {
-                        config_file: config_path.display().to_string(),
-                        api_key: api_key.map(|k| crate::config::mask_key(&k)),
-                        key_source: key_source.to_string(),
-                        coinpaprika_url: if has_key {
-                            "https://api-pro.coinpaprika.com/v1".to_string()
-                        } else {
-                            "https://api.coinpaprika.com/v1".to_string()
-                        },
-                    };
-                    crate::output::print_json_wrapped(&info, crate::output::ResponseMeta::coinpaprika("/config"), raw)?;
-                }
+            let config_path = cfg::config_path()?;
+            let (coinpaprika_url, url_source) = match &settings.base_url {
+                Some(url) => (url.value.clone(), url.source.to_string()),
+                None if settings.api_key.is_some() => (PRO_BASE_URL.to_string(), "Default (paid tier)".to_string()),
+                None => (FREE_BASE_URL.to_string(), "Default (free tier)".to_string()),
+            };
+            let info = ConfigInfo {
+                config_file: config_path.display().to_string(),
+                profile: settings.profile.as_ref().map(|p| p.value.clone()),
+                profile_source: settings.profile.as_ref().map(|p| p.source.to_string()),
+                api_key: settings
+                    .api_key()
+                    .map(cfg::mask_key)
+                    .or_else(|| settings.locked_key.as_ref().map(|_| "Locked (unlocked when a command needs it)".into())),
+                key_source: settings.key_source(),
+                coinpaprika_url,
+                url_source,
+                quotes: settings.quotes.as_ref().map(|q| q.value.clone()),
+                quotes_source: settings.quotes.as_ref().map(|q| q.source.to_string()),
+                output: settings.output.as_ref().map(|o| o.value.clone()),
+                output_source: settings.output.as_ref().map(|o| o.source.to_string()),
+                defaults: settings.defaults.clone(),
+            };
+            Ok(CommandOutput::new(OutputData::Config(info), "/config"))
+        }
+        ConfigCommand::SetKey { key, encrypt, command } => {
+            let profile = settings.profile_name();
+            let target = profile.map(|p| format!(" (profile '{p}')")).unwrap_or_default();
+            let config_path = cfg::config_path()?;
+            if let Some(command) = command {
+                // Run it once now, so a typo shows up here rather than later.
+                let key = secret::run_key_command(&command)?;
+                cfg::save_key(StoredKey::Command(command), profile)?;
+                return Ok(CommandOutput::message(format!(
+                    "API key command saved to {}{target}\nKey: {}",
+                    config_path.display(),
+                    cfg::mask_key(&key)
+                )));
+            }
+            if encrypt {
+                let key = match key {
+                    Some(key) => key,
+                    None => read_secret("API key: ", "Pass the key as an argument")?,
+                };
+                let passphrase = new_passphrase()?;
+                let path = cfg::save_encrypted_key(&key, &passphrase, profile)?;
+                return Ok(CommandOutput::message(format!(
+                    "API key encrypted to {}{target}\nKey: {}\nIt is unlocked with {PASSPHRASE_ENV} or at a prompt.",
+                    path.display(),
+                    cfg::mask_key(&key)
+                )));
             }
+            let key = key.unwrap_or_default();
+            cfg::save_api_key(&key, profile)?;
+            Ok(CommandOutput::message(format!(
+                "API key saved to {}{target}\nKey: {}",
+                config_path.display(),
+                cfg::mask_key(&key)
+            )))
         }
-        ConfigCommand::SetKey { key } => {
-            crate::config::save_api_key(&key)?;
-            println!("API key saved to {}", crate::config::config_path()?.display());
-            println!("Key: {}", crate::config::mask_key(&key));
+        ConfigCommand::Set { key, value } => {
+            let (key, _) = default_setting(&key, Some(&value))?;
+            let mut config = cfg::load_config()?;
+            config.defaults.insert(key.clone(), value.clone());
+            cfg::save_config(&config)?;
+            Ok(CommandOutput::message(format!("{key} = {value}")))
+        }
+        ConfigCommand::Get { key } => {
+            let (key, built_in) = default_setting(&key, None)?;
+            Ok(CommandOutput::message(match settings.defaults.get(&key) {
+                Some(value) => value.clone(),
+                None => format!("{} (built-in)", built_in.as_deref().unwrap_or("not set")),
+            }))
+        }
+        ConfigCommand::Unset { key } => {
+            let (key, _) = default_setting(&key, None)?;
+            let mut config = cfg::load_config()?;
+            if config.defaults.remove(&key).is_none() {
+                return Ok(CommandOutput::message(format!("{key} was not set.")));
+            }
+            cfg::save_config(&config)?;
+            Ok(CommandOutput::message(format!("Unset {key}.")))
+        }
+        ConfigCommand::Profile(cmd) => execute_profile(cmd, settings),
+        ConfigCommand::Validate { file } => {
+            let path = match file {
+                Some(path) => path,
+                None => cfg::config_path()?,
+            };
+            if !path.exists() {
+                return Ok(CommandOutput::message(format!("No config file at {}; the built-in defaults apply.", path.display())));
+            }
+            let text = std::fs::read_to_string(&path).map_err(|e| anyhow!("Can't read {}: {e}", path.display()))?;
+            let problems = cfg::validate(&text, |key, value| default_setting(key, Some(value)).map(|_| ()));
+            if problems.is_empty() {
+                return Ok(CommandOutput::message(format!("{} is valid.", path.display())));
+            }
+            let lines: Vec<String> =
+                problems.iter().map(|p| format!("  line {}, column {}: {}", p.line, p.column, p.message)).collect();
+            let count = if problems.len() == 1 { "1 problem".to_string() } else { format!("{} problems", problems.len()) };
+            bail!("{} has {count}:\n{}", path.display(), lines.join("\n"))
         }
         ConfigCommand::Reset => {
-            crate::config::delete_config()?;
-            println!("Configuration deleted.");
+            cfg::delete_config()?;
+            Ok(CommandOutput::message("Configuration deleted."))
+        }
+    }
+}
+
+fn execute_profile(cmd: ProfileCommand, settings: &Settings) -> Result<CommandOutput> {
+    let mut config = cfg::load_config()?;
+    match cmd {
+        ProfileCommand::Add { name, key, url, quotes, format } => {
+            if name == DEFAULT_PROFILE || name.is_empty() || name.contains(char::is_whitespace) {
+                bail!("'{name}' can't be used as a profile name. Pick a name without spaces, other than '{DEFAULT_PROFILE}'.");
+            }
+            let existed = config.profiles.contains_key(&name);
+            let profile = config.profiles.entry(name.clone()).or_default();
+            if let Some(key) = key {
+                profile.api_key = Some(key);
+            }
+            if let Some(url) = url {
+                profile.base_url = Some(url.trim_end_matches('/').to_string());
+            }
+            if let Some(quotes) = quotes {
+                profile.quotes = Some(quotes.to_uppercase());
+            }
+            if let Some(format) = format {
+                profile.output = format.to_possible_value().map(|v| v.get_name().to_string());
+            }
+            cfg::save_config(&config)?;
+            let verb = if existed { "Updated" } else { "Added" };
+            Ok(CommandOutput::message(format!(
+                "{verb} profile '{name}'.\nUse it with --profile {name}, or by default with: coinpaprika-cli config profile use {name}"
+            )))
+        }
+        ProfileCommand::Use { name } => {
+            if name == DEFAULT_PROFILE {
+                config.active_profile = None;
+            } else {
+                config.profile(&name)?;
+                config.active_profile = Some(name.clone());
+            }
+            cfg::save_config(&config)?;
+            Ok(CommandOutput::message(format!("Now using profile '{name}'.")))
+        }
+        ProfileCommand::List => {
+            let profiles = config
+                .profiles
+                .into_iter()
+                .map(|(name, p)| ProfileInfo {
+                    active: settings.profile_name() == Some(name.as_str()),
+                    name,
+                    api_key: p.api_key.as_deref().map(cfg::mask_key),
+                    base_url: p.base_url,
+                    quotes: p.quotes,
+                    output: p.output,
+                })
+                .collect();
+            Ok(CommandOutput::new(OutputData::Profiles(profiles), "/config"))
+        }
+        ProfileCommand::Remove { name } => {
+            config.profile(&name)?;
+            config.profiles.remove(&name);
+            if config.active_profile.as_deref() == Some(name.as_str()) {
+                config.active_profile = None;
+            }
+            cfg::save_config(&config)?;
+            Ok(CommandOutput::message(format!("Removed profile '{name}'.")))
         }
     }
-    Ok(())
+}
+
+/// Ask for the passphrase of an encrypted key file.
+pub fn ask_passphrase() -> Result<String> {
+    read_secret("Passphrase for the API key: ", &format!("Set {PASSPHRASE_ENV} to unlock the API key"))
+}
+
+fn new_passphrase() -> Result<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        if !passphrase.is_empty() {
+            return Ok(passphrase);
+        }
+    }
+    let hint = format!("Set {PASSPHRASE_ENV} to the passphrase to encrypt with");
+    let passphrase = read_secret("New passphrase: ", &hint)?;
+    if passphrase.is_empty() {
+        bail!("The passphrase can't be empty.");
+    }
+    if read_secret("Repeat passphrase: ", &hint)? != passphrase {
+        bail!("The passphrases don't match.");
+    }
+    Ok(passphrase)
+}
+
+/// Read a line from the terminal without echoing it. `hint` says what to do
+/// instead when there's no terminal.
+fn read_secret(prompt: &str, hint: &str) -> Result<String> {
+    use std::io::IsTerminal;
+    if !std::io::stdin().is_terminal() {
+        bail!("No terminal to ask on. {hint}.");
+    }
+    eprint!("{prompt}");
+    let echo = EchoOff::new();
+    let mut line = String::new();
+    let read = std::io::stdin().read_line(&mut line);
+    drop(echo);
+    eprintln!();
+    read?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Turns terminal echo off until dropped.
+#[cfg(unix)]
+struct EchoOff(Option<libc::termios>);
+
+#[cfg(unix)]
+impl EchoOff {
+    fn new() -> Self {
+        // SAFETY: termios is plain data, and both calls only read or write
+        // the struct we pass for stdin's file descriptor.
+        unsafe {
+            let mut term: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut term) != 0 {
+                return Self(None);
+            }
+            let saved = term;
+            term.c_lflag &= !libc::ECHO;
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &term);
+            Self(Some(saved))
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        if let Some(saved) = &self.0 {
+            // SAFETY: restores the settings read in `new`.
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, saved);
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+struct EchoOff;
+
+#[cfg(not(unix))]
+impl EchoOff {
+    fn new() -> Self {
+        EchoOff
+    }
+}
+
+/// Global flags that make sense as defaults; `--api-key` and the like don't.
+const GLOBAL_DEFAULTS: &[&str] = &["output", "table_style"];
+
+/// Check a `defaults` key, and the value if given, against the CLI
+/// definition. Returns the key as stored (`tickers.limit`, `table_style`)
+/// and the flag's built-in default.
+pub fn default_setting(key: &str, value: Option<&str>) -> Result<(String, Option<String>)> {
+    let mut root = crate::Cli::command();
+    root.build();
+
+    let (path, flag) = key.rsplit_once('.').map_or((None, key), |(path, flag)| (Some(path), flag));
+    let id = flag.trim_start_matches("--").replace('-', "_");
+    let candidates: Vec<&Arg> = match path {
+        None if GLOBAL_DEFAULTS.contains(&id.as_str()) => root.get_arguments().filter(|a| a.get_id() == id.as_str()).collect(),
+        None => all_commands(&root)
+            .into_iter()
+            .flat_map(|c| c.get_arguments().filter(|a| a.get_id() == id.as_str() && settable(a)))
+            .collect(),
+        Some(path) => {
+            let mut command = &root;
+            for name in path.split('.') {
+                command = command.find_subcommand(name).ok_or_else(|| anyhow!("Unknown command '{name}' in '{key}'."))?;
+            }
+            command.get_arguments().filter(|a| a.get_id() == id.as_str() && settable(a)).collect()
+        }
+    };
+    let Some(first) = candidates.first() else {
+        bail!(
+            "'{key}' is not a setting. Use a flag name such as quotes, limit, output or table_style, \
+             optionally for one command: tickers.limit, ohlcv.interval."
+        );
+    };
+
+    if let Some(value) = value {
+        for arg in &candidates {
+            // The flag on its own, so its value parser runs and nothing else.
+            let long = arg.get_long().unwrap_or_default();
+            Command::new("check")
+                .arg((*arg).clone().global(false))
+                .try_get_matches_from(["check".to_string(), format!("--{long}={value}")])
+                .map_err(|e| {
+                    let message = e.to_string();
+                    let first = message.lines().next().unwrap_or_default().trim_start_matches("error: ").to_string();
+                    anyhow!("Invalid value for {key}: {first}")
+                })?;
+        }
+    }
+    let stored = path.map_or_else(|| id.clone(), |path| format!("{path}.{id}"));
+    let built_in = first.get_default_values().first().map(|v| v.to_string_lossy().into_owned());
+    Ok((stored, built_in))
+}
+
+fn all_commands(command: &Command) -> Vec<&Command> {
+    let mut commands = vec![command];
+    for sub in command.get_subcommands() {
+        commands.extend(all_commands(sub));
+    }
+    commands
+}
+
+/// Options that take a value and may be left out.
+fn settable(arg: &Arg) -> bool {
+    arg.get_long().is_some() && arg.get_action().takes_values() && !arg.is_required_set() && !arg.is_global_set()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_checked_against_flags() {
+        assert_eq!(default_setting("tickers.limit", Some("20")).unwrap(), ("tickers.limit".into(), Some("50".into())));
+        assert_eq!(default_setting("table-style", Some("markdown")).unwrap().0, "table_style");
+        assert!(default_setting("quotes", Some("EUR")).is_ok());
+        assert!(default_setting("tickers.limit", Some("lots")).is_err());
+        assert!(default_setting("output", Some("xml")).is_err());
+        assert!(default_setting("api_key", Some("x")).is_err());
+        assert!(default_setting("nope.limit", Some("1")).is_err());
+        assert!(default_setting("ohlcv.start", Some("2024-01-01")).is_err());
+    }
 }
@@ -1,125 +1,37 @@
 use anyhow::Result;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use coinpaprika_cli::{ApiClient, HistoricalParams};
 
-use crate::client::ApiClient;
-use crate::output::OutputFormat;
+use super::{multi, CommandOutput, OutputData};
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct Contract {
-    pub address: Option<String>,
-    #[serde(rename = "type")]
-    pub contract_type: Option<String>,
-    pub id: Option<String>,
-    pub active: Option<bool>,
+pub async fn execute_platforms(client: &ApiClient) -> Result<CommandOutput> {
+    let platforms = client.platforms().await?;
+    Ok(CommandOutput::new(OutputData::Platforms(platforms), "/contracts"))
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct ContractTicker {
-    pub id: Option<String>,
-    pub name: Option<String>,
-    pub symbol: Option<String>,
-    pub rank: Option<i64>,
-    pub circulating_supply: Option<f64>,
-    pub total_supply: Option<f64>,
-    pub max_supply: Option<f64>,
-    pub beta_value: Option<f64>,
-    pub first_data_at: Option<String>,
-    pub last_updated: Option<String>,
-    pub quotes: Option<HashMap<String, ContractQuote>>,
+pub async fn execute_contracts(client: &ApiClient, platform_id: &str, limit: usize) -> Result<CommandOutput> {
+    let contracts = client.contracts(platform_id).await?;
+    let contracts = contracts.into_iter().take(limit).collect();
+    Ok(CommandOutput::new(OutputData::Contracts(contracts), &format!("/contracts/{platform_id}")))
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct ContractQuote {
-    pub price: Option<f64>,
-    pub volume_24h: Option<f64>,
-    pub volume_24h_change_24h: Option<f64>,
-    pub market_cap: Option<f64>,
-    pub market_cap_change_24h: Option<f64>,
-    pub percent_change_24h: Option<f64>,
-    pub percent_change_7d: Option<f64>,
-    pub percent_change_30d: Option<f64>,
-    pub percent_change_1y: Option<f64>,
-    pub ath_price: Option<f64>,
-    pub ath_date: Option<String>,
-    pub percent_from_price_ath: Option<f64>,
+pub async fn execute_ticker(client: &ApiClient, platform_id: &str, address: &str) -> Result<CommandOutput> {
+    let ticker = client.contract_ticker(platform_id, address).await?;
+    Ok(CommandOutput::new(OutputData::ContractTicker(Box::new(ticker)), &format!("/contracts/{platform_id}/{address}")))
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct ContractHistoryPoint {
-    pub timestamp: Option<String>,
-    pub price: Option<f64>,
-    pub volume_24h: Option<f64>,
-    pub market_cap: Option<f64>,
-}
-
-pub async fn execute_platforms(client: &ApiClient, output: OutputFormat, raw: bool) -> Result<()> {
-    let platforms: Vec<String> = client.coinpaprika_get("/contracts", &[]).await?;
-    match output {
-        OutputFormat::Table => crate::output::contracts::print_platforms(&platforms),
-        OutputFormat::Json => {
-            crate::output::print_json_wrapped(&platforms, crate::output::ResponseMeta::coinpaprika("/contracts"), raw)?;
-        }
-    }
-    Ok(())
-}
-
-pub async fn execute_contracts(client: &ApiClient, platform_id: &str, limit: usize, output: OutputFormat, raw: bool) -> Result<()> {
-    let contracts: Vec<Contract> = client.coinpaprika_get(&format!("/contracts/{platform_id}"), &[]).await?;
-    let contracts: Vec<Contract> = contracts.into_iter().take(limit).collect();
-    match output {
-        OutputFormat::Table => crate::output::contracts::print_contracts_table(&contracts),
-        OutputFormat::Json => {
-            crate::output::print_json_wrapped(&contracts, crate::output::ResponseMeta::coinpaprika(&format!("/contracts/{platform_id}")), raw)?;
-        }
-    }
-    Ok(())
-}
-
-pub async fn execute_ticker(client: &ApiClient, platform_id: &str, address: &str, output: OutputFormat, raw: bool) -> Result<()> {
-    let ticker: ContractTicker = client.coinpaprika_get(
-        &format!("/contracts/{platform_id}/{address}"),
-        &[],
-    ).await?;
-    match output {
-        OutputFormat::Table => crate::output::contracts::print_contract_ticker(&ticker),
-        OutputFormat::Json => {
-            crate::output::print_json_wrapped(&ticker, crate::output::ResponseMeta::coinpaprika(&format!("/contracts/{platform_id}/{address}")), raw)?;
-        }
-    }
-    Ok(())
+pub async fn execute_ticker_many(client: &ApiClient, platform_id: &str, addresses: &[String], parallel: u16) -> Result<CommandOutput> {
+    let (items, errors) = multi::fetch_all(addresses, parallel, |address| client.contract_ticker(platform_id, address)).await;
+    let tickers: Vec<_> = items.into_iter().map(|(_, ticker)| ticker).collect();
+    let succeeded = tickers.len();
+    multi::output(OutputData::ContractTickers(tickers), succeeded, errors, &format!("/contracts/{platform_id}"))
 }
 
 pub async fn execute_history(
     client: &ApiClient,
     platform_id: &str,
     address: &str,
-    start: &str,
-    end: Option<&str>,
-    interval: &str,
-    limit: usize,
-    output: OutputFormat,
-    raw: bool,
-) -> Result<()> {
-    let limit_str = limit.to_string();
-    let mut params: Vec<(&str, &str)> = vec![
-        ("start", start),
-        ("interval", interval),
-        ("limit", &limit_str),
-    ];
-    if let Some(e) = end {
-        params.push(("end", e));
-    }
-
-    let data: Vec<ContractHistoryPoint> = client.coinpaprika_get(
-        &format!("/contracts/{platform_id}/{address}/historical"),
-        &params,
-    ).await?;
-    match output {
-        OutputFormat::Table => crate::output::contracts::print_contract_history(&data),
-        OutputFormat::Json => {
-            crate::output::print_json_wrapped(&data, crate::output::ResponseMeta::coinpaprika(&format!("/contracts/{platform_id}/{address}")), raw)?;
-        }
-    }
-    Ok(())
+    params: &HistoricalParams,
+) -> Result<CommandOutput> {
+    let data = client.contract_history(platform_id, address, params).await?;
+    Ok(CommandOutput::new(OutputData::ContractHistory(data), &format!("/contracts/{platform_id}/{address}")).with_range(params))
 }
@@ -0,0 +1,360 @@
+use anyhow::Result;
+use chrono::Duration;
+use coinpaprika_cli::client::{Probe, FREE_BASE_URL, PRO_BASE_URL};
+use coinpaprika_cli::config::{self as cfg, Settings};
+use coinpaprika_cli::plans::{self, Need};
+use coinpaprika_cli::{usage, ApiClient};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fs;
+
+use super::{AlreadyReported, CommandOutput, OutputData};
+
+/// Slower than this and a host gets a warning.
+const SLOW_MS: u128 = 1500;
+/// Clock skew tolerated before a warning, and before a failure.
+const SKEW_WARN_SECS: i64 = 30;
+const SKEW_FAIL_SECS: i64 = 300;
+/// How far back "recently used endpoints" goes.
+const RECENT_DAYS: i64 = 30;
+const PROXY_VARS: &[&str] =
+    &["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy", "NO_PROXY", "no_proxy"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to do about a warning or failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DoctorReport {
+    pub passed: usize,
+    pub warnings: usize,
+    pub failed: usize,
+    pub checks: Vec<Check>,
+}
+
+fn check(name: &str, status: CheckStatus, detail: impl Into<String>, fix: Option<String>) -> Check {
+    Check { name: name.into(), status, detail: detail.into(), fix }
+}
+
+fn pass(name: &str, detail: impl Into<String>) -> Check {
+    check(name, CheckStatus::Pass, detail, None)
+}
+
+pub async fn execute(settings: &Settings) -> CommandOutput {
+    let mut checks = vec![config_permissions()];
+
+    let (key_check, plan, key) = api_key(settings).await;
+    checks.push(key_check);
+    checks.push(plan_usage(plan.as_deref(), key.as_deref()));
+
+    let mut hosts = vec![("Free API", FREE_BASE_URL.to_string(), None), ("Pro API", PRO_BASE_URL.to_string(), key)];
+    if let Some(url) = settings.base_url.as_ref().filter(|u| u.value != FREE_BASE_URL && u.value != PRO_BASE_URL) {
+        hosts.push(("Configured API", url.value.clone(), settings.api_key().map(str::to_string)));
+    }
+    let probes = futures::future::join_all(hosts.iter().map(|(_, url, key)| async move {
+        ApiClient::new(key.clone()).with_base_url(url).probe("/global").await
+    }))
+    .await;
+    for ((name, url, _), probe) in hosts.iter().zip(&probes) {
+        checks.push(reachability(name, url, probe));
+    }
+    checks.push(tls(&probes[0]));
+    checks.push(proxies());
+    checks.push(clock_skew(&probes));
+    checks.push(cache_health());
+
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    let report = DoctorReport {
+        passed: count(CheckStatus::Pass),
+        warnings: count(CheckStatus::Warn),
+        failed: count(CheckStatus::Fail),
+        checks,
+    };
+    CommandOutput::new(OutputData::Doctor(report), "/doctor")
+}
+
+/// Fail the process, after the report is printed, if any check failed.
+pub fn exit_status(output: &CommandOutput) -> Result<()> {
+    match &output.data {
+        OutputData::Doctor(report) if report.failed > 0 => {
+            let checks = if report.failed == 1 { "check" } else { "checks" };
+            Err(AlreadyReported(format!("{} {checks} failed", report.failed)).into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The config directory should be 0700 and the config and key files 0600,
+/// as `save_config` leaves them.
+fn config_permissions() -> Check {
+    const NAME: &str = "Config permissions";
+    let Ok(dir) = cfg::config_dir() else {
+        return check(NAME, CheckStatus::Fail, "Could not determine the home directory", Some("Set HOME".into()));
+    };
+    let files: Vec<_> = fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            name == "config.json" || (name.starts_with("key") && name.ends_with(".enc"))
+        })
+        .collect();
+    if files.is_empty() {
+        return pass(NAME, format!("No config file yet ({})", dir.display()));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = |path: &std::path::Path| fs::metadata(path).map(|m| m.permissions().mode() & 0o777).unwrap_or(0);
+        let mut problems = Vec::new();
+        let mut fixes = Vec::new();
+        if mode(&dir) & 0o077 != 0 {
+            problems.push(format!("{} is {:o}", dir.display(), mode(&dir)));
+            fixes.push(format!("chmod 700 {}", dir.display()));
+        }
+        for file in files {
+            if mode(&file) & 0o077 != 0 {
+                problems.push(format!("{} is {:o}", file.display(), mode(&file)));
+                fixes.push(format!("chmod 600 {}", file.display()));
+            }
+        }
+        if !problems.is_empty() {
+            return check(
+                NAME,
+                CheckStatus::Warn,
+                format!("Readable by other users: {}", problems.join(", ")),
+                Some(fixes.join(" && ")),
+            );
+        }
+        pass(NAME, format!("{} is private", dir.display()))
+    }
+    #[cfg(not(unix))]
+    pass(NAME, format!("Not checked on this platform ({})", dir.display()))
+}
+
+/// Whether a key is set and accepted. Also returns the plan and the key.
+async fn api_key(settings: &Settings) -> (Check, Option<String>, Option<String>) {
+    const NAME: &str = "API key";
+    let mut settings = settings.clone();
+    let source = settings.key_source();
+    if let Err(e) = settings.unlock(super::config::ask_passphrase) {
+        let fix = "Check api_key_command or COINPAPRIKA_PASSPHRASE: coinpaprika-cli config show".to_string();
+        return (check(NAME, CheckStatus::Fail, format!("Could not unlock the key from {source}: {e}"), Some(fix)), None, None);
+    }
+    let Some(key) = settings.api_key().map(str::to_string) else {
+        return (pass(NAME, "Not set; using the free tier"), None, None);
+    };
+    let mut client = ApiClient::new(Some(key.clone())).with_retries(0);
+    if let Some(url) = &settings.base_url {
+        client = client.with_base_url(&url.value);
+    }
+    let source = settings.key_source();
+    match client.key_info().await {
+        Ok(info) => {
+            let plan = info.plan.unwrap_or_else(|| "unknown".into());
+            (pass(NAME, format!("{} accepted, plan: {plan}. From: {source}", cfg::mask_key(&key))), Some(plan), Some(key))
+        }
+        Err(e) if e.to_string().starts_with("Invalid API key") => {
+            let fix = "Set a valid key: coinpaprika-cli config set-key <KEY>".to_string();
+            (check(NAME, CheckStatus::Fail, format!("{} was rejected. From: {source}", cfg::mask_key(&key)), Some(fix)), None, Some(key))
+        }
+        Err(e) => {
+            let detail = format!("Could not check {}: {}", cfg::mask_key(&key), first_line(&e.to_string()));
+            (check(NAME, CheckStatus::Warn, detail, Some("Retry once the API is reachable".into())), None, Some(key))
+        }
+    }
+}
+
+/// Endpoints this key used recently that its plan doesn't include.
+fn plan_usage(plan: Option<&str>, key: Option<&str>) -> Check {
+    const NAME: &str = "Plan vs usage";
+    let recent = usage::recent(Duration::days(RECENT_DAYS), &usage::key_label(key));
+    if recent.is_empty() {
+        return pass(NAME, format!("No API calls with this key in the last {RECENT_DAYS} days"));
+    }
+    let plan = match (plan, key) {
+        (_, None) => Some(&plans::FREE),
+        (Some(name), Some(_)) => plans::find(name),
+        (None, Some(_)) => None,
+    };
+    let Some(plan) = plan else {
+        return pass(NAME, format!("{} endpoints used in the last {RECENT_DAYS} days; the plan is unknown", recent.len()));
+    };
+    let outside: Vec<&str> = recent
+        .iter()
+        .filter(|(endpoint, _)| Need::for_endpoint(endpoint).is_some_and(|need| plan.check(need).is_err()))
+        .map(|(endpoint, _)| endpoint.as_str())
+        .collect();
+    if outside.is_empty() {
+        return pass(NAME, format!("{} endpoints used in the last {RECENT_DAYS} days, all within the {} plan", recent.len(), plan.name));
+    }
+    let fix = match key {
+        Some(_) => format!("Upgrade at {}", plans::PRICING_URL),
+        None => format!("Get a key at {}, then: coinpaprika-cli config set-key <KEY>", plans::PRICING_URL),
+    };
+    check(NAME, CheckStatus::Fail, format!("Not included in the {} plan: {}", plan.name, outside.join(", ")), Some(fix))
+}
+
+fn reachability(name: &str, url: &str, probe: &Result<Probe>) -> Check {
+    match probe {
+        Ok(p) if p.status >= 500 => check(
+            name,
+            CheckStatus::Warn,
+            format!("{url} answered HTTP {} in {} ms", p.status, p.elapsed.as_millis()),
+            Some("The API is having trouble; try again shortly".into()),
+        ),
+        Ok(p) if p.elapsed.as_millis() > SLOW_MS => check(
+            name,
+            CheckStatus::Warn,
+            format!("{url} is slow: {} ms", p.elapsed.as_millis()),
+            Some("Check your connection or proxy".into()),
+        ),
+        Ok(p) => pass(name, format!("{url} answered HTTP {} in {} ms", p.status, p.elapsed.as_millis())),
+        Err(e) => check(
+            name,
+            CheckStatus::Fail,
+            format!("{url} is unreachable: {}", first_line(&format!("{e:#}"))),
+            Some("Check your network, firewall and proxy variables".into()),
+        ),
+    }
+}
+
+/// Certificate errors show up as a failed request to the free host.
+fn tls(free: &Result<Probe>) -> Check {
+    const NAME: &str = "TLS";
+    match free {
+        Ok(_) => pass(NAME, "Certificate for api.coinpaprika.com verified"),
+        Err(e) => {
+            let error = format!("{e:#}");
+            let lower = error.to_lowercase();
+            if lower.contains("certificate") || lower.contains("tls") || lower.contains("handshake") {
+                check(
+                    NAME,
+                    CheckStatus::Fail,
+                    format!("Handshake failed: {}", first_line(&error)),
+                    Some("A proxy may be intercepting HTTPS, or the clock may be wrong".into()),
+                )
+            } else {
+                check(NAME, CheckStatus::Warn, "Not checked: the free API is unreachable", None)
+            }
+        }
+    }
+}
+
+/// Proxy variables apply to every request; list them with credentials hidden.
+fn proxies() -> Check {
+    const NAME: &str = "Proxy";
+    let set: Vec<(&str, String)> =
+        PROXY_VARS.iter().filter_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()).map(|v| (*var, v))).collect();
+    if set.is_empty() {
+        return pass(NAME, "No proxy variables set");
+    }
+    let shown: Vec<String> = set.iter().map(|(var, value)| format!("{var}={}", hide_credentials(value))).collect();
+    let bad: Vec<&str> = set
+        .iter()
+        .filter(|(var, value)| {
+            !var.eq_ignore_ascii_case("NO_PROXY")
+                && value.split_once("://").is_some_and(|(scheme, _)| !matches!(scheme, "http" | "https" | "socks5" | "socks5h"))
+        })
+        .map(|(var, _)| *var)
+        .collect();
+    if bad.is_empty() {
+        pass(NAME, format!("Requests use {}", shown.join(", ")))
+    } else {
+        check(
+            NAME,
+            CheckStatus::Warn,
+            format!("Unsupported proxy scheme in {}: {}", bad.join(", "), shown.join(", ")),
+            Some("Use an http://, https:// or socks5:// proxy URL".into()),
+        )
+    }
+}
+
+fn hide_credentials(url: &str) -> String {
+    match (url.split_once("://"), url.rsplit_once('@')) {
+        (Some((scheme, _)), Some((_, host))) => format!("{scheme}://***@{host}"),
+        _ => url.to_string(),
+    }
+}
+
+/// Compare the local clock with the `Date` header of the first host that answered.
+fn clock_skew(probes: &[Result<Probe>]) -> Check {
+    const NAME: &str = "Clock";
+    let Some(skew) = probes.iter().flatten().find_map(|p| p.clock_skew) else {
+        return check(NAME, CheckStatus::Warn, "Not checked: no API response with a Date header", None);
+    };
+    let secs = skew.num_seconds();
+    let direction = if secs >= 0 { "ahead of" } else { "behind" };
+    let detail = format!("Local clock is {}s {direction} the API", secs.abs());
+    let fix = Some("Sync the clock, e.g. timedatectl set-ntp true".to_string());
+    match secs.abs() {
+        s if s > SKEW_FAIL_SECS => check(NAME, CheckStatus::Fail, detail, fix),
+        s if s > SKEW_WARN_SECS => check(NAME, CheckStatus::Warn, detail, fix),
+        _ => pass(NAME, detail),
+    }
+}
+
+/// The cache directory should be writable and hold readable entries.
+fn cache_health() -> Check {
+    const NAME: &str = "Cache";
+    let Ok(dir) = cfg::cache_dir() else {
+        return check(NAME, CheckStatus::Fail, "Could not determine the home directory", Some("Set HOME".into()));
+    };
+    if !dir.exists() {
+        return pass(NAME, format!("Empty; created on first use ({})", dir.display()));
+    }
+    let probe = dir.join(".doctor");
+    if fs::write(&probe, b"").and_then(|_| fs::remove_file(&probe)).is_err() {
+        return check(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} is not writable", dir.display()),
+            Some(format!("chmod u+w {0}, or remove {0}", dir.display())),
+        );
+    }
+    let entries: Vec<_> = fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    let size: u64 = entries.iter().filter_map(|p| fs::metadata(p).ok()).map(|m| m.len()).sum();
+    let corrupt: Vec<String> = entries
+        .iter()
+        .filter(|p| {
+            let entry = fs::read_to_string(p).ok().and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok());
+            entry.is_none_or(|e| e.get("fetched_at").is_none())
+        })
+        .map(|p| p.display().to_string())
+        .collect();
+    if !corrupt.is_empty() {
+        return check(
+            NAME,
+            CheckStatus::Warn,
+            format!("Unreadable entries: {}", corrupt.join(", ")),
+            Some(format!("rm {} (they are fetched again)", corrupt.join(" "))),
+        );
+    }
+    pass(NAME, format!("{} entries, {} KB in {}", entries.len(), size.div_ceil(1024), dir.display()))
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
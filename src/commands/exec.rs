@@ -0,0 +1,253 @@
+//! `exec`: a JSON request interface, so agents don't have to build argv
+//! strings. `{"command": "ohlcv", "args": {"coin_id": "btc-bitcoin"}}` is
+//! turned into the same arguments clap would see and validated by clap.
+
+use anyhow::{Context, Result};
+use clap::{Args, CommandFactory};
+use coinpaprika_cli::config::Settings;
+use coinpaprika_cli::ApiClient;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::io::Read;
+
+use super::{AlreadyReported, OutputData};
+use crate::output::ResponseMeta;
+use crate::Cli;
+
+#[derive(Args)]
+pub struct ExecArgs {
+    /// Request JSON: an object or an array of objects (default: read stdin)
+    #[arg(value_name = "JSON|-")]
+    request: Option<String>,
+}
+
+/// One request: a command name (`"ticker"`, or `"changelog apply"` for a
+/// subcommand) and its arguments by name.
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExecRequest {
+    pub command: String,
+    #[serde(default)]
+    pub args: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ExecResponse {
+    pub data: Option<Value>,
+    pub _meta: Option<ResponseMeta>,
+    pub error: Option<ExecError>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ExecError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// The input is not JSON.
+    InvalidJson,
+    /// The JSON is not a request object or an array of them.
+    InvalidRequest,
+    UnknownCommand,
+    /// An argument is unknown, missing or has an invalid value.
+    InvalidArgs,
+    /// The command ran and failed, e.g. an API error.
+    CommandFailed,
+}
+
+impl ExecError {
+    fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into() }
+    }
+}
+
+/// Commands that make no sense behind a JSON request.
+const NOT_ALLOWED: &[&str] = &["shell", "onboard", "mock-server", "batch", "exec", "script", "completions", "__complete"];
+
+pub async fn run(args: ExecArgs, client: &ApiClient, settings: &Settings, strict: bool) -> Result<()> {
+    let input = match args.request.as_deref() {
+        Some(json) if json != "-" => json.to_string(),
+        _ => {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text).context("Failed to read the request from stdin")?;
+            text
+        }
+    };
+
+    let (responses, is_array) = match serde_json::from_str::<Value>(&input) {
+        Err(e) => (vec![failure(ExecError::new(ErrorKind::InvalidJson, e.to_string()))], false),
+        Ok(Value::Array(requests)) => {
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in requests {
+                responses.push(respond(request, client, settings, strict).await);
+            }
+            (responses, true)
+        }
+        Ok(request) => (vec![respond(request, client, settings, strict).await], false),
+    };
+
+    let failed = responses.iter().filter(|r| r.error.is_some()).count();
+    if is_array {
+        println!("{}", serde_json::to_string_pretty(&responses)?);
+    } else {
+        println!("{}", serde_json::to_string_pretty(&responses[0])?);
+    }
+    if failed > 0 {
+        return Err(AlreadyReported(format!("{failed} of {} requests failed", responses.len())).into());
+    }
+    Ok(())
+}
+
+async fn respond(request: Value, client: &ApiClient, settings: &Settings, strict: bool) -> ExecResponse {
+    let request: ExecRequest = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(e) => {
+            let message = format!("Expected {{\"command\": \"...\", \"args\": {{...}}}}: {e}");
+            return failure(ExecError::new(ErrorKind::InvalidRequest, message));
+        }
+    };
+    let argv = match to_argv(&request) {
+        Ok(argv) => argv,
+        Err(e) => return failure(e),
+    };
+    let cli = match crate::try_parse(&argv, settings) {
+        Ok(cli) => cli,
+        Err(e) => return failure(ExecError::new(ErrorKind::InvalidArgs, e.to_string().trim_end())),
+    };
+    match crate::execute(cli.command, client, settings, strict || cli.strict).await {
+        Ok(output) => {
+            let data = match output.data {
+                OutputData::Message(text) => Value::String(text),
+                data => serde_json::to_value(&data).unwrap_or(Value::Null),
+            };
+            ExecResponse { data: Some(data), _meta: Some(output.meta), error: None }
+        }
+        Err(e) => failure(ExecError::new(ErrorKind::CommandFailed, e.to_string())),
+    }
+}
+
+fn failure(error: ExecError) -> ExecResponse {
+    ExecResponse { data: None, _meta: None, error: Some(error) }
+}
+
+/// Build argv from a request using clap's own definitions. Keys may be the
+/// field name (`coin_id`), the long flag (`ids-from`) or the value name.
+/// `true` sets a flag, arrays repeat a value and `null` is skipped.
+fn to_argv(request: &ExecRequest) -> Result<Vec<String>, ExecError> {
+    let mut root = Cli::command();
+    root.build();
+
+    let mut argv = vec![root.get_name().to_string()];
+    let mut command = &root;
+    for name in request.command.split_whitespace() {
+        command = command.find_subcommand(name).ok_or_else(|| {
+            let known: Vec<&str> = command.get_subcommands().map(|c| c.get_name()).collect();
+            ExecError::new(ErrorKind::UnknownCommand, format!("Unknown command '{}'. Commands: {}", request.command, known.join(", ")))
+        })?;
+        argv.push(command.get_name().to_string());
+    }
+    if argv.len() == 1 || NOT_ALLOWED.contains(&argv[1].as_str()) {
+        return Err(ExecError::new(ErrorKind::UnknownCommand, format!("'{}' cannot be run through exec", request.command)));
+    }
+
+    let mut positionals: Vec<(usize, Vec<String>)> = Vec::new();
+    for (key, value) in &request.args {
+        let wanted = normalize(key);
+        let arg = command
+            .get_arguments()
+            .find(|a| {
+                normalize(a.get_id().as_str()) == wanted
+                    || a.get_long().is_some_and(|l| normalize(l) == wanted)
+                    || a.get_value_names().is_some_and(|names| names.iter().any(|n| normalize(n) == wanted))
+            })
+            .filter(|a| !a.is_global_set() || a.get_id() == "strict")
+            .ok_or_else(|| {
+                let known: Vec<String> = command
+                    .get_arguments()
+                    .filter(|a| !a.is_global_set() && a.get_id() != "help")
+                    .map(|a| a.get_id().to_string())
+                    .collect();
+                ExecError::new(ErrorKind::InvalidArgs, format!("Unknown argument '{key}' for {}. Arguments: {}", request.command, known.join(", ")))
+            })?;
+
+        let values: Vec<String> = match value {
+            Value::Null => continue,
+            Value::Array(items) => items.iter().map(scalar).collect::<Result<_, _>>()?,
+            other => vec![scalar(other)?],
+        };
+        match (arg.get_index(), arg.get_long()) {
+            (Some(index), _) => positionals.push((index, values)),
+            (None, Some(long)) if !arg.get_action().takes_values() => {
+                if *value != Value::Bool(true) {
+                    return Err(ExecError::new(ErrorKind::InvalidArgs, format!("'{key}' is a flag: pass true, or leave it out")));
+                }
+                argv.push(format!("--{long}"));
+            }
+            (None, Some(long)) => {
+                for v in values {
+                    argv.push(format!("--{long}={v}"));
+                }
+            }
+            (None, None) => {
+                return Err(ExecError::new(ErrorKind::InvalidArgs, format!("'{key}' cannot be set through exec")));
+            }
+        }
+    }
+
+    // After `--` so values such as negative numbers aren't taken for flags.
+    positionals.sort_by_key(|(index, _)| *index);
+    if !positionals.is_empty() {
+        argv.push("--".into());
+        argv.extend(positionals.into_iter().flat_map(|(_, values)| values));
+    }
+    Ok(argv)
+}
+
+fn normalize(name: &str) -> String {
+    name.to_lowercase().replace('-', "_")
+}
+
+fn scalar(value: &Value) -> Result<String, ExecError> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        other => Err(ExecError::new(ErrorKind::InvalidArgs, format!("Expected a string, number or boolean, got {other}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn argv(json: &str) -> Result<Vec<String>, ExecError> {
+        to_argv(&serde_json::from_str(json).unwrap())
+    }
+
+    #[test]
+    fn builds_argv_from_clap_definitions() {
+        let args = argv(r#"{"command": "ohlcv", "args": {"coin_id": "btc-bitcoin", "start": "2024-01-01", "limit": 5}}"#).unwrap();
+        assert_eq!(args, ["coinpaprika-cli", "ohlcv", "--start=2024-01-01", "--limit=5", "--", "btc-bitcoin"]);
+        assert!(Cli::try_parse_from(&args).is_ok());
+
+        let args = argv(r#"{"command": "ticker", "args": {"coin_id": ["btc-bitcoin", "eth-ethereum"], "strict": true}}"#).unwrap();
+        assert_eq!(args, ["coinpaprika-cli", "ticker", "--strict", "--", "btc-bitcoin", "eth-ethereum"]);
+    }
+
+    #[test]
+    fn rejects_unknown_commands_and_arguments() {
+        assert_eq!(argv(r#"{"command": "nope"}"#).unwrap_err().kind, ErrorKind::UnknownCommand);
+        assert_eq!(argv(r#"{"command": "shell"}"#).unwrap_err().kind, ErrorKind::UnknownCommand);
+        assert_eq!(argv(r#"{"command": "ticker", "args": {"colour": "red"}}"#).unwrap_err().kind, ErrorKind::InvalidArgs);
+        assert_eq!(argv(r#"{"command": "ticker", "args": {"api_key": "x"}}"#).unwrap_err().kind, ErrorKind::InvalidArgs);
+        assert_eq!(argv(r#"{"command": "script run", "args": {"file": "x.rhai"}}"#).unwrap_err().kind, ErrorKind::UnknownCommand);
+        assert_eq!(argv(r#"{"command": "__complete"}"#).unwrap_err().kind, ErrorKind::UnknownCommand);
+        assert_eq!(argv(r#"{"command": "ticker", "args": {"strict": false}}"#).unwrap_err().kind, ErrorKind::InvalidArgs);
+        assert_eq!(argv(r#"{"command": "ticker", "args": {"strict": "yes"}}"#).unwrap_err().kind, ErrorKind::InvalidArgs);
+    }
+}
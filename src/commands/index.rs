@@ -0,0 +1,26 @@
+use anyhow::Result;
+use clap::Subcommand;
+use coinpaprika_cli::{cache, index, ApiClient};
+
+use super::CommandOutput;
+
+#[derive(Subcommand)]
+pub enum IndexCommand {
+    /// Download coins, exchanges and tags for `search --local`
+    Update,
+}
+
+pub async fn execute(client: &ApiClient, cmd: IndexCommand) -> Result<CommandOutput> {
+    match cmd {
+        IndexCommand::Update => {
+            let built = index::update(client).await?;
+            Ok(CommandOutput::message(format!(
+                "Indexed {} coins, {} exchanges and {} tags.\nSaved to {}",
+                built.data.coins.len(),
+                built.data.exchanges.len(),
+                built.data.tags.len(),
+                cache::cache_dir()?.display()
+            )))
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use coinpaprika_cli::mappings::{self, cached_mappings};
+use coinpaprika_cli::types::CoinMapping;
+use coinpaprika_cli::ApiClient;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::{CommandOutput, OutputData};
+
+#[derive(Subcommand)]
+pub enum MappingsCommand {
+    /// Find the CoinPaprika coin for another provider's ID
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli mappings lookup --from coingecko:bitcoin\n  coinpaprika-cli mappings lookup --from cmc:1")]
+    Lookup {
+        /// PROVIDER:ID (providers: coinmarketcap/cmc, coingecko/cg, cryptocompare/cc, ...)
+        #[arg(long, value_name = "PROVIDER:ID")]
+        from: String,
+    },
+    /// Show a coin's IDs on other providers
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli mappings for btc-bitcoin")]
+    For {
+        /// Coin ID (e.g., btc-bitcoin)
+        coin_id: String,
+    },
+    /// Translate a column of IDs in a CSV file between providers
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli mappings translate holdings.csv --column id --from coingecko\n  coinpaprika-cli mappings translate ids.csv --column coin --from cmc --to coingecko --out ids-cg.csv")]
+    Translate {
+        /// CSV file with a header row
+        file: PathBuf,
+        /// Header of the column to translate
+        #[arg(long)]
+        column: String,
+        /// Provider the column's IDs come from (e.g., coingecko, cmc)
+        #[arg(long)]
+        from: String,
+        /// Provider to translate to
+        #[arg(long, default_value = "coinpaprika")]
+        to: String,
+        /// Write the result here instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+}
+
+pub async fn execute_list(client: &ApiClient, limit: Option<usize>) -> Result<CommandOutput> {
+    let mut mappings = cached_mappings(client).await?;
+    if let Some(limit) = limit {
+        mappings.truncate(limit);
+    }
+    Ok(CommandOutput::new(OutputData::Mappings(mappings), "/coins/mappings"))
+}
+
+pub async fn execute(client: &ApiClient, cmd: MappingsCommand) -> Result<CommandOutput> {
+    let all = cached_mappings(client).await?;
+    match cmd {
+        MappingsCommand::Lookup { from } => {
+            let (provider, id) = mappings::parse_reference(&from)?;
+            let found: Vec<CoinMapping> = mappings::lookup(&all, &provider, &id).into_iter().cloned().collect();
+            if found.is_empty() {
+                bail!(
+                    "No CoinPaprika coin maps to {provider}:{id}.\n\n\
+                     Known providers: {}",
+                    mappings::providers(&all).join(", ")
+                );
+            }
+            Ok(CommandOutput::new(OutputData::Mappings(found), "/coins/mappings"))
+        }
+        MappingsCommand::For { coin_id } => {
+            let Some(mapping) = all.into_iter().find(|m| m.coinpaprika == coin_id) else {
+                bail!("No mappings for {coin_id}. Check the ID (e.g., btc-bitcoin) or try: coinpaprika-cli mappings lookup");
+            };
+            Ok(CommandOutput::new(OutputData::Mapping(Box::new(mapping)), &format!("/coins/{coin_id}")))
+        }
+        MappingsCommand::Translate { file, column, from, to, out } => {
+            let (from, to) = (mappings::provider_name(&from), mappings::provider_name(&to));
+            let table: HashMap<String, &str> = all
+                .iter()
+                .filter_map(|m| Some((m.id_for(&from)?.to_lowercase(), m.id_for(&to)?)))
+                .collect();
+            if table.is_empty() {
+                bail!(
+                    "No coins map from '{from}' to '{to}'.\n\nKnown providers: {}",
+                    mappings::providers(&all).join(", ")
+                );
+            }
+
+            let input = std::fs::read(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+            let (csv, translated, missed) = translate_csv(&input, &column, |id| table.get(&id.to_lowercase()).copied())?;
+            let summary = format!("Translated {translated} {from} ID(s) to {to}; {missed} had no mapping and were left as-is.");
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, csv).with_context(|| format!("Failed to write {}", path.display()))?;
+                    Ok(CommandOutput::message(format!("{summary}\nSaved to {}", path.display())))
+                }
+                None => {
+                    eprintln!("{summary}");
+                    Ok(CommandOutput::message(String::from_utf8_lossy(&csv).trim_end()))
+                }
+            }
+        }
+    }
+}
+
+/// Rewrite one column of a CSV through `translate`. Returns the new CSV and
+/// counts of translated and untranslatable non-empty cells.
+fn translate_csv<'a>(
+    input: &[u8],
+    column: &str,
+    translate: impl Fn(&str) -> Option<&'a str>,
+) -> Result<(Vec<u8>, usize, usize)> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(input);
+    let headers = reader.headers()?.clone();
+    let Some(index) = headers.iter().position(|h| h.trim() == column) else {
+        bail!(
+            "No column '{column}' in the CSV header.\n\nColumns: {}",
+            headers.iter().collect::<Vec<_>>().join(", ")
+        );
+    };
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&headers)?;
+    let (mut translated, mut missed) = (0, 0);
+    for record in reader.records() {
+        let record = record?;
+        let row: Vec<&str> = record
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                if i != index || cell.trim().is_empty() {
+                    return cell;
+                }
+                match translate(cell.trim()) {
+                    Some(new) => {
+                        translated += 1;
+                        new
+                    }
+                    None => {
+                        missed += 1;
+                        cell
+                    }
+                }
+            })
+            .collect();
+        writer.write_record(row)?;
+    }
+    Ok((writer.into_inner()?, translated, missed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translates_only_the_named_column() {
+        let input = b"name,id,qty\nBitcoin,bitcoin,1\n\"Eth, Inc\",ethereum,2\nFoo,unknown,3\n";
+        let (out, translated, missed) = translate_csv(input, "id", |id| match id {
+            "bitcoin" => Some("btc-bitcoin"),
+            "ethereum" => Some("eth-ethereum"),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "name,id,qty\nBitcoin,btc-bitcoin,1\n\"Eth, Inc\",eth-ethereum,2\nFoo,unknown,3\n"
+        );
+        assert_eq!((translated, missed), (2, 1));
+    }
+
+    #[test]
+    fn unknown_column_is_an_error() {
+        assert!(translate_csv(b"a,b\n1,2\n", "id", |_| None).is_err());
+    }
+}
@@ -8,9 +8,132 @@ pub mod people;
 pub mod search;
 pub mod convert;
 pub mod contracts;
+pub mod api;
 pub mod api_management;
+pub mod batch;
+pub mod exec;
+pub mod changelog;
 pub mod config;
+pub mod doctor;
+pub mod index;
+pub mod mappings;
+pub mod multi;
 pub mod status;
 pub mod attribution;
 pub mod onboard;
 pub mod plans;
+pub mod plugins;
+pub mod schema;
+pub mod script;
+
+use coinpaprika_cli::HistoricalParams;
+use coinpaprika_cli::types::{
+    ChangelogEntry, CoinDetail, CoinMapping, CoinEvent, CoinExchange, CoinListItem, CoinMarket, Contract, ContractHistoryPoint, ContractTicker,
+    ConvertResult, Exchange, ExchangeMarket, GlobalData, KeyInfo, OhlcvData, Person, SearchResult, Tag, Ticker,
+    TickerHistoryPoint,
+};
+use serde::Serialize;
+
+use crate::output::{RangeMeta, ResponseMeta};
+
+/// The result of running a command, before it is rendered.
+///
+/// Commands only fetch and shape data; `output::render` decides how it is
+/// shown. This keeps results reusable by the shell and testable without
+/// capturing stdout.
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub data: OutputData,
+    pub meta: ResponseMeta,
+}
+
+impl CommandOutput {
+    pub fn new(data: OutputData, entity_path: &str) -> Self {
+        Self {
+            data,
+            meta: ResponseMeta::coinpaprika(entity_path),
+        }
+    }
+
+    /// Record the resolved range of a historical request in `_meta`.
+    pub fn with_range(mut self, params: &HistoricalParams) -> Self {
+        let (start, end) = params.range();
+        let time = |t: chrono::DateTime<chrono::Utc>| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        self.meta.range = Some(RangeMeta {
+            start: time(start),
+            end: time(end),
+            interval: params.requested_interval().map(|i| i.to_string()),
+        });
+        self
+    }
+
+    /// A plain status message, e.g. from `config set-key`.
+    pub fn message(text: impl Into<String>) -> Self {
+        Self::new(OutputData::Message(text.into()), "")
+    }
+}
+
+/// A failure whose details are already in the command's output, e.g. the
+/// failed lines of a `batch` document. `main` prints only this summary, to
+/// stderr, so stdout stays valid JSON.
+#[derive(Debug)]
+pub struct AlreadyReported(pub String);
+
+impl std::fmt::Display for AlreadyReported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for AlreadyReported {}
+
+/// One variant per payload shape. Serializes as the bare payload so JSON
+/// output is unchanged from what the API returned.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum OutputData {
+    Global(GlobalData),
+    Coins(Vec<CoinListItem>),
+    Coin(Box<CoinDetail>),
+    CoinDetails(Vec<CoinDetail>),
+    CoinEvents(Vec<CoinEvent>),
+    CoinEventsByCoin(Vec<multi::ForCoin<CoinEvent>>),
+    CoinExchanges(Vec<CoinExchange>),
+    CoinMarkets(Vec<CoinMarket>),
+    Tickers(Vec<Ticker>),
+    Ticker(Box<Ticker>),
+    TickerHistory(Vec<TickerHistoryPoint>),
+    Ohlcv(Vec<OhlcvData>),
+    OhlcvByCoin(Vec<multi::ForCoin<OhlcvData>>),
+    Exchanges(Vec<Exchange>),
+    Exchange(Box<Exchange>),
+    ExchangeMarkets(Vec<ExchangeMarket>),
+    Tags(Vec<Tag>),
+    Tag(Tag),
+    Person(Person),
+    Search(SearchResult),
+    Convert(ConvertResult),
+    Platforms(Vec<String>),
+    Contracts(Vec<Contract>),
+    ContractTicker(Box<ContractTicker>),
+    ContractTickers(Vec<ContractTicker>),
+    ContractHistory(Vec<ContractHistoryPoint>),
+    KeyInfo(KeyInfo),
+    Mappings(Vec<CoinMapping>),
+    Mapping(Box<CoinMapping>),
+    Changelog(Vec<ChangelogEntry>),
+    ChangelogApply(changelog::ApplyReport),
+    Config(config::ConfigInfo),
+    Profiles(Vec<config::ProfileInfo>),
+    Status(status::StatusResult),
+    Doctor(doctor::DoctorReport),
+    Attribution(attribution::AttributionData),
+    Plans(plans::PlansInfo),
+    Plugins(Vec<plugins::Plugin>),
+    /// Whatever a script emitted.
+    Script(serde_json::Value),
+    /// A response body from `api`, as the API returned it.
+    Api(serde_json::Value),
+    Schema(Box<schema::CommandSchema>),
+    Message(String),
+}
@@ -0,0 +1,192 @@
+//! Running one command over many IDs: `ticker btc-bitcoin eth-ethereum` or
+//! `ticker --ids-from coins.txt`.
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use futures::stream::{self, StreamExt};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::future::Future;
+use std::io::Read;
+
+use super::{CommandOutput, OutputData};
+use crate::output::ItemError;
+
+#[derive(Args, Debug, Clone)]
+pub struct BatchArgs {
+    /// Read more IDs from a file, or `-` for stdin (one per line or comma-separated; `#` starts a comment)
+    #[arg(long, value_name = "FILE|-")]
+    pub ids_from: Option<String>,
+    /// How many requests to run at once
+    #[arg(long, default_value = "8", value_parser = clap::value_parser!(u16).range(1..=64))]
+    pub parallel: u16,
+    /// Set when IDs were dropped before fetching, so a single one left
+    /// still gets the multi-ID output.
+    #[arg(skip)]
+    pub dropped_ids: bool,
+}
+
+impl BatchArgs {
+    /// Append the IDs from `--ids-from`, if any, to `ids`.
+    pub fn extend_ids(&self, ids: &mut Vec<String>) -> Result<()> {
+        let Some(source) = &self.ids_from else {
+            return Ok(());
+        };
+        let text = if source == "-" {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text).context("Failed to read IDs from stdin")?;
+            text
+        } else {
+            std::fs::read_to_string(source).with_context(|| format!("Failed to read {source}"))?
+        };
+        ids.extend(parse_ids(&text));
+        if ids.is_empty() {
+            bail!("No IDs given. Pass them as arguments or list them in {source}.");
+        }
+        Ok(())
+    }
+
+    /// The only ID when the command should keep its single-item output.
+    pub fn single<'a>(&self, ids: &'a [String]) -> Option<&'a str> {
+        match ids {
+            [id] if self.ids_from.is_none() && !self.dropped_ids => Some(id),
+            _ => None,
+        }
+    }
+}
+
+/// An item tagged with the coin it belongs to, so results for several coins
+/// can share one table or array.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[schemars(rename = "ForCoin_{T}")]
+pub struct ForCoin<T> {
+    pub coin_id: String,
+    #[serde(flatten)]
+    pub item: T,
+}
+
+fn parse_ids(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .flat_map(|line| line.split([',', ' ', '\t']))
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(String::from)
+}
+
+/// Fetch every ID with at most `parallel` requests in flight. Results keep
+/// the order of `ids`; failures are collected instead of aborting the rest.
+pub async fn fetch_all<'a, T, Fut>(
+    ids: &'a [String],
+    parallel: u16,
+    fetch: impl Fn(&'a str) -> Fut,
+) -> (Vec<(&'a str, T)>, Vec<ItemError>)
+where
+    Fut: Future<Output = Result<T>>,
+{
+    // Futures do nothing until polled, so building them all up front still
+    // keeps only `parallel` requests in flight.
+    let requests: Vec<_> = ids.iter().map(|id| {
+        let request = fetch(id);
+        async move { (id.as_str(), request.await) }
+    }).collect();
+    let results: Vec<(&str, Result<T>)> = stream::iter(requests).buffered(usize::from(parallel)).collect().await;
+
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    for (id, result) in results {
+        match result {
+            Ok(item) => items.push((id, item)),
+            Err(e) => errors.push(ItemError { id: id.to_string(), error: e.to_string() }),
+        }
+    }
+    (items, errors)
+}
+
+/// Every item of a multi-ID request failed.
+#[derive(Debug)]
+pub struct AllFailed(pub Vec<ItemError>);
+
+impl std::fmt::Display for AllFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "All {} requests failed:", self.0.len())?;
+        for e in &self.0 {
+            write!(f, "\n  {}: {}", e.id, first_line(&e.error))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for AllFailed {}
+
+/// Wrap merged results, failing only when nothing succeeded.
+pub fn output(data: OutputData, succeeded: usize, errors: Vec<ItemError>, entity_path: &str) -> Result<CommandOutput> {
+    if succeeded == 0 && !errors.is_empty() {
+        return Err(AllFailed(errors).into());
+    }
+    let mut result = CommandOutput::new(data, entity_path);
+    result.meta.errors = errors;
+    Ok(result)
+}
+
+/// Add items that failed before any request, such as IDs that couldn't be
+/// resolved, to a multi-ID result.
+pub fn with_errors(result: Result<CommandOutput>, mut errors: Vec<ItemError>) -> Result<CommandOutput> {
+    if errors.is_empty() {
+        return result;
+    }
+    match result {
+        Ok(mut output) => {
+            errors.append(&mut output.meta.errors);
+            output.meta.errors = errors;
+            Ok(output)
+        }
+        Err(e) => match e.downcast::<AllFailed>() {
+            Ok(AllFailed(mut failed)) => {
+                errors.append(&mut failed);
+                Err(AllFailed(errors).into())
+            }
+            Err(e) => Err(e),
+        },
+    }
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lines_commas_and_comments() {
+        let ids: Vec<String> = parse_ids("btc-bitcoin\n# watchlist\neth-ethereum, sol-solana  # L1s\n\n").collect();
+        assert_eq!(ids, ["btc-bitcoin", "eth-ethereum", "sol-solana"]);
+    }
+
+    #[tokio::test]
+    async fn keeps_order_and_collects_errors() {
+        let ids: Vec<String> = ["a", "bad", "c"].map(String::from).to_vec();
+        let (items, errors) = fetch_all(&ids, 2, |id| async move {
+            if id == "bad" {
+                bail!("not found")
+            }
+            Ok(id.to_uppercase())
+        })
+        .await;
+        assert_eq!(items, [("a", "A".to_string()), ("c", "C".to_string())]);
+        assert_eq!(errors[0].id, "bad");
+    }
+
+    #[test]
+    fn merges_errors_from_before_the_fetch() {
+        let error = |id: &str| ItemError { id: id.into(), error: "ambiguous".into() };
+        let fetched = output(OutputData::Message(String::new()), 1, vec![error("b")], "/coins");
+        assert_eq!(with_errors(fetched, vec![error("a")]).unwrap().meta.errors.len(), 2);
+
+        let failed = output(OutputData::Message(String::new()), 0, vec![error("b")], "/coins");
+        let message = with_errors(failed, vec![error("a")]).unwrap_err().to_string();
+        assert!(message.starts_with("All 2 requests failed:\n  a: ambiguous"), "{message}");
+    }
+}
@@ -1,80 +1,30 @@
 use anyhow::Result;
-use serde::{Deserialize, Serialize};
+use coinpaprika_cli::{ApiClient, HistoricalParams};
 
-use crate::client::ApiClient;
-use crate::output::OutputFormat;
+use super::multi::{self, ForCoin};
+use super::{CommandOutput, OutputData};
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct OhlcvData {
-    pub time_open: Option<String>,
-    pub time_close: Option<String>,
-    pub open: Option<f64>,
-    pub high: Option<f64>,
-    pub low: Option<f64>,
-    pub close: Option<f64>,
-    pub volume: Option<f64>,
-    pub market_cap: Option<f64>,
+pub async fn execute_historical(client: &ApiClient, coin_id: &str, params: &HistoricalParams) -> Result<CommandOutput> {
+    let data = client.ohlcv_historical(coin_id, params).await?;
+    Ok(CommandOutput::new(OutputData::Ohlcv(data), &format!("/coin/{coin_id}")).with_range(params))
 }
 
-pub async fn execute_historical(
-    client: &ApiClient,
-    coin_id: &str,
-    start: &str,
-    end: Option<&str>,
-    interval: &str,
-    limit: usize,
-    quote: &str,
-    output: OutputFormat,
-    raw: bool,
-) -> Result<()> {
-    let limit_str = limit.to_string();
-    let mut params: Vec<(&str, &str)> = vec![
-        ("start", start),
-        ("interval", interval),
-        ("limit", &limit_str),
-        ("quote", quote),
-    ];
-    if let Some(e) = end {
-        params.push(("end", e));
-    }
-
-    let data: Vec<OhlcvData> = client.coinpaprika_get(
-        &format!("/coins/{coin_id}/ohlcv/historical"),
-        &params,
-    ).await?;
-    match output {
-        OutputFormat::Table => crate::output::ohlcv::print_ohlcv_table(&data),
-        OutputFormat::Json => {
-            crate::output::print_json_wrapped(&data, crate::output::ResponseMeta::coinpaprika(&format!("/coin/{coin_id}")), raw)?;
-        }
-    }
-    Ok(())
+pub async fn execute_latest(client: &ApiClient, coin_id: &str, quote: &str) -> Result<CommandOutput> {
+    let data = client.ohlcv_latest(coin_id, quote).await?;
+    Ok(CommandOutput::new(OutputData::Ohlcv(data), &format!("/coin/{coin_id}")))
 }
 
-pub async fn execute_latest(client: &ApiClient, coin_id: &str, quote: &str, output: OutputFormat, raw: bool) -> Result<()> {
-    let data: Vec<OhlcvData> = client.coinpaprika_get(
-        &format!("/coins/{coin_id}/ohlcv/latest"),
-        &[("quote", quote)],
-    ).await?;
-    match output {
-        OutputFormat::Table => crate::output::ohlcv::print_ohlcv_table(&data),
-        OutputFormat::Json => {
-            crate::output::print_json_wrapped(&data, crate::output::ResponseMeta::coinpaprika(&format!("/coin/{coin_id}")), raw)?;
-        }
-    }
-    Ok(())
+pub async fn execute_latest_many(client: &ApiClient, coin_ids: &[String], quote: &str, parallel: u16) -> Result<CommandOutput> {
+    let (items, errors) = multi::fetch_all(coin_ids, parallel, |id| client.ohlcv_latest(id, quote)).await;
+    let succeeded = items.len();
+    let data = items
+        .into_iter()
+        .flat_map(|(id, data)| data.into_iter().map(move |item| ForCoin { coin_id: id.to_string(), item }))
+        .collect();
+    multi::output(OutputData::OhlcvByCoin(data), succeeded, errors, "/coins")
 }
 
-pub async fn execute_today(client: &ApiClient, coin_id: &str, quote: &str, output: OutputFormat, raw: bool) -> Result<()> {
-    let data: Vec<OhlcvData> = client.coinpaprika_get(
-        &format!("/coins/{coin_id}/ohlcv/today"),
-        &[("quote", quote)],
-    ).await?;
-    match output {
-        OutputFormat::Table => crate::output::ohlcv::print_ohlcv_table(&data),
-        OutputFormat::Json => {
-            crate::output::print_json_wrapped(&data, crate::output::ResponseMeta::coinpaprika(&format!("/coin/{coin_id}")), raw)?;
-        }
-    }
-    Ok(())
+pub async fn execute_today(client: &ApiClient, coin_id: &str, quote: &str) -> Result<CommandOutput> {
+    let data = client.ohlcv_today(coin_id, quote).await?;
+    Ok(CommandOutput::new(OutputData::Ohlcv(data), &format!("/coin/{coin_id}")))
 }
@@ -1,23 +1,22 @@
 use anyhow::Result;
+use coinpaprika_cli::ApiClient;
 
 pub async fn execute(key: Option<String>) -> Result<()> {
     // Non-interactive mode: validate then save the key
     if let Some(key) = key {
         println!("Validating key...");
-        let client = crate::client::ApiClient::new(Some(key.clone()));
-        match client.coinpaprika_get::<serde_json::Value>("/key/info", &[]).await {
+        let client = ApiClient::new(Some(key.clone()));
+        match client.key_info().await {
             Ok(info) => {
-                crate::config::save_api_key(&key)?;
-                let plan = info.get("plan")
-                    .and_then(|p| p.as_str())
-                    .unwrap_or("unknown");
+                crate::config::save_api_key(&key, None)?;
+                let plan = info.plan.as_deref().unwrap_or("unknown");
                 println!("Key validated! Plan: {plan}");
                 println!("Saved to {}", crate::config::config_path()?.display());
                 println!("Key: {}", crate::config::mask_key(&key));
             }
             Err(_) => {
                 println!("Could not validate key (it may still work). Saving anyway.");
-                crate::config::save_api_key(&key)?;
+                crate::config::save_api_key(&key, None)?;
                 println!("Saved to {}", crate::config::config_path()?.display());
                 println!("Key: {}", crate::config::mask_key(&key));
             }
@@ -55,19 +54,17 @@ pub async fn execute(key: Option<String>) -> Result<()> {
 
         // Validate by calling /key-info
         println!("Validating key...");
-        let client = crate::client::ApiClient::new(Some(key.to_string()));
-        match client.coinpaprika_get::<serde_json::Value>("/key/info", &[]).await {
+        let client = ApiClient::new(Some(key.to_string()));
+        match client.key_info().await {
             Ok(info) => {
-                crate::config::save_api_key(key)?;
-                let plan = info.get("plan")
-                    .and_then(|p| p.as_str())
-                    .unwrap_or("unknown");
+                crate::config::save_api_key(key, None)?;
+                let plan = info.plan.as_deref().unwrap_or("unknown");
                 println!("Key validated! Plan: {plan}");
                 println!("Saved to {}", crate::config::config_path()?.display());
             }
             Err(_) => {
                 println!("Could not validate key (it may still work). Saving anyway.");
-                crate::config::save_api_key(key)?;
+                crate::config::save_api_key(key, None)?;
                 println!("Saved to {}", crate::config::config_path()?.display());
             }
         }
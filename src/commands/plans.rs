@@ -1,34 +1,39 @@
-use anyhow::Result;
+use coinpaprika_cli::plans::{self, Plan};
+use schemars::JsonSchema;
 use serde::Serialize;
 
-use crate::output::OutputFormat;
+use super::{CommandOutput, OutputData};
 
-#[derive(Debug, Serialize)]
-struct FreeTier {
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FreeTier {
     rate_limit: &'static str,
     update_frequency: &'static str,
     endpoints: &'static str,
     assets: &'static str,
     use_case: &'static str,
-    daily_ohlcv_history: &'static str,
-    hourly_ohlcv: &'static str,
-    ohlcv_intervals: &'static str,
+    pub daily_ohlcv_history: String,
+    pub hourly_ohlcv: String,
+    pub ohlcv_intervals: String,
     ticker_history: bool,
     circulating_supply: bool,
     api_id_mappings: bool,
+    id_changelog: bool,
     websockets: bool,
     redistribution: bool,
     sla: bool,
 }
 
-#[derive(Debug, Serialize)]
-struct PlansInfo {
-    free_tier: FreeTier,
-    paid_plans_url: &'static str,
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PlansInfo {
+    pub free_tier: FreeTier,
+    /// What each plan allows; requests outside it are rejected before they are sent.
+    pub plans: &'static [Plan],
+    pub paid_plans_url: &'static str,
     set_key_command: &'static str,
 }
 
-pub fn execute(output: OutputFormat, raw: bool) -> Result<()> {
+pub fn execute() -> CommandOutput {
+    let free = &plans::FREE;
     let info = PlansInfo {
         free_tier: FreeTier {
             rate_limit: "20,000 calls/month",
@@ -36,60 +41,31 @@ pub fn execute(output: OutputFormat, raw: bool) -> Result<()> {
             endpoints: "25+",
             assets: "2,000",
             use_case: "Personal",
-            daily_ohlcv_history: "up to 1 year back",
-            hourly_ohlcv: "last 24 hours",
-            ohlcv_intervals: "24h only",
-            ticker_history: false,
+            daily_ohlcv_history: history(free.daily_history_days),
+            hourly_ohlcv: history(free.intraday_history_days),
+            ohlcv_intervals: free.interval_list(),
+            ticker_history: free.ticker_history,
             circulating_supply: false,
-            api_id_mappings: false,
+            api_id_mappings: free.mappings,
+            id_changelog: free.changelog,
             websockets: false,
             redistribution: false,
             sla: false,
         },
-        paid_plans_url: "https://coinpaprika.com/api/pricing",
+        plans: plans::PLANS,
+        paid_plans_url: plans::PRICING_URL,
         set_key_command: "coinpaprika-cli config set-key <KEY>",
     };
 
-    match output {
-        OutputFormat::Table => {
-            println!();
-            println!("  ─── Free tier ($0/mo, no API key needed) ───");
-            println!();
-            println!("  Rate limits");
-            println!("    20,000 calls/month");
-            println!("    Data updates every ~10 minutes");
-            println!();
-            println!("  Coverage");
-            println!("    25+ endpoints");
-            println!("    2,000 assets");
-            println!("    Personal use");
-            println!();
-            println!("  Historical data");
-            println!("    Daily OHLCV:    up to 1 year back");
-            println!("    Hourly OHLCV:   last 24 hours");
-            println!("    OHLCV interval: 24h only");
-            println!("    5-min / ticker history: not available");
-            println!();
-            println!("  Not included");
-            println!("    Circulating supply");
-            println!("    API ID mappings");
-            println!("    WebSockets");
-            println!("    Redistribution rights");
-            println!("    SLA / dedicated infrastructure");
-            println!();
-            println!("  ─── Need more? ───");
-            println!();
-            println!("  Paid plans add: full history, 5-min intervals, circulating supply,");
-            println!("  higher limits, WebSockets, commercial use, and priority support.");
-            println!();
-            println!("  See current pricing:  https://coinpaprika.com/api/pricing");
-            println!("  Set your API key:     coinpaprika-cli config set-key <KEY>");
-            println!();
-        }
-        OutputFormat::Json => {
-            crate::output::print_json_wrapped(&info, crate::output::ResponseMeta::coinpaprika("/plans"), raw)?;
-        }
-    }
+    CommandOutput::new(OutputData::Plans(info), "/plans")
+}
 
-    Ok(())
+/// "up to 365 days back", "last 24 hours" for one day, or "full" for `None`.
+pub fn history(days: Option<i64>) -> String {
+    match days {
+        None => "full".into(),
+        Some(0) => "none".into(),
+        Some(1) => "last 24 hours".into(),
+        Some(days) => format!("up to {days} days back"),
+    }
 }
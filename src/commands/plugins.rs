@@ -0,0 +1,200 @@
+//! Git-style plugins: `coinpaprika-cli foo` runs `coinpaprika-cli-foo` from
+//! PATH, passing the resolved settings in the environment and a JSON
+//! context on stdin.
+
+use anyhow::{bail, Context, Result};
+use clap::{Subcommand, ValueEnum};
+use coinpaprika_cli::resolve::levenshtein;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::{CommandOutput, OutputData};
+use crate::output::OutputFormat;
+
+pub const PREFIX: &str = "coinpaprika-cli-";
+
+#[derive(Subcommand)]
+pub enum PluginsCommand {
+    /// List plugins found on PATH
+    List,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Plugin {
+    pub name: String,
+    pub path: String,
+}
+
+/// What a plugin is told about the invocation, as JSON on stdin.
+#[derive(Debug, Serialize)]
+pub struct PluginContext<'a> {
+    pub version: &'a str,
+    pub plugin: &'a str,
+    pub args: &'a [String],
+    pub api_key: Option<&'a str>,
+    pub base_url: &'a str,
+    pub output: &'a str,
+    pub raw: bool,
+    pub strict: bool,
+}
+
+pub fn execute(cmd: PluginsCommand) -> Result<CommandOutput> {
+    match cmd {
+        PluginsCommand::List => Ok(CommandOutput::new(OutputData::Plugins(discover()), "")),
+    }
+}
+
+/// Plugins on PATH by name; an earlier PATH entry wins, as in the shell.
+pub fn discover() -> Vec<Plugin> {
+    let mut found: BTreeMap<String, PathBuf> = BTreeMap::new();
+    let Some(path) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    for dir in std::env::split_paths(&path) {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let Some(name) = file_name.to_str().and_then(|n| n.strip_prefix(PREFIX)) else { continue };
+            let name = name.strip_suffix(std::env::consts::EXE_SUFFIX).unwrap_or(name);
+            if !name.is_empty() && is_executable(&entry.path()) {
+                found.entry(name.to_string()).or_insert_with(|| entry.path());
+            }
+        }
+    }
+    found
+        .into_iter()
+        .map(|(name, path)| Plugin { name, path: path.display().to_string() })
+        .collect()
+}
+
+/// A `--help` section listing plugins, if there are any.
+pub fn help_section() -> Option<String> {
+    let plugins = discover();
+    if plugins.is_empty() {
+        return None;
+    }
+    let width = plugins.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    let lines: Vec<String> = plugins.iter().map(|p| format!("  {:<width$}  {}", p.name, p.path)).collect();
+    Some(format!("Plugins:\n{}", lines.join("\n")))
+}
+
+/// A plugin that exited unsuccessfully; `main` exits with the same code.
+#[derive(Debug)]
+pub struct PluginExit {
+    pub name: String,
+    pub code: i32,
+}
+
+impl std::fmt::Display for PluginExit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{PREFIX}{} exited with status {}", self.name, self.code)
+    }
+}
+
+impl std::error::Error for PluginExit {}
+
+/// Run the plugin named by `args[0]`, failing with [`PluginExit`] if it does.
+/// Blocks until the plugin exits, so call this from
+/// `tokio::task::block_in_place`.
+pub fn run(args: &[String], api_key: Option<&str>, base_url: &str, output: OutputFormat, raw: bool, strict: bool) -> Result<()> {
+    let Some((name, rest)) = args.split_first() else {
+        bail!("No command given. Run coinpaprika-cli --help");
+    };
+    let plugins = discover();
+    let Some(plugin) = plugins.iter().find(|p| &p.name == name) else {
+        return Err(unknown_command(name, &plugins));
+    };
+
+    let output = output.to_possible_value().map(|v| v.get_name().to_string()).unwrap_or_default();
+    let context = PluginContext {
+        version: env!("CARGO_PKG_VERSION"),
+        plugin: name,
+        args: rest,
+        api_key,
+        base_url,
+        output: &output,
+        raw,
+        strict,
+    };
+
+    let mut command = Command::new(&plugin.path);
+    command
+        .args(rest)
+        .env("COINPAPRIKA_BASE_URL", base_url)
+        .env("COINPAPRIKA_OUTPUT", &output)
+        .env("COINPAPRIKA_RAW", if raw { "1" } else { "0" })
+        .stdin(Stdio::piped());
+    match api_key {
+        Some(key) => command.env("COINPAPRIKA_API_KEY", key),
+        None => command.env_remove("COINPAPRIKA_API_KEY"),
+    };
+    let mut child = command.spawn().with_context(|| format!("Failed to run {}", plugin.path))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A plugin that ignores stdin may exit before reading it.
+        let _ = stdin.write_all(serde_json::to_string(&context)?.as_bytes());
+    }
+    let status = child.wait().with_context(|| format!("Failed to wait for {}", plugin.path))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(PluginExit { name: name.clone(), code: status.code().unwrap_or(1) }.into())
+    }
+}
+
+/// The error for a plugin name where plugins can't run (`batch`, `exec`).
+pub fn not_runnable_here(args: &[String]) -> anyhow::Error {
+    let name = args.first().map(String::as_str).unwrap_or_default();
+    let plugins = discover();
+    if plugins.iter().any(|p| p.name == name) {
+        anyhow::anyhow!("'{name}' is a plugin; plugins only run directly from the command line or shell")
+    } else {
+        unknown_command(name, &plugins)
+    }
+}
+
+fn unknown_command(name: &str, plugins: &[Plugin]) -> anyhow::Error {
+    use clap::CommandFactory;
+    let builtins = crate::Cli::command();
+    let candidates = builtins
+        .get_subcommands()
+        .map(|c| c.get_name().to_string())
+        .chain(plugins.iter().map(|p| p.name.clone()));
+    let suggestion = candidates
+        .map(|c| (levenshtein(name, &c), c))
+        .filter(|(d, _)| *d <= 2)
+        .min()
+        .map(|(_, c)| format!("\n\nDid you mean '{c}'?"))
+        .unwrap_or_default();
+    anyhow::anyhow!(
+        "Unknown command '{name}'.{suggestion}\n\n\
+         Run coinpaprika-cli --help for commands, or install a plugin as {PREFIX}{name} on PATH."
+    )
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_command_suggests_a_close_name() {
+        let plugins = [Plugin { name: "portfolio".into(), path: "/usr/local/bin/coinpaprika-cli-portfolio".into() }];
+        assert!(unknown_command("tickr", &plugins).to_string().contains("Did you mean 'ticker'?"));
+        assert!(unknown_command("portfolo", &plugins).to_string().contains("'portfolio'"));
+        assert!(!unknown_command("zzzzzz", &plugins).to_string().contains("Did you mean"));
+    }
+}
@@ -0,0 +1,255 @@
+use anyhow::{bail, Result};
+use coinpaprika_cli::types::{
+    ChangelogEntry, CoinDetail, CoinMapping, CoinEvent, CoinExchange, CoinListItem, CoinMarket, Contract, ContractHistoryPoint, ContractTicker,
+    ConvertResult, Exchange, ExchangeMarket, GlobalData, KeyInfo, OhlcvData, Person, SearchResult, Tag, Ticker,
+    TickerHistoryPoint,
+};
+use schemars::generate::SchemaSettings;
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use super::multi::ForCoin;
+use super::{attribution, changelog, config, doctor, exec, plans, status, CommandOutput, OutputData};
+use crate::output::WrappedResponse;
+
+/// Schemas for one command's JSON output.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandSchema {
+    /// What `--output json` prints: `{data, _meta}`.
+    pub envelope: serde_json::Value,
+    /// What `--output json --raw` prints.
+    pub payload: serde_json::Value,
+}
+
+/// Commands with JSON output, in `--help` order.
+pub const COMMANDS: &[&str] = &[
+    "global",
+    "coins",
+    "coin",
+    "coin-events",
+    "coin-exchanges",
+    "coin-markets",
+    "tickers",
+    "ticker",
+    "ticker-history",
+    "ohlcv",
+    "ohlcv-latest",
+    "ohlcv-today",
+    "exchanges",
+    "exchange",
+    "exchange-markets",
+    "tags",
+    "tag",
+    "person",
+    "search",
+    "convert",
+    "platforms",
+    "contracts",
+    "contract-ticker",
+    "contract-history",
+    "key-info",
+    "mappings",
+    "mappings-for",
+    "changelog",
+    "changelog-apply",
+    "config",
+    "config-profile-list",
+    "status",
+    "doctor",
+    "attribution",
+    "plans",
+    "exec",
+];
+
+pub fn execute(command: Option<&str>) -> Result<CommandOutput> {
+    let Some(command) = command else {
+        return Ok(CommandOutput::message(format!(
+            "Commands with a JSON schema:\n  {}\n\nUsage: coinpaprika-cli schema <COMMAND>",
+            COMMANDS.join("\n  ")
+        )));
+    };
+    let schema = match command {
+        "global" => schema::<GlobalData>(),
+        "coins" => schema::<Vec<CoinListItem>>(),
+        "coin" => schema::<OneOf<CoinDetail, Vec<CoinDetail>>>(),
+        "coin-events" => schema::<AnyOf<Vec<CoinEvent>, Vec<ForCoin<CoinEvent>>>>(),
+        "coin-exchanges" => schema::<Vec<CoinExchange>>(),
+        "coin-markets" => schema::<Vec<CoinMarket>>(),
+        "tickers" => schema::<Vec<Ticker>>(),
+        "ticker" => schema::<OneOf<Ticker, Vec<Ticker>>>(),
+        "ticker-history" => schema::<Vec<TickerHistoryPoint>>(),
+        "ohlcv" | "ohlcv-today" => schema::<Vec<OhlcvData>>(),
+        "ohlcv-latest" => schema::<AnyOf<Vec<OhlcvData>, Vec<ForCoin<OhlcvData>>>>(),
+        "exchanges" => schema::<Vec<Exchange>>(),
+        "exchange" => schema::<Exchange>(),
+        "exchange-markets" => schema::<Vec<ExchangeMarket>>(),
+        "tags" => schema::<Vec<Tag>>(),
+        "tag" => schema::<Tag>(),
+        "person" => schema::<Person>(),
+        "search" => schema::<SearchResult>(),
+        "convert" => schema::<ConvertResult>(),
+        "platforms" => schema::<Vec<String>>(),
+        "contracts" => schema::<Vec<Contract>>(),
+        "contract-ticker" => schema::<OneOf<ContractTicker, Vec<ContractTicker>>>(),
+        "contract-history" => schema::<Vec<ContractHistoryPoint>>(),
+        "key-info" => schema::<KeyInfo>(),
+        "mappings" => schema::<Vec<CoinMapping>>(),
+        "mappings-for" => schema::<CoinMapping>(),
+        "changelog" => schema::<Vec<ChangelogEntry>>(),
+        "changelog-apply" => schema::<changelog::ApplyReport>(),
+        "config" => schema::<config::ConfigInfo>(),
+        "config-profile-list" => schema::<Vec<config::ProfileInfo>>(),
+        "status" => schema::<status::StatusResult>(),
+        "doctor" => schema::<doctor::DoctorReport>(),
+        "attribution" => schema::<attribution::AttributionData>(),
+        "plans" => schema::<plans::PlansInfo>(),
+        "exec" => {
+            // exec prints its own envelope rather than the usual data/_meta wrapper.
+            let response = root::<exec::ExecResponse>();
+            CommandSchema { envelope: response.clone(), payload: response }
+        }
+        other => bail!(
+            "No schema for '{other}'.\n\nCommands with JSON output:\n  {}",
+            COMMANDS.join(", ")
+        ),
+    };
+    Ok(CommandOutput::new(OutputData::Schema(Box::new(schema)), ""))
+}
+
+fn schema<T: JsonSchema + 'static>() -> CommandSchema {
+    CommandSchema { envelope: root::<WrappedResponse<'static, T>>(), payload: root::<T>() }
+}
+
+/// The schema of what `T` serializes to, so fields left out when empty
+/// aren't required.
+fn root<T: JsonSchema>() -> serde_json::Value {
+    let generator = SchemaSettings::draft2020_12().for_serialize().into_generator();
+    serde_json::to_value(generator.into_root_schema_for::<T>()).expect("schemas serialize")
+}
+
+/// `One` for a single ID, `Many` for several (`ticker a b`, `--ids-from`).
+struct OneOf<One, Many>(PhantomData<(One, Many)>);
+
+/// Like [`OneOf`], for shapes that overlap: an array of coin-tagged items is
+/// also a valid array of untagged ones, and `[]` is both.
+struct AnyOf<One, Many>(PhantomData<(One, Many)>);
+
+impl<One: JsonSchema, Many: JsonSchema> JsonSchema for OneOf<One, Many> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        format!("{}_or_{}", One::schema_name(), Many::schema_name()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({ "oneOf": [generator.subschema_for::<One>(), generator.subschema_for::<Many>()] })
+    }
+}
+
+impl<One: JsonSchema, Many: JsonSchema> JsonSchema for AnyOf<One, Many> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        format!("{}_or_{}", One::schema_name(), Many::schema_name()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({ "anyOf": [generator.subschema_for::<One>(), generator.subschema_for::<Many>()] })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{multi, tickers};
+    use crate::output::ItemError;
+    use coinpaprika_cli::ApiClient;
+
+    #[test]
+    fn every_listed_command_has_a_schema() {
+        for command in COMMANDS {
+            assert!(execute(Some(command)).is_ok(), "{command}");
+        }
+        assert!(execute(Some("shell")).is_err());
+    }
+
+    /// Enough of JSON Schema for the shapes schemars emits.
+    fn conforms(value: &serde_json::Value, schema: &serde_json::Value, root: &serde_json::Value) -> bool {
+        use serde_json::Value;
+        if let Some(Value::String(reference)) = schema.get("$ref") {
+            let name = reference.trim_start_matches("#/$defs/");
+            return conforms(value, &root["$defs"][name], root);
+        }
+        if let Some(Value::Array(options)) = schema.get("oneOf") {
+            return options.iter().filter(|s| conforms(value, s, root)).count() == 1;
+        }
+        if let Some(Value::Array(options)) = schema.get("anyOf") {
+            return options.iter().any(|s| conforms(value, s, root));
+        }
+        let types: Vec<&str> = match &schema["type"] {
+            Value::String(t) => vec![t],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => return true,
+        };
+        let kind = match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(n) if n.is_f64() => "number",
+            Value::Number(_) => "integer",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        };
+        if !types.iter().any(|t| *t == kind || (*t == "number" && kind == "integer")) {
+            return false;
+        }
+        match value {
+            Value::Array(items) => items.iter().all(|item| conforms(item, &schema["items"], root)),
+            Value::Object(fields) => {
+                let required = schema["required"].as_array().into_iter().flatten().filter_map(Value::as_str);
+                required.clone().all(|name| fields.contains_key(name))
+                    && fields.iter().all(|(name, field)| match schema["properties"].get(name) {
+                        Some(property) => conforms(field, property, root),
+                        None => schema["additionalProperties"].as_object().is_none_or(|_| {
+                            conforms(field, &schema["additionalProperties"], root)
+                        }),
+                    })
+            }
+            _ => true,
+        }
+    }
+
+    #[tokio::test]
+    async fn ticker_schema_matches_multi_id_output() {
+        let mock = crate::mock_server::TestServer::start(&[]).await;
+        let client = ApiClient::new(None).with_base_url(&mock.base_url);
+        let OutputData::Schema(schema) = execute(Some("ticker")).unwrap().data else { panic!("expected a schema") };
+
+        let ids = ["btc-bitcoin".to_string(), "eth-ethereum".to_string()];
+        let single = tickers::execute_detail(&client, &ids[0], "USD").await.unwrap();
+        let many = tickers::execute_many(&client, &ids, "USD", 2).await.unwrap();
+        let partial = multi::with_errors(Ok(many.clone()), vec![ItemError { id: "x".into(), error: "not found".into() }]).unwrap();
+        for output in [single, many, partial] {
+            let printed = serde_json::to_value(WrappedResponse { data: &output.data, _meta: &output.meta }).unwrap();
+            assert!(conforms(&printed, &schema.envelope, &schema.envelope), "{printed}");
+        }
+        let array = serde_json::json!([{"id": "btc-bitcoin"}]);
+        assert!(!conforms(&array, &schema.payload, &schema.payload));
+    }
+
+    #[test]
+    fn envelope_describes_meta() {
+        let output = execute(Some("ticker")).unwrap();
+        let OutputData::Schema(schema) = output.data else { panic!("expected a schema") };
+        let meta = serde_json::to_string(&schema.envelope).unwrap();
+        assert!(meta.contains("schema_version"));
+        assert_eq!(schema.payload["oneOf"][1]["type"], "array");
+        assert_eq!(schema.envelope["$defs"]["ResponseMeta"]["properties"]["errors"]["type"], "array");
+    }
+}
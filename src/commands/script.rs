@@ -0,0 +1,152 @@
+//! `script run`: Rhai scripts over API data. Scripts call bound functions
+//! such as `ticker("btc-bitcoin")`, which go through the shared client and
+//! return the usual response structs as maps, then `emit` a result that is
+//! rendered like any other command's output.
+
+use anyhow::{anyhow, Context, Result};
+use clap::Subcommand;
+use coinpaprika_cli::{ApiClient, HistoricalParams, Period, TickersParams};
+use rhai::module_resolvers::{DummyModuleResolver, FileModuleResolver};
+use rhai::{Dynamic, Engine, EvalAltResult, Scope};
+use serde::Serialize;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use super::{CommandOutput, OutputData};
+
+/// Upper bounds that keep a runaway script from hanging or exhausting memory.
+const MAX_OPERATIONS: u64 = 50_000_000;
+const MAX_COLLECTION_SIZE: usize = 1_000_000;
+const MAX_STRING_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(Subcommand)]
+pub enum ScriptCommand {
+    /// Run a Rhai script
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli script run movers.rhai\n  coinpaprika-cli script run report.rhai -o json -- btc-bitcoin eth-ethereum\n  coinpaprika-cli script run export.rhai --allow-fs")]
+    Run {
+        /// Script file
+        file: PathBuf,
+        /// Allow `read_file`, `write_file` and `import` of other scripts
+        #[arg(long)]
+        allow_fs: bool,
+        /// Arguments available to the script as `ARGS`
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+}
+
+type FnResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Runs synchronously; API calls block on the current runtime, so call this
+/// from `tokio::task::block_in_place`. `quotes` is the configured default
+/// for calls that don't pass their own.
+pub fn execute(client: Arc<ApiClient>, cmd: ScriptCommand, quotes: Option<&str>) -> Result<CommandOutput> {
+    let ScriptCommand::Run { file, allow_fs, args } = cmd;
+    let source = std::fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+
+    let emitted: Arc<Mutex<Option<Dynamic>>> = Arc::default();
+    let engine = engine(client, &file, allow_fs, quotes.map(str::to_string), emitted.clone());
+    let mut scope = Scope::new();
+    scope.push_constant("ARGS", args.into_iter().map(Dynamic::from).collect::<rhai::Array>());
+
+    let result = engine
+        .eval_with_scope::<Dynamic>(&mut scope, &source)
+        .map_err(|e| anyhow!("{}: {e}", file.display()))?;
+
+    // An explicit emit() wins; otherwise the script's final value is the output.
+    let output = emitted.lock().unwrap_or_else(|e| e.into_inner()).take().unwrap_or(result);
+    let value: serde_json::Value = rhai::serde::from_dynamic(&output).map_err(|e| anyhow!("Script output is not JSON-compatible: {e}"))?;
+    Ok(CommandOutput::new(OutputData::Script(value), ""))
+}
+
+fn engine(client: Arc<ApiClient>, file: &Path, allow_fs: bool, quotes: Option<String>, emitted: Arc<Mutex<Option<Dynamic>>>) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .disable_symbol("eval");
+    // stdout is reserved for the rendered output.
+    engine.on_print(|text| eprintln!("{text}"));
+    engine.on_debug(|text, _, pos| eprintln!("[{pos}] {text}"));
+
+    if allow_fs {
+        let base = file.parent().map(Path::to_path_buf).unwrap_or_default();
+        engine.set_module_resolver(FileModuleResolver::new_with_path(base));
+        engine.register_fn("read_file", |path: &str| -> FnResult<String> {
+            std::fs::read_to_string(path).map_err(|e| format!("read_file({path}): {e}").into())
+        });
+        engine.register_fn("write_file", |path: &str, text: &str| -> FnResult<()> {
+            std::fs::write(path, text).map_err(|e| format!("write_file({path}): {e}").into())
+        });
+    } else {
+        engine.set_module_resolver(DummyModuleResolver::new());
+    }
+
+    engine.register_fn("emit", move |value: Dynamic| {
+        *emitted.lock().unwrap_or_else(|e| e.into_inner()) = Some(value);
+    });
+
+    let c = client.clone();
+    engine.register_fn("global", move || call(c.global()));
+    let c = client.clone();
+    let default_quotes = quotes.clone().unwrap_or_else(|| "USD".to_string());
+    engine.register_fn("ticker", move |id: &str| call(c.ticker(id, &default_quotes)));
+    let c = client.clone();
+    engine.register_fn("ticker", move |id: &str, quotes: &str| call(c.ticker(id, quotes)));
+    let c = client.clone();
+    engine.register_fn("tickers", move |limit: rhai::INT| {
+        let limit = usize::try_from(limit).map_err(|_| format!("tickers({limit}): limit must be positive"))?;
+        let params = TickersParams::new().limit(limit);
+        call(c.tickers(&match &quotes {
+            Some(quotes) => params.quotes(quotes.as_str()),
+            None => params,
+        }))
+    });
+    let c = client.clone();
+    engine.register_fn("ohlcv", move |id: &str, start: &str| {
+        call(c.ohlcv_historical(id, &HistoricalParams::new(period("ohlcv", start)?)))
+    });
+    let c = client;
+    engine.register_fn("ohlcv", move |id: &str, start: &str, end: &str| {
+        call(c.ohlcv_historical(id, &HistoricalParams::new(period("ohlcv", start)?).end(period("ohlcv", end)?)))
+    });
+    engine
+}
+
+/// A date argument, as a script error if it doesn't parse.
+fn period(function: &str, value: &str) -> FnResult<Period> {
+    value.parse().map_err(|e| format!("{function}: {e}").into())
+}
+
+/// Block on an API call and convert the response struct to a Rhai map (or
+/// array of maps). API errors become script errors with a position.
+fn call<T: Serialize>(request: impl Future<Output = Result<T>>) -> FnResult<Dynamic> {
+    let data = tokio::runtime::Handle::current().block_on(request).map_err(|e| e.to_string())?;
+    rhai::serde::to_dynamic(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(source: &str, allow_fs: bool) -> Result<Dynamic, Box<EvalAltResult>> {
+        let engine = engine(Arc::new(ApiClient::new(None)), Path::new("test.rhai"), allow_fs, None, Arc::default());
+        engine.eval(source)
+    }
+
+    #[test]
+    fn sandbox_blocks_files_and_eval() {
+        assert!(run(r#"read_file("/etc/hostname")"#, false).is_err());
+        assert!(run(r#"import "other" as other; 1"#, false).is_err());
+        assert!(run(r#"eval("1 + 1")"#, false).is_err());
+        assert_eq!(run("[1, 2, 3].len()", false).unwrap().as_int().unwrap(), 3);
+    }
+
+    #[test]
+    fn runaway_scripts_are_stopped() {
+        assert!(run("loop {}", false).is_err());
+    }
+}
@@ -1,42 +1,183 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use chrono::Utc;
+use clap::Args;
+use coinpaprika_cli::ApiClient;
+use schemars::JsonSchema;
 use serde::Serialize;
-use std::time::Instant;
+use std::collections::BTreeMap;
+use std::time::Duration;
 
-use crate::client::ApiClient;
-use crate::output::OutputFormat;
+use super::{AlreadyReported, CommandOutput, OutputData};
+use crate::output::{self, OutputFormat, WrappedResponse};
 
-#[derive(Debug, Serialize)]
+/// Probed by default; `/key/info` is added when a key is set.
+const DEFAULT_ENDPOINTS: &[&str] = &["/global", "/tickers/btc-bitcoin", "/coins/btc-bitcoin/ohlcv/today", "/search?q=bitcoin"];
+
+#[derive(Args, Debug, Clone)]
+pub struct StatusArgs {
+    /// Endpoint to probe instead of the defaults, repeatable, e.g. /tickers/eth-ethereum
+    #[arg(short, long = "endpoint", value_name = "PATH")]
+    endpoints: Vec<String>,
+    /// Requests per endpoint
+    #[arg(short = 'n', long, default_value = "3", value_parser = clap::value_parser!(u32).range(1..=100))]
+    samples: u32,
+    /// Keep probing, printing a report every --every seconds; stops when nothing answers
+    #[arg(long)]
+    pub watch: bool,
+    /// Seconds between reports with --watch
+    #[arg(long, default_value = "30", requires = "watch", value_parser = clap::value_parser!(u64).range(1..))]
+    every: u64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct StatusResult {
     pub coinpaprika: ApiStatus,
     pub api_key_configured: bool,
+    pub checked_at: String,
+    pub endpoints: Vec<EndpointStatus>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct ApiStatus {
+    /// OK, DEGRADED (some requests failed) or ERROR (none succeeded).
     pub status: String,
+    /// Median latency over every request that got a response.
     pub response_time_ms: u128,
 }
 
-pub async fn execute(client: &ApiClient, output: OutputFormat, raw: bool) -> Result<()> {
-    let cp_start = Instant::now();
-    let cp_result: Result<serde_json::Value> = client.coinpaprika_get("/global", &[]).await;
-    let cp_time = cp_start.elapsed().as_millis();
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct EndpointStatus {
+    pub endpoint: String,
+    pub samples: u32,
+    pub p50_ms: Option<u128>,
+    pub p90_ms: Option<u128>,
+    pub p99_ms: Option<u128>,
+    /// Share of samples without a 2xx response, 0 to 1.
+    pub error_rate: f64,
+    /// Samples by HTTP status; `error` counts requests that got no response.
+    pub statuses: BTreeMap<String, u32>,
+    /// The endpoint answered 402: the current plan doesn't include it.
+    pub requires_upgrade: bool,
+}
+
+pub async fn execute(client: &ApiClient, args: &StatusArgs, api_key_configured: bool) -> Result<CommandOutput> {
+    if args.watch {
+        bail!("--watch only works when status is run on its own.");
+    }
+    Ok(probe_all(client, args, api_key_configured).await)
+}
+
+/// Print a report every `--every` seconds until interrupted, or until a
+/// round in which nothing answered. JSON reports are one object per line.
+pub async fn watch(client: &ApiClient, args: &StatusArgs, api_key_configured: bool, format: OutputFormat, raw: bool) -> Result<()> {
+    loop {
+        let result = probe_all(client, args, api_key_configured).await;
+        match format {
+            OutputFormat::Json if raw => println!("{}", serde_json::to_string(&result.data)?),
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string(&WrappedResponse { data: &result.data, _meta: &result.meta })?)
+            }
+            OutputFormat::Table => {
+                println!("{}", Utc::now().format("%Y-%m-%d %H:%M:%S UTC"));
+                output::render(&result, format, raw)?;
+                println!();
+            }
+            OutputFormat::Csv => output::render(&result, format, raw)?,
+        }
+        exit_status(&result)?;
+        tokio::time::sleep(Duration::from_secs(args.every)).await;
+    }
+}
+
+/// Fail the process, after the report is printed, if nothing succeeded.
+pub fn exit_status(output: &CommandOutput) -> Result<()> {
+    match &output.data {
+        OutputData::Status(result) if result.coinpaprika.status == "ERROR" => {
+            Err(AlreadyReported("No endpoint answered successfully".into()).into())
+        }
+        _ => Ok(()),
+    }
+}
+
+async fn probe_all(client: &ApiClient, args: &StatusArgs, api_key_configured: bool) -> CommandOutput {
+    let mut endpoints: Vec<String> = args.endpoints.clone();
+    if endpoints.is_empty() {
+        endpoints = DEFAULT_ENDPOINTS.iter().map(|e| e.to_string()).collect();
+        if api_key_configured {
+            endpoints.push("/key/info".into());
+        }
+    }
+    let endpoints = endpoints.iter().map(|e| if e.starts_with('/') { e.clone() } else { format!("/{e}") });
+    let results = futures::future::join_all(endpoints.map(|endpoint| probe_endpoint(client, endpoint, args.samples))).await;
 
-    let api_key_configured = crate::config::resolve_api_key(None).is_some();
+    let latencies: Vec<u128> = results.iter().flat_map(|(_, l)| l.iter().copied()).collect();
+    let endpoints: Vec<EndpointStatus> = results.into_iter().map(|(e, _)| e).collect();
+    let ok = |e: &EndpointStatus| e.statuses.iter().filter(|(s, _)| s.starts_with('2')).map(|(_, n)| n).sum::<u32>();
+    let succeeded: u32 = endpoints.iter().map(ok).sum();
+    // A 402 is the plan talking, not an outage.
+    let failed = endpoints.iter().any(|e| e.statuses.iter().any(|(s, _)| !s.starts_with('2') && s != "402"));
+    let status = match (succeeded, failed) {
+        (0, _) => "ERROR",
+        (_, true) => "DEGRADED",
+        _ => "OK",
+    };
 
     let result = StatusResult {
-        coinpaprika: ApiStatus {
-            status: if cp_result.is_ok() { "OK".into() } else { "ERROR".into() },
-            response_time_ms: cp_time,
-        },
+        coinpaprika: ApiStatus { status: status.into(), response_time_ms: percentile(&latencies, 50).unwrap_or(0) },
         api_key_configured,
+        checked_at: Utc::now().to_rfc3339(),
+        endpoints,
     };
+    CommandOutput::new(OutputData::Status(result), "/status")
+}
 
-    match output {
-        OutputFormat::Table => crate::output::status::print_status(&result),
-        OutputFormat::Json => {
-            crate::output::print_json_wrapped(&result, crate::output::ResponseMeta::coinpaprika("/status"), raw)?;
-        }
+/// Probe one endpoint `samples` times in a row. Also returns the latencies.
+async fn probe_endpoint(client: &ApiClient, endpoint: String, samples: u32) -> (EndpointStatus, Vec<u128>) {
+    let mut statuses = BTreeMap::new();
+    let mut latencies = Vec::new();
+    for _ in 0..samples {
+        let key = match client.probe(&endpoint).await {
+            Ok(probe) => {
+                latencies.push(probe.elapsed.as_millis());
+                probe.status.to_string()
+            }
+            Err(_) => "error".to_string(),
+        };
+        *statuses.entry(key).or_insert(0) += 1;
+    }
+    let ok: u32 = statuses.iter().filter(|(s, _)| s.starts_with('2')).map(|(_, n)| n).sum();
+    let status = EndpointStatus {
+        p50_ms: percentile(&latencies, 50),
+        p90_ms: percentile(&latencies, 90),
+        p99_ms: percentile(&latencies, 99),
+        error_rate: f64::from(samples - ok) / f64::from(samples),
+        requires_upgrade: statuses.contains_key("402"),
+        statuses,
+        samples,
+        endpoint,
+    };
+    (status, latencies)
+}
+
+/// Nearest-rank percentile.
+fn percentile(values: &[u128], p: usize) -> Option<u128> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let values: Vec<u128> = (1..=10).rev().collect();
+        assert_eq!(percentile(&values, 50), Some(5));
+        assert_eq!(percentile(&values, 90), Some(9));
+        assert_eq!(percentile(&values, 99), Some(10));
+        assert_eq!(percentile(&[7], 50), Some(7));
+        assert_eq!(percentile(&[], 50), None);
     }
-    Ok(())
 }
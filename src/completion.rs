@@ -0,0 +1,378 @@
+//! Completion for the interactive shell and for bash, zsh and fish, driven
+//! by the clap definition and the cached ID lists.
+//!
+//! The shell scripts stay static: they pass the words on the command line
+//! to the hidden `__complete` command, which answers from the same engine
+//! the shell uses, so new commands and freshly cached IDs need no new script.
+
+use clap::{Arg, Command, CommandFactory, ValueEnum};
+use coinpaprika_cli::client::FREE_BASE_URL;
+use coinpaprika_cli::index::{self, IdKind};
+use coinpaprika_cli::{config, Interval};
+
+use std::io::Write;
+
+use crate::Cli;
+
+/// Most IDs offered at once, best first, when little has been typed.
+const MAX_IDS: usize = 200;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ShellKind {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub value: String,
+    pub help: String,
+}
+
+/// What a word on the command line is, for highlighting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Command,
+    UnknownCommand,
+    Flag,
+    UnknownFlag,
+    /// A flag's value or a positional argument.
+    Value,
+}
+
+/// Where the words before the cursor leave off.
+struct Position<'a> {
+    command: &'a Command,
+    positionals: usize,
+    /// A flag still waiting for its value.
+    pending: Option<&'a Arg>,
+    /// IDs of the flags given so far.
+    seen: Vec<&'a str>,
+}
+
+pub struct Engine {
+    root: Command,
+    plugins: Vec<String>,
+    /// The API whose cached IDs are offered.
+    base_url: String,
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Self::with_command(Cli::command())
+    }
+
+    /// An engine for `root`, e.g. the CLI with the shell's built-ins added.
+    pub fn with_command(mut root: Command) -> Self {
+        // Copies the global flags into every subcommand.
+        root.build();
+        let plugins = crate::commands::plugins::discover().into_iter().map(|p| p.name).collect();
+        Self { root, plugins, base_url: FREE_BASE_URL.to_string() }
+    }
+
+    /// Offer IDs cached from the API at `base_url` instead of the official one.
+    pub fn set_base_url(&mut self, base_url: &str) {
+        self.base_url = base_url.to_string();
+    }
+
+    /// Candidates for `current`, the word being typed after `words`.
+    pub fn complete(&self, words: &[String], current: &str) -> Vec<Candidate> {
+        let (position, _) = self.walk(words);
+        let command = position.command;
+
+        if let Some(arg) = position.pending {
+            return matching(self.values(arg), current);
+        }
+        if let Some((flag, value)) = current.strip_prefix("--").and_then(|c| c.split_once('=')) {
+            let Some(arg) = command.get_arguments().find(|a| a.get_long() == Some(flag)) else {
+                return Vec::new();
+            };
+            return matching(self.values(arg), value)
+                .into_iter()
+                .map(|c| Candidate { value: format!("--{flag}={}", c.value), help: c.help })
+                .collect();
+        }
+        if current.starts_with('-') {
+            let flags = command
+                .get_arguments()
+                .filter(|a| !a.is_positional() && !a.is_hide_set())
+                .filter_map(|a| a.get_long().map(|long| Candidate { value: format!("--{long}"), help: help(a) }));
+            return matching(flags.collect(), current);
+        }
+
+        let mut candidates = Vec::new();
+        if position.positionals == 0 {
+            candidates.extend(
+                command
+                    .get_subcommands()
+                    .filter(|c| !c.is_hide_set())
+                    .map(|c| Candidate { value: c.get_name().to_string(), help: about(c) }),
+            );
+            if std::ptr::eq(command, &self.root) {
+                candidates.extend(self.plugins.iter().map(|name| Candidate { value: name.clone(), help: "plugin".into() }));
+            }
+        }
+        if let Some(arg) = positional(command, position.positionals) {
+            candidates.extend(self.values(arg));
+        }
+        matching(candidates, current)
+    }
+
+    /// Greyed-out text to show after the cursor: the rest of the only way
+    /// `current` can go on, or what the command expects next.
+    pub fn hint(&self, words: &[String], current: &str) -> Option<String> {
+        if !current.is_empty() {
+            let candidates = self.complete(words, current);
+            let common = common_prefix(candidates.iter().map(|c| c.value.as_str()))?;
+            return common.strip_prefix(current).filter(|rest| !rest.is_empty()).map(str::to_string);
+        }
+        if words.is_empty() {
+            return None;
+        }
+        let (position, _) = self.walk(words);
+        if let Some(arg) = position.pending {
+            return Some(placeholder(arg));
+        }
+        if let Some(arg) = positional(position.command, position.positionals).filter(|a| a.is_required_set()) {
+            if position.positionals < position.command.get_positionals().count() {
+                return Some(placeholder(arg));
+            }
+        }
+        let missing = position
+            .command
+            .get_arguments()
+            .find(|a| !a.is_positional() && a.is_required_set() && !position.seen.contains(&a.get_id().as_str()))?;
+        Some(format!("--{} {}", missing.get_long()?, placeholder(missing)))
+    }
+
+    /// The role of each word, for highlighting.
+    pub fn roles(&self, words: &[String]) -> Vec<Role> {
+        self.walk(words).1
+    }
+
+    fn walk<'a>(&'a self, words: &[String]) -> (Position<'a>, Vec<Role>) {
+        let mut position = Position { command: &self.root, positionals: 0, pending: None, seen: Vec::new() };
+        let mut roles = Vec::with_capacity(words.len());
+        let mut only_values = false;
+        for word in words {
+            if position.pending.take().is_some() {
+                roles.push(Role::Value);
+                continue;
+            }
+            let command = position.command;
+            if word == "--" && !only_values {
+                only_values = true;
+                roles.push(Role::Flag);
+                continue;
+            }
+            if !only_values && word.starts_with('-') && word.len() > 1 {
+                let arg = match word.strip_prefix("--") {
+                    Some(long) => {
+                        let (name, inline) = long.split_once('=').map_or((long, false), |(name, _)| (name, true));
+                        command.get_arguments().find(|a| a.get_long() == Some(name)).map(|a| (a, inline))
+                    }
+                    None => {
+                        let short = word.chars().nth(1).unwrap_or_default();
+                        command.get_arguments().find(|a| a.get_short() == Some(short)).map(|a| (a, word.len() > 2))
+                    }
+                };
+                match arg {
+                    Some((arg, inline)) => {
+                        position.seen.push(arg.get_id().as_str());
+                        if arg.get_action().takes_values() && !inline {
+                            position.pending = Some(arg);
+                        }
+                        roles.push(Role::Flag);
+                    }
+                    None => roles.push(Role::UnknownFlag),
+                }
+                continue;
+            }
+            if position.positionals == 0 && !only_values {
+                if let Some(sub) = command.find_subcommand(word) {
+                    position = Position { command: sub, positionals: 0, pending: None, seen: Vec::new() };
+                    roles.push(Role::Command);
+                    continue;
+                }
+                let is_plugin = std::ptr::eq(command, &self.root) && self.plugins.contains(word);
+                if command.has_subcommands() && command.get_positionals().next().is_none() {
+                    roles.push(if is_plugin { Role::Command } else { Role::UnknownCommand });
+                    position.positionals += 1;
+                    continue;
+                }
+            }
+            position.positionals += 1;
+            roles.push(Role::Value);
+        }
+        (position, roles)
+    }
+
+    /// What `arg` can be set to: its fixed values, intervals, profiles or IDs.
+    fn values(&self, arg: &Arg) -> Vec<Candidate> {
+        let fixed: Vec<Candidate> = arg
+            .get_possible_values()
+            .iter()
+            .filter(|v| !v.is_hide_set())
+            .map(|v| Candidate { value: v.get_name().to_string(), help: v.get_help().map(|h| h.to_string()).unwrap_or_default() })
+            .collect();
+        if !fixed.is_empty() {
+            return fixed;
+        }
+        let plain = |values: Vec<String>| values.into_iter().map(|value| Candidate { value, help: String::new() }).collect();
+        match arg.get_id().as_str() {
+            "interval" => plain(Interval::ALL.iter().map(|i| i.to_string()).collect()),
+            "profile" => plain(config::profile_names()),
+            id => match id_kind(id) {
+                Some(kind) => plain(index::cached_ids(kind, &self.base_url)),
+                None => Vec::new(),
+            },
+        }
+    }
+}
+
+/// The list an argument's IDs come from, by its clap ID.
+fn id_kind(id: &str) -> Option<IdKind> {
+    match id {
+        "coin_id" | "coin_ids" | "base_id" | "quote_id" => Some(IdKind::Coin),
+        "exchange_id" => Some(IdKind::Exchange),
+        "tag_id" => Some(IdKind::Tag),
+        "platform_id" => Some(IdKind::Platform),
+        _ => None,
+    }
+}
+
+/// The positional argument at `index`, or the last one if it takes many.
+fn positional(command: &Command, index: usize) -> Option<&Arg> {
+    let positionals: Vec<&Arg> = command.get_positionals().collect();
+    positionals.get(index).copied().or_else(|| {
+        positionals.last().copied().filter(|a| a.get_num_args().is_some_and(|n| n.max_values() > 1))
+    })
+}
+
+/// Candidates starting with `prefix`, ignoring case; at most [`MAX_IDS`].
+fn matching(candidates: Vec<Candidate>, prefix: &str) -> Vec<Candidate> {
+    let prefix = prefix.to_lowercase();
+    candidates.into_iter().filter(|c| c.value.to_lowercase().starts_with(&prefix)).take(MAX_IDS).collect()
+}
+
+fn common_prefix<'a>(mut values: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let first = values.next()?;
+    let len = values.fold(first.len(), |len, value| {
+        first[..len].char_indices().zip(value.chars()).find(|((_, a), b)| a != b).map_or(len.min(value.len()), |((i, _), _)| i)
+    });
+    Some(&first[..len])
+}
+
+fn placeholder(arg: &Arg) -> String {
+    let name = arg.get_value_names().and_then(|names| names.first().map(|n| n.to_string()));
+    format!("<{}>", name.unwrap_or_else(|| arg.get_id().as_str().to_uppercase()))
+}
+
+fn help(arg: &Arg) -> String {
+    arg.get_help().map(|h| h.to_string()).unwrap_or_default()
+}
+
+fn about(command: &Command) -> String {
+    command.get_about().map(|h| h.to_string()).unwrap_or_default()
+}
+
+/// `__complete`: one candidate per line, `value<TAB>help`. The last word is
+/// the one being completed, possibly empty.
+pub fn print_candidates(words: &[String], base_url: &str) {
+    let (current, words) = words.split_last().map_or(("", &[][..]), |(current, words)| (current.as_str(), words));
+    let mut out = std::io::stdout().lock();
+    let mut engine = Engine::new();
+    engine.set_base_url(base_url);
+    for candidate in engine.complete(words, current) {
+        // The shell may stop reading early; that isn't an error.
+        if writeln!(out, "{}\t{}", candidate.value, candidate.help.lines().next().unwrap_or_default()).is_err() {
+            break;
+        }
+    }
+}
+
+pub fn script(shell: ShellKind) -> &'static str {
+    match shell {
+        ShellKind::Bash => BASH,
+        ShellKind::Zsh => ZSH,
+        ShellKind::Fish => FISH,
+    }
+}
+
+const BASH: &str = r#"# coinpaprika-cli completion for bash
+# Load with: source <(coinpaprika-cli completions bash)
+_coinpaprika_cli() {
+    local IFS=$'\n'
+    local cur=${COMP_WORDS[COMP_CWORD]}
+    COMPREPLY=($(coinpaprika-cli __complete -- "${COMP_WORDS[@]:1:COMP_CWORD-1}" "$cur" 2>/dev/null | cut -f1))
+}
+complete -o default -F _coinpaprika_cli coinpaprika-cli
+"#;
+
+const ZSH: &str = r#"#compdef coinpaprika-cli
+# coinpaprika-cli completion for zsh
+# Load with: source <(coinpaprika-cli completions zsh), or save as _coinpaprika-cli on your $fpath
+_coinpaprika_cli() {
+    local -a candidates
+    local line
+    for line in "${(@f)$(coinpaprika-cli __complete -- "${(@)words[2,CURRENT]}" 2>/dev/null)}"; do
+        [[ -n $line ]] && candidates+=("${${line%%$'\t'*}//:/\\:}:${line#*$'\t'}")
+    done
+    _describe 'coinpaprika-cli' candidates
+}
+if [[ $funcstack[1] == _coinpaprika-cli ]]; then
+    _coinpaprika_cli "$@"
+else
+    compdef _coinpaprika_cli coinpaprika-cli
+fi
+"#;
+
+const FISH: &str = r#"# coinpaprika-cli completion for fish
+# Load with: coinpaprika-cli completions fish | source
+function __coinpaprika_cli_complete
+    set -l words (commandline -opc)
+    set -e words[1]
+    coinpaprika-cli __complete -- $words (commandline -ct) 2>/dev/null
+end
+complete -c coinpaprika-cli -f -a '(__coinpaprika_cli_complete)'
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn values(engine: &Engine, line: &str, current: &str) -> Vec<String> {
+        engine.complete(&words(line), current).into_iter().map(|c| c.value).collect()
+    }
+
+    #[test]
+    fn completes_commands_flags_and_values() {
+        let engine = Engine::new();
+        assert!(values(&engine, "", "ticker").contains(&"ticker-history".to_string()));
+        assert!(values(&engine, "ohlcv btc-bitcoin", "--st").contains(&"--start".to_string()));
+        assert!(values(&engine, "ohlcv btc-bitcoin", "--").contains(&"--output".to_string()));
+        assert_eq!(values(&engine, "ohlcv btc-bitcoin --interval", "1"), ["10m", "15m", "1h", "12h", "1d", "14d"]);
+        assert_eq!(values(&engine, "tickers -o", "j"), ["json"]);
+        assert_eq!(values(&engine, "tickers", "--output=c"), ["--output=csv"]);
+        assert_eq!(values(&engine, "config", "set-"), ["set-key"]);
+    }
+
+    #[test]
+    fn hints_and_roles() {
+        let engine = Engine::new();
+        assert_eq!(engine.hint(&[], "ticker-h").as_deref(), Some("istory"));
+        assert_eq!(engine.hint(&words("ohlcv"), "").as_deref(), Some("<COIN_ID>"));
+        assert_eq!(engine.hint(&words("ohlcv btc-bitcoin"), "").as_deref(), Some("--start <START>"));
+        assert_eq!(engine.hint(&words("ohlcv btc-bitcoin --start 7d"), ""), None);
+        assert_eq!(
+            engine.roles(&words("ohlcv btc-bitcoin --start 7d --bogus")),
+            [Role::Command, Role::Value, Role::Flag, Role::Value, Role::UnknownFlag]
+        );
+        assert_eq!(engine.roles(&words("config nope")), [Role::Command, Role::UnknownCommand]);
+    }
+}
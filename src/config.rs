@@ -1,40 +1,799 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::fs;
-use std::path::PathBuf;
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::str::Chars;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::secret;
+
+/// The profile name that stands for the top-level settings, so
+/// `config profile use default` switches profiles off.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The config file's current `version`. Files without one are version 1;
+/// older files are migrated forward when loaded.
+pub const CONFIG_VERSION: u64 = 2;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Config {
+    #[serde(default)]
+    pub version: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
+    /// Command that prints the key, e.g. `pass show coinpaprika`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_command: Option<String>,
+    /// Passphrase-encrypted key file written by `config set-key --encrypt`,
+    /// relative to the config directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_file: Option<String>,
+    /// Profile used when neither `--profile` nor `COINPAPRIKA_PROFILE` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Flag defaults: `quotes` for every command, `tickers.limit` for one.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub defaults: BTreeMap<String, String>,
 }
 
-pub fn config_dir() -> Result<PathBuf> {
+/// A named set of settings, e.g. one per API key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Default for `--quotes`, e.g. `EUR` or `USD,BTC`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quotes: Option<String>,
+    /// Default for `--output`: `table`, `json` or `csv`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+/// How a key is kept: in the config, behind a command, or encrypted.
+#[derive(Debug, Clone)]
+pub enum StoredKey {
+    Plain(String),
+    Command(String),
+    EncryptedFile(String),
+}
+
+impl Config {
+    pub fn profile(&self, name: &str) -> Result<&Profile> {
+        match self.profiles.get(name) {
+            Some(profile) => Ok(profile),
+            None => {
+                let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                let known = if known.is_empty() { "none yet".to_string() } else { known.join(", ") };
+                bail!(
+                    "Unknown profile '{name}'. Profiles: {known}\n\n\
+                     Add it with:  coinpaprika-cli config profile add {name} --key <KEY>\n\
+                     List them:    coinpaprika-cli config profile list"
+                )
+            }
+        }
+    }
+}
+
+/// Where a setting came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Flag(&'static str),
+    Env(&'static str),
+    Profile(String),
+    ConfigFile,
+    /// `api_key_command`, from a profile or the top-level config.
+    KeyCommand { command: String, profile: Option<String> },
+    /// `api_key_file`, from a profile or the top-level config.
+    EncryptedFile { path: String, profile: Option<String> },
+    /// `set` in the interactive shell.
+    Shell,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Flag(flag) => write!(f, "CLI flag ({flag})"),
+            Source::Env(var) => write!(f, "Environment variable ({var})"),
+            Source::Profile(name) => write!(f, "Profile '{name}' ({})", config_path_label()),
+            Source::ConfigFile => write!(f, "Config file ({})", config_path_label()),
+            Source::KeyCommand { command, profile } => write!(f, "Command `{command}`{}", in_profile(profile)),
+            Source::EncryptedFile { path, profile } => write!(f, "Encrypted key file {path}{}", in_profile(profile)),
+            Source::Shell => write!(f, "Shell session (set)"),
+        }
+    }
+}
+
+fn in_profile(profile: &Option<String>) -> String {
+    match profile {
+        Some(name) => format!(" (profile '{name}')"),
+        None => " (config file)".into(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setting {
+    pub value: String,
+    pub source: Source,
+}
+
+/// Settings after applying, in order: CLI flags, environment variables, the
+/// selected profile and the top-level config. `None` means the built-in
+/// default applies.
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    pub profile: Option<Setting>,
+    pub api_key: Option<Setting>,
+    /// A key that has to be fetched or decrypted first; see [`Settings::unlock`].
+    pub locked_key: Option<Setting>,
+    pub base_url: Option<Setting>,
+    /// `--quotes` and `--output` defaults, from the profile or else `defaults`.
+    pub quotes: Option<Setting>,
+    pub output: Option<Setting>,
+    /// The config's `defaults` section.
+    pub defaults: BTreeMap<String, String>,
+}
+
+impl Settings {
+    /// Resolve settings for the given flags. Fails if the selected profile
+    /// does not exist.
+    pub fn resolve(cli_profile: Option<&str>, cli_key: Option<&str>, cli_base_url: Option<&str>) -> Result<Self> {
+        let requested = first_set([
+            (cli_profile.map(str::to_string), Source::Flag("--profile")),
+            (std::env::var("COINPAPRIKA_PROFILE").ok(), Source::Env("COINPAPRIKA_PROFILE")),
+        ]);
+        // A broken config file shouldn't stop commands that don't need it.
+        let config = match &requested {
+            Some(_) => load_config()?,
+            None => load_config().unwrap_or_default(),
+        };
+        let active = config.active_profile.clone().map(|value| Setting { value, source: Source::ConfigFile });
+        let profile = requested.or(active);
+        let selected = match &profile {
+            Some(setting) if setting.value != DEFAULT_PROFILE => Some((setting.value.as_str(), config.profile(&setting.value)?)),
+            _ => None,
+        };
+        let from_profile = |field: fn(&Profile) -> &Option<String>| {
+            let (name, profile) = selected?;
+            first_set([(field(profile).clone(), Source::Profile(name.to_string()))])
+        };
+
+        let mut api_key = first_set([
+            (cli_key.map(str::to_string), Source::Flag("--api-key")),
+            (std::env::var("COINPAPRIKA_API_KEY").ok(), Source::Env("COINPAPRIKA_API_KEY")),
+        ]);
+        let mut locked_key = None;
+        if api_key.is_none() {
+            // A profile without a key is a free-tier profile.
+            let (plain, command, file, profile) = match selected {
+                Some((name, p)) => (&p.api_key, &p.api_key_command, &p.api_key_file, Some(name.to_string())),
+                None => (&config.api_key, &config.api_key_command, &config.api_key_file, None),
+            };
+            api_key = match &profile {
+                Some(name) => first_set([(plain.clone(), Source::Profile(name.clone()))]),
+                None => first_set([(plain.clone(), Source::ConfigFile)]),
+            };
+            if api_key.is_none() {
+                locked_key = first_set([
+                    (command.clone(), Source::KeyCommand { command: command.clone().unwrap_or_default(), profile: profile.clone() }),
+                    (file.clone(), Source::EncryptedFile { path: key_file_path(file.as_deref().unwrap_or_default()).display().to_string(), profile }),
+                ]);
+            }
+        }
+        let base_url = first_set([
+            (cli_base_url.map(str::to_string), Source::Flag("--base-url")),
+            (std::env::var("COINPAPRIKA_BASE_URL").ok(), Source::Env("COINPAPRIKA_BASE_URL")),
+        ])
+        .or_else(|| from_profile(|p| &p.base_url));
+
+        let from_defaults = |key: &str| first_set([(config.defaults.get(key).cloned(), Source::ConfigFile)]);
+
+        Ok(Settings {
+            api_key,
+            locked_key,
+            base_url,
+            quotes: from_profile(|p| &p.quotes).or_else(|| from_defaults("quotes")),
+            output: from_profile(|p| &p.output).or_else(|| from_defaults("output")),
+            profile,
+            defaults: config.defaults.clone(),
+        })
+    }
+
+    pub fn api_key(&self) -> Option<&str> {
+        self.api_key.as_ref().map(|s| s.value.as_str())
+    }
+
+    /// The selected profile, unless it is the top-level default.
+    pub fn profile_name(&self) -> Option<&str> {
+        self.profile.as_ref().map(|s| s.value.as_str()).filter(|name| *name != DEFAULT_PROFILE)
+    }
+
+    pub fn key_source(&self) -> String {
+        match (&self.api_key, &self.locked_key) {
+            (Some(setting), _) => setting.source.to_string(),
+            (None, Some(locked)) => format!("{} (not unlocked)", locked.source),
+            (None, None) => "Not set (using free tier)".into(),
+        }
+    }
+
+    /// Fetch a key kept behind `api_key_command` or in an encrypted file.
+    /// `passphrase` is asked only if `COINPAPRIKA_PASSPHRASE` is unset. Keys
+    /// are remembered for the rest of the process, e.g. a shell session.
+    pub fn unlock(&mut self, passphrase: impl FnOnce() -> Result<String>) -> Result<()> {
+        static UNLOCKED: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+        let Some(locked) = self.locked_key.take() else {
+            return Ok(());
+        };
+        let cache_key = locked.source.to_string();
+        let cached = UNLOCKED.lock().unwrap_or_else(|e| e.into_inner()).get(&cache_key).cloned();
+        let key = match (cached, &locked.source) {
+            (Some(key), _) => key,
+            (None, Source::KeyCommand { command, .. }) => secret::run_key_command(command)?,
+            (None, Source::EncryptedFile { path, .. }) => {
+                let contents = fs::read_to_string(path).with_context(|| format!("Failed to read encrypted key file {path}"))?;
+                let passphrase = match std::env::var(secret::PASSPHRASE_ENV) {
+                    Ok(p) if !p.is_empty() => p,
+                    _ => passphrase()?,
+                };
+                secret::decrypt(&contents, &passphrase)?
+            }
+            (None, _) => locked.value.clone(),
+        };
+        UNLOCKED.lock().unwrap_or_else(|e| e.into_inner()).insert(cache_key, key.clone());
+        self.api_key = Some(Setting { value: key, source: locked.source });
+        Ok(())
+    }
+}
+
+/// The first non-empty value, with where it came from.
+fn first_set<const N: usize>(candidates: [(Option<String>, Source); N]) -> Option<Setting> {
+    candidates
+        .into_iter()
+        .find_map(|(value, source)| value.filter(|v| !v.is_empty()).map(|value| Setting { value, source }))
+}
+
+/// Everything used to live here; still the default when the XDG variables
+/// are unset.
+fn legacy_dir() -> Result<PathBuf> {
     let home = dirs::home_dir().context("Could not determine home directory")?;
     Ok(home.join(".coinpaprika"))
 }
 
+/// `$VAR/coinpaprika`, if the XDG variable is set to an absolute path.
+fn xdg_dir(var: &str) -> Option<PathBuf> {
+    let base = std::env::var_os(var).map(PathBuf::from).filter(|p| p.is_absolute())?;
+    Some(base.join("coinpaprika"))
+}
+
+/// The interactive shell's line history, in [`state_dir`].
+pub const SHELL_HISTORY_FILE: &str = "shell_history";
+
+/// State files such as history, moved over from the legacy directory.
+const STATE_FILES: &[&str] = &[crate::usage::USAGE_FILE, SHELL_HISTORY_FILE];
+
+/// State files moved over from the legacy directory.
+fn is_state_file(name: &str) -> bool {
+    STATE_FILES.contains(&name)
+}
+
+/// Config files moved over from the legacy directory.
+fn is_config_file(name: &str) -> bool {
+    name == "config.json" || (name.starts_with("key") && name.ends_with(".enc"))
+}
+
+/// The config file and encrypted keys: `$XDG_CONFIG_HOME/coinpaprika`, or
+/// `~/.coinpaprika`.
+pub fn config_dir() -> Result<PathBuf> {
+    let legacy = legacy_dir()?;
+    Ok(match xdg_dir("XDG_CONFIG_HOME") {
+        Some(dir) => settled(legacy, dir, is_config_file),
+        None => legacy,
+    })
+}
+
+/// Cached API lists: `$XDG_CACHE_HOME/coinpaprika`, or `~/.coinpaprika/cache`.
+pub fn cache_dir() -> Result<PathBuf> {
+    let legacy = legacy_dir()?.join("cache");
+    Ok(match xdg_dir("XDG_CACHE_HOME") {
+        Some(dir) => settled(legacy, dir, |_| true),
+        None => legacy,
+    })
+}
+
+/// Files the CLI keeps between runs: `$XDG_STATE_HOME/coinpaprika`, or
+/// `~/.coinpaprika`.
+pub fn state_dir() -> Result<PathBuf> {
+    let legacy = legacy_dir()?;
+    Ok(match xdg_dir("XDG_STATE_HOME") {
+        Some(dir) => settled(legacy, dir, is_state_file),
+        None => legacy,
+    })
+}
+
+/// Move files from `~/.coinpaprika` into the XDG directories that are set
+/// and don't exist yet. Run once at startup, before anything is read.
+pub fn adopt_legacy_dirs() {
+    let Ok(legacy) = legacy_dir() else {
+        return;
+    };
+    // The cache goes first, so the last move can remove the emptied legacy directory.
+    if let Some(dir) = xdg_dir("XDG_CACHE_HOME") {
+        adopt_legacy(&legacy.join("cache"), dir, |_| true);
+    }
+    if let Some(dir) = xdg_dir("XDG_CONFIG_HOME") {
+        adopt_legacy(&legacy, dir, is_config_file);
+    }
+    if let Some(dir) = xdg_dir("XDG_STATE_HOME") {
+        adopt_legacy(&legacy, dir, is_state_file);
+    }
+}
+
+/// The directory in use: `dir`, unless it doesn't exist yet and the legacy
+/// directory still holds files `keep` selects, i.e. they couldn't be moved.
+fn settled(legacy: PathBuf, dir: PathBuf, keep: impl Fn(&str) -> bool) -> PathBuf {
+    if dir.exists() || legacy_files(&legacy, keep).is_none_or(|names| names.is_empty()) {
+        dir
+    } else {
+        legacy
+    }
+}
+
+/// The names in `legacy` that `keep` selects, or `None` if it can't be read.
+fn legacy_files(legacy: &Path, keep: impl Fn(&str) -> bool) -> Option<Vec<std::ffi::OsString>> {
+    let entries = fs::read_dir(legacy).ok()?;
+    Some(
+        entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name())
+            .filter(|name| name.to_str().is_some_and(&keep))
+            .collect(),
+    )
+}
+
+/// Move the files `keep` selects from the legacy directory into `dir`, if
+/// `dir` doesn't exist yet. If any of them can't be moved, everything
+/// stays where it was and the legacy directory is used instead.
+fn adopt_legacy(legacy: &Path, dir: PathBuf, keep: impl Fn(&str) -> bool) -> PathBuf {
+    if dir.exists() || !legacy.is_dir() {
+        return dir;
+    }
+    let Some(names) = legacy_files(legacy, keep) else {
+        return legacy.to_path_buf();
+    };
+    if names.is_empty() {
+        return dir;
+    }
+    if fs::create_dir_all(&dir).is_err() {
+        return legacy.to_path_buf();
+    }
+    for (i, name) in names.iter().enumerate() {
+        if fs::rename(legacy.join(name), dir.join(name)).is_err() {
+            for moved in &names[..i] {
+                let _ = fs::rename(dir.join(moved), legacy.join(moved));
+            }
+            let _ = fs::remove_dir(&dir);
+            return legacy.to_path_buf();
+        }
+    }
+    // Only succeeds once the legacy directory is empty.
+    let _ = fs::remove_dir(legacy);
+    dir
+}
+
 pub fn config_path() -> Result<PathBuf> {
     Ok(config_dir()?.join("config.json"))
 }
 
-#[allow(dead_code)]
-pub fn config_exists() -> bool {
-    config_path().map(|p| p.exists()).unwrap_or(false)
+/// The config file's path for messages, with the home directory as `~`.
+pub fn config_path_label() -> String {
+    let Ok(path) = config_path() else {
+        return "config.json".into();
+    };
+    match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(|rest| Path::new("~").join(rest))) {
+        Some(short) => short.display().to_string(),
+        None => path.display().to_string(),
+    }
+}
+
+/// Where an `api_key_file` value points; relative paths are in the config
+/// directory.
+fn key_file_path(stored: &str) -> PathBuf {
+    config_dir().map(|dir| dir.join(stored)).unwrap_or_else(|_| PathBuf::from(stored))
+}
+
+/// The profile names in the config file, read without migrating or saving
+/// it, for completion. Empty if there is no readable file.
+pub fn profile_names() -> Vec<String> {
+    let Some(text) = config_path().ok().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    match serde_json::from_str::<Value>(&text) {
+        Ok(Value::Object(mut config)) => match config.remove("profiles") {
+            Some(Value::Object(profiles)) => {
+                let mut names: Vec<String> = profiles.into_iter().map(|(name, _)| name).collect();
+                names.sort();
+                names
+            }
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Set by [`read_only`].
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Keep [`load_config`] from rewriting a file it migrates, for shell
+/// completion, which runs on every Tab and must not write files.
+pub fn read_only() {
+    READ_ONLY.store(true, Ordering::Relaxed);
 }
 
+/// Load the config file, migrating it to [`CONFIG_VERSION`] if it is older.
 pub fn load_config() -> Result<Config> {
     let path = config_path()?;
     if !path.exists() {
-        return Ok(Config { api_key: None });
+        return Ok(Config::default());
     }
     let contents = fs::read_to_string(&path)
         .with_context(|| format!("Failed to read config file at {}", path.display()))?;
-    let config: Config = serde_json::from_str(&contents)
-        .with_context(|| "Failed to parse config file")?;
+    let invalid = |e: serde_json::Error| {
+        anyhow!(
+            "Invalid config file {}: {e}\n\n\
+             Find the problems with:  coinpaprika-cli config validate",
+            path.display()
+        )
+    };
+    let mut value: Value = serde_json::from_str(&contents).map_err(invalid)?;
+    let migrated = migrate(&mut value, &[legacy_dir()?, config_dir()?])?;
+    let config: Config = serde_json::from_value(value).map_err(invalid)?;
+    if migrated && !READ_ONLY.load(Ordering::Relaxed) {
+        // Reading still works if the file can't be rewritten.
+        let _ = save_config(&config);
+    }
     Ok(config)
 }
 
-pub fn save_api_key(key: &str) -> Result<()> {
+/// A migration from one config version to the next. `key_dirs` are the
+/// directories key files may have been written to.
+type Migration = fn(&mut Map<String, Value>, key_dirs: &[PathBuf]) -> Result<()>;
+
+/// `MIGRATIONS[i]` upgrades a version `i + 1` file to version `i + 2`.
+const MIGRATIONS: [Migration; (CONFIG_VERSION - 1) as usize] = [v1_to_v2];
+
+/// Bring a config file up to [`CONFIG_VERSION`]. Returns whether it changed.
+fn migrate(value: &mut Value, key_dirs: &[PathBuf]) -> Result<bool> {
+    let Some(config) = value.as_object_mut() else {
+        bail!("Invalid config file: expected a JSON object");
+    };
+    let version = match config.get("version") {
+        None => 1,
+        Some(v) => v.as_u64().context("Invalid config file: `version` must be a number")?.max(1),
+    };
+    if version > CONFIG_VERSION {
+        bail!(
+            "The config file is version {version}, written by a newer coinpaprika-cli; this one reads up to version {CONFIG_VERSION}.\n\
+             Upgrade coinpaprika-cli, or move the config file aside."
+        );
+    }
+    if version == CONFIG_VERSION && config.contains_key("version") {
+        return Ok(false);
+    }
+    for migration in &MIGRATIONS[version as usize - 1..] {
+        migration(config, key_dirs)?;
+    }
+    config.insert("version".into(), CONFIG_VERSION.into());
+    Ok(true)
+}
+
+/// Version 2 keeps encrypted key files relative to the config directory, so
+/// the directory can move, and no longer writes `"api_key": null`.
+fn v1_to_v2(config: &mut Map<String, Value>, key_dirs: &[PathBuf]) -> Result<()> {
+    if config.get("api_key").is_some_and(Value::is_null) {
+        config.remove("api_key");
+    }
+    let relative = |section: &mut Map<String, Value>| {
+        if let Some(Value::String(stored)) = section.get_mut("api_key_file") {
+            let path = Path::new(stored.as_str());
+            if let (Some(dir), Some(name)) = (path.parent(), path.file_name()) {
+                if key_dirs.iter().any(|d| d == dir) {
+                    *stored = name.to_string_lossy().into_owned();
+                }
+            }
+        }
+    };
+    relative(config);
+    if let Some(Value::Object(profiles)) = config.get_mut("profiles") {
+        profiles.values_mut().filter_map(Value::as_object_mut).for_each(relative);
+    }
+    Ok(())
+}
+
+/// A problem found by [`validate`], at a 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Problem {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+const TOP_LEVEL_KEYS: &[&str] =
+    &["version", "api_key", "api_key_command", "api_key_file", "active_profile", "profiles", "defaults"];
+const PROFILE_KEYS: &[&str] = &["api_key", "api_key_command", "api_key_file", "base_url", "quotes", "output"];
+
+/// Check config file text without loading it: JSON syntax, unknown keys and
+/// values of the wrong type. `check_value` vets flag defaults, e.g.
+/// `("tickers.limit", "50")`, and a profile's `quotes` and `output`.
+pub fn validate(text: &str, check_value: impl Fn(&str, &str) -> Result<()>) -> Vec<Problem> {
+    let root: Value = match serde_json::from_str(text) {
+        Ok(root) => root,
+        Err(e) => {
+            // serde_json appends " at line L column C"; the position has its own fields.
+            let message = e.to_string();
+            let message = message.split(" at line ").next().unwrap_or_default();
+            return vec![Problem { line: e.line(), column: e.column(), message: format!("Invalid JSON: {message}") }];
+        }
+    };
+    let positions = key_positions(text);
+    let mut problems = Vec::new();
+    let mut report = |path: &[&str], message: String| {
+        let path: Vec<String> = path.iter().map(|p| p.to_string()).collect();
+        let (line, column) = positions.get(&path).copied().unwrap_or((1, 1));
+        problems.push(Problem { line, column, message });
+    };
+    let wrong_type = |key: &str, expected: &str, found: &Value| format!("`{key}` should be {expected}, not {}", type_name(found));
+    let unknown = |key: &str, known: &[&str]| format!("Unknown key `{key}`. Expected one of: {}", known.join(", "));
+
+    let Some(top) = root.as_object() else {
+        report(&[], format!("The config should be a JSON object, not {}", type_name(&root)));
+        return problems;
+    };
+    for (key, value) in top {
+        let path = [key.as_str()];
+        match key.as_str() {
+            "version" => match value.as_u64() {
+                Some(v) if v > CONFIG_VERSION => {
+                    report(&path, format!("Version {v} is newer than this coinpaprika-cli supports ({CONFIG_VERSION})"))
+                }
+                Some(_) => {}
+                None => report(&path, wrong_type(key, "a whole number", value)),
+            },
+            "profiles" | "defaults" => {
+                let Some(section) = value.as_object() else {
+                    report(&path, wrong_type(key, "an object", value));
+                    continue;
+                };
+                for (name, entry) in section {
+                    let path = [key.as_str(), name.as_str()];
+                    if key == "defaults" {
+                        match entry.as_str() {
+                            Some(v) => {
+                                if let Err(e) = check_value(name, v) {
+                                    report(&path, first_line(&e));
+                                }
+                            }
+                            None => report(&path, wrong_type(name, "a string", entry)),
+                        }
+                        continue;
+                    }
+                    let Some(fields) = entry.as_object() else {
+                        report(&path, wrong_type(name, "an object", entry));
+                        continue;
+                    };
+                    for (field, v) in fields {
+                        let path = [key.as_str(), name.as_str(), field.as_str()];
+                        if !PROFILE_KEYS.contains(&field.as_str()) {
+                            report(&path, unknown(field, PROFILE_KEYS));
+                        } else if !(v.is_string() || v.is_null()) {
+                            report(&path, wrong_type(field, "a string", v));
+                        } else if let (Some(v), "quotes" | "output") = (v.as_str(), field.as_str()) {
+                            if let Err(e) = check_value(field, v) {
+                                report(&path, first_line(&e));
+                            }
+                        }
+                    }
+                }
+            }
+            _ if TOP_LEVEL_KEYS.contains(&key.as_str()) => {
+                if !(value.is_string() || value.is_null()) {
+                    report(&path, wrong_type(key, "a string", value));
+                }
+            }
+            _ => report(&path, unknown(key, TOP_LEVEL_KEYS)),
+        }
+    }
+    problems
+}
+
+fn first_line(e: &anyhow::Error) -> String {
+    e.to_string().lines().next().unwrap_or_default().to_string()
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// Line and column of every object key in a JSON document, by path, e.g.
+/// `["profiles", "team", "quotes"]`. serde_json doesn't keep positions.
+fn key_positions(text: &str) -> HashMap<Vec<String>, (usize, usize)> {
+    let mut scanner = Scanner { chars: text.chars().peekable(), line: 1, column: 1, keys: HashMap::new() };
+    scanner.value(&mut Vec::new());
+    scanner.keys
+}
+
+struct Scanner<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+    keys: HashMap<Vec<String>, (usize, usize)>,
+}
+
+impl Scanner<'_> {
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.bump();
+        }
+    }
+
+    fn value(&mut self, path: &mut Vec<String>) {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('{') => {
+                self.bump();
+                loop {
+                    self.skip_whitespace();
+                    let at = (self.line, self.column);
+                    match self.bump() {
+                        Some('"') => {
+                            let key = self.string();
+                            path.push(key);
+                            self.keys.insert(path.clone(), at);
+                            self.skip_whitespace();
+                            self.bump(); // ':'
+                            self.value(path);
+                            path.pop();
+                        }
+                        Some(',') => {}
+                        _ => break,
+                    }
+                }
+            }
+            Some('[') => {
+                self.bump();
+                let mut index = 0;
+                loop {
+                    self.skip_whitespace();
+                    match self.chars.peek() {
+                        Some(']') | None => {
+                            self.bump();
+                            break;
+                        }
+                        Some(',') => {
+                            self.bump();
+                            index += 1;
+                        }
+                        _ => {
+                            path.push(index.to_string());
+                            self.value(path);
+                            path.pop();
+                        }
+                    }
+                }
+            }
+            Some('"') => {
+                self.bump();
+                self.string();
+            }
+            _ => {
+                while self.chars.peek().is_some_and(|c| !matches!(c, ',' | ']' | '}') && !c.is_whitespace()) {
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    /// The rest of a string after its opening quote, unescaped.
+    fn string(&mut self) -> String {
+        let mut s = String::new();
+        while let Some(c) = self.bump() {
+            match c {
+                '"' => break,
+                '\\' => match self.bump() {
+                    Some('u') => {
+                        let hex: String = (0..4).filter_map(|_| self.bump()).collect();
+                        s.extend(u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32));
+                    }
+                    Some('n') => s.push('\n'),
+                    Some('t') => s.push('\t'),
+                    Some('r') => s.push('\r'),
+                    Some('b') => s.push('\u{8}'),
+                    Some('f') => s.push('\u{c}'),
+                    Some(c) => s.push(c),
+                    None => break,
+                },
+                c => s.push(c),
+            }
+        }
+        s
+    }
+}
+
+/// Save the key to a profile, or to the top-level config with `None`.
+pub fn save_api_key(key: &str, profile: Option<&str>) -> Result<()> {
+    save_key(StoredKey::Plain(key.to_string()), profile)
+}
+
+/// Save how the key is kept, replacing any other way it was kept before.
+pub fn save_key(key: StoredKey, profile: Option<&str>) -> Result<()> {
+    let mut config = load_config()?;
+    let (plain, command, file) = match profile {
+        Some(name) => {
+            config.profile(name)?;
+            let p = config.profiles.entry(name.to_string()).or_default();
+            (&mut p.api_key, &mut p.api_key_command, &mut p.api_key_file)
+        }
+        None => (&mut config.api_key, &mut config.api_key_command, &mut config.api_key_file),
+    };
+    (*plain, *command, *file) = match key {
+        StoredKey::Plain(key) => (Some(key), None, None),
+        StoredKey::Command(cmd) => (None, Some(cmd), None),
+        StoredKey::EncryptedFile(path) => (None, None, Some(path)),
+    };
+    save_config(&config)
+}
+
+/// Encrypt the key into `key.enc` (`key-NAME.enc` for a profile) and use
+/// that file from now on. Returns the file's path.
+pub fn save_encrypted_key(key: &str, passphrase: &str, profile: Option<&str>) -> Result<PathBuf> {
+    if let Some(name) = profile {
+        load_config()?.profile(name)?;
+    }
+    let name = profile.map_or_else(|| "key.enc".to_string(), |p| format!("key-{p}.enc"));
+    let path = config_dir()?.join(&name);
+    write_private(&path, &secret::encrypt(key, passphrase)?)?;
+    save_key(StoredKey::EncryptedFile(name), profile)?;
+    Ok(path)
+}
+
+/// Write the config file, readable only by the owner.
+pub fn save_config(config: &Config) -> Result<()> {
+    let mut value = serde_json::to_value(config)?;
+    value["version"] = CONFIG_VERSION.into();
+    write_private(&config_path()?, &serde_json::to_string_pretty(&value)?)
+}
+
+/// Write a file in the config directory, readable only by the owner.
+fn write_private(path: &Path, contents: &str) -> Result<()> {
     let dir = config_dir()?;
     fs::create_dir_all(&dir)?;
 
@@ -44,12 +803,6 @@ pub fn save_api_key(key: &str) -> Result<()> {
         fs::set_permissions(&dir, fs::Permissions::from_mode(0o700))?;
     }
 
-    let config = Config {
-        api_key: Some(key.to_string()),
-    };
-    let json = serde_json::to_string_pretty(&config)?;
-    let path = config_path()?;
-
     #[cfg(unix)]
     {
         use std::os::unix::fs::OpenOptionsExt;
@@ -59,50 +812,24 @@ pub fn save_api_key(key: &str) -> Result<()> {
             .write(true)
             .truncate(true)
             .mode(0o600)
-            .open(&path)?;
-        file.write_all(json.as_bytes())?;
+            .open(path)?;
+        file.write_all(contents.as_bytes())?;
     }
 
     #[cfg(not(unix))]
-    fs::write(&path, &json)?;
+    fs::write(path, contents)?;
 
     Ok(())
 }
 
+/// Delete the config, cache and state directories.
 pub fn delete_config() -> Result<()> {
-    let dir = config_dir()?;
-    if dir.exists() {
-        fs::remove_dir_all(&dir)?;
-    }
-    Ok(())
-}
-
-/// Resolve API key with priority: CLI flag > env var > config file
-pub fn resolve_api_key(cli_key: Option<&str>) -> Option<String> {
-    // 1. CLI flag
-    if let Some(key) = cli_key {
-        if !key.is_empty() {
-            return Some(key.to_string());
-        }
-    }
-
-    // 2. Environment variable
-    if let Ok(key) = std::env::var("COINPAPRIKA_API_KEY") {
-        if !key.is_empty() {
-            return Some(key);
-        }
-    }
-
-    // 3. Config file
-    if let Ok(config) = load_config() {
-        if let Some(key) = config.api_key {
-            if !key.is_empty() {
-                return Some(key);
-            }
+    for dir in [config_dir()?, cache_dir()?, state_dir()?] {
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
         }
     }
-
-    None
+    Ok(())
 }
 
 pub fn mask_key(key: &str) -> String {
@@ -112,21 +839,78 @@ pub fn mask_key(key: &str) -> String {
     format!("{}...{}", &key[..4], &key[key.len() - 4..])
 }
 
-pub fn key_source(cli_key: Option<&str>) -> &'static str {
-    if let Some(k) = cli_key {
-        if !k.is_empty() {
-            return "CLI flag (--api-key)";
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_1_files_migrate() {
+        let dirs = [std::env::temp_dir().join("coinpaprika-legacy"), std::env::temp_dir().join("coinpaprika-xdg")];
+        let legacy = dirs[0].join("key.enc");
+        let mut value = serde_json::json!({
+            "api_key": null,
+            "api_key_file": legacy.display().to_string(),
+            "profiles": {"team": {"api_key_file": "/elsewhere/key-team.enc"}},
+        });
+        assert!(migrate(&mut value, &dirs).unwrap());
+        assert_eq!(value["version"], CONFIG_VERSION);
+        assert_eq!(value["api_key_file"], "key.enc");
+        assert_eq!(value["profiles"]["team"]["api_key_file"], "/elsewhere/key-team.enc");
+        assert!(value.get("api_key").is_none());
+        assert!(!migrate(&mut value, &dirs).unwrap());
+        assert!(migrate(&mut serde_json::json!({"version": CONFIG_VERSION + 1}), &dirs).is_err());
     }
-    if let Ok(k) = std::env::var("COINPAPRIKA_API_KEY") {
-        if !k.is_empty() {
-            return "Environment variable (COINPAPRIKA_API_KEY)";
-        }
+
+    #[test]
+    fn validate_reports_positions() {
+        let text = "{\n  \"api_key\": 42,\n  \"profiles\": {\n    \"team\": {\"qoutes\": \"EUR\", \"output\": \"xml\"}\n  },\n  \"defaults\": {\"tickers.limit\": \"50\"}\n}";
+        let check = |key: &str, value: &str| if key == "output" && value == "xml" { bail!("bad output") } else { Ok(()) };
+        let problems = validate(text, check);
+        let at: Vec<_> = problems.iter().map(|p| (p.line, p.column)).collect();
+        assert_eq!(at, [(2, 3), (4, 14), (4, 31)]);
+        assert!(problems[0].message.contains("should be a string, not a number"));
+        assert!(problems[1].message.starts_with("Unknown key `qoutes`"));
+        assert_eq!(problems[2].message, "bad output");
+
+        let problems = validate("{\n  \"api_key\": \"x\",\n}", check);
+        assert_eq!((problems[0].line, problems[0].column), (3, 1));
     }
-    if let Ok(config) = load_config() {
-        if config.api_key.is_some() {
-            return "Config file (~/.coinpaprika/config.json)";
-        }
+
+    #[test]
+    fn validator_knows_every_key() {
+        let profile = Profile {
+            api_key: Some("k".into()),
+            api_key_command: Some("c".into()),
+            api_key_file: Some("f".into()),
+            base_url: Some("u".into()),
+            quotes: Some("USD".into()),
+            output: Some("json".into()),
+        };
+        let config = Config {
+            version: CONFIG_VERSION,
+            api_key: Some("k".into()),
+            api_key_command: Some("c".into()),
+            api_key_file: Some("f".into()),
+            active_profile: Some("team".into()),
+            profiles: BTreeMap::from([("team".to_string(), profile)]),
+            defaults: BTreeMap::from([("quotes".to_string(), "EUR".to_string())]),
+        };
+        let text = serde_json::to_string_pretty(&config).unwrap();
+        assert_eq!(validate(&text, |_, _| Ok(())), []);
+    }
+
+    #[test]
+    fn legacy_files_move_once() {
+        let root = std::env::temp_dir().join(format!("coinpaprika-adopt-{}", std::process::id()));
+        let (legacy, dir) = (root.join("legacy"), root.join("xdg"));
+        fs::create_dir_all(legacy.join("cache")).unwrap();
+        fs::write(legacy.join("config.json"), "{}").unwrap();
+
+        assert_eq!(adopt_legacy(&legacy, dir.clone(), |name| name == "config.json"), dir);
+        assert!(dir.join("config.json").exists());
+        assert!(!legacy.join("config.json").exists() && legacy.join("cache").exists());
+        assert_eq!(adopt_legacy(&legacy, dir.clone(), |_| true), dir);
+        assert!(legacy.join("cache").exists());
+        fs::remove_dir_all(&root).unwrap();
     }
-    "Not set (using free tier)"
 }
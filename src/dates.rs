@@ -0,0 +1,186 @@
+//! `--start` and `--end` values, resolved against the current time.
+//!
+//! Besides what the API takes (`2024-01-01`, RFC 3339, unix seconds) this
+//! accepts `now`, `today`, `yesterday`, `ytd`, months (`2024-03`), quarters
+//! (`2024-Q1`) and offsets back from now (`12h`, `7d`, `2w`). A value that
+//! names a span of time, like a day or a quarter, starts a range at its
+//! beginning and ends one at its end.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, SecondsFormat, TimeZone, Utc};
+use std::str::FromStr;
+
+const ACCEPTED: &str = "Use a date (2024-01-01), RFC 3339 time (2024-01-01T12:00:00Z), unix seconds, \
+                        now, today, yesterday, ytd, a month (2024-03), a quarter (2024-Q1) \
+                        or a time ago (12h, 7d, 2w).";
+
+/// A resolved `--start`/`--end`: an instant, or a span such as a day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    pub start: DateTime<Utc>,
+    /// The last second of the span; `start` for an instant.
+    pub end: DateTime<Utc>,
+    /// Set for values counted from now, so they can be counted again.
+    relative: Option<Relative>,
+}
+
+/// A value counted from now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Relative {
+    Now,
+    Today,
+    Yesterday,
+    Ytd,
+    Ago(Duration),
+}
+
+impl Relative {
+    fn resolve(self, now: DateTime<Utc>) -> Period {
+        let today = now.date_naive();
+        let period = match self {
+            Relative::Now => Period::instant(now),
+            Relative::Today => Period { start: midnight(today), end: now, relative: None },
+            Relative::Yesterday => Period::days(today - Duration::days(1), today),
+            Relative::Ytd => {
+                let jan1 = NaiveDate::from_ymd_opt(today.year(), 1, 1).expect("January 1st exists");
+                Period { start: midnight(jan1), end: now, relative: None }
+            }
+            Relative::Ago(ago) => Period::instant(now - ago),
+        };
+        Period { relative: Some(self), ..period }
+    }
+}
+
+impl Period {
+    fn instant(at: DateTime<Utc>) -> Self {
+        Self { start: at, end: at, relative: None }
+    }
+
+    /// From the start of `first` to the end of the day before `after`.
+    fn days(first: NaiveDate, after: NaiveDate) -> Self {
+        Self { start: midnight(first), end: midnight(after) - Duration::seconds(1), relative: None }
+    }
+
+    /// The same value counted from `now` instead, if it is relative.
+    pub fn at(self, now: DateTime<Utc>) -> Self {
+        self.relative.map_or(self, |relative| relative.resolve(now))
+    }
+
+    /// Parse `value` with relative forms counted from `now`.
+    pub fn parse_at(value: &str, now: DateTime<Utc>) -> Result<Self> {
+        let value = value.trim();
+        let lower = value.to_lowercase();
+        let relative = match lower.as_str() {
+            "now" => Some(Relative::Now),
+            "today" => Some(Relative::Today),
+            "yesterday" => Some(Relative::Yesterday),
+            "ytd" => Some(Relative::Ytd),
+            _ => ago(&lower).map(Relative::Ago),
+        };
+        if let Some(relative) = relative {
+            return Ok(relative.resolve(now));
+        }
+
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return Ok(Self::days(date, date + Duration::days(1)));
+        }
+        if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+            return Ok(Self::instant(time.with_timezone(&Utc)));
+        }
+        if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) {
+            let secs: i64 = value.parse().map_err(|_| anyhow!("'{value}' is not a unix timestamp in range."))?;
+            let at = DateTime::from_timestamp(secs, 0).ok_or_else(|| anyhow!("'{value}' is not a unix timestamp in range."))?;
+            return Ok(Self::instant(at));
+        }
+        if let Some((year, quarter)) = lower.split_once("-q") {
+            if let (Ok(year), Ok(quarter @ 1..=4)) = (year.parse::<i32>(), quarter.parse::<u32>()) {
+                return Self::months(year, (quarter - 1) * 3 + 1, 3).ok_or_else(|| anyhow!("'{value}' is out of range."));
+            }
+        }
+        if let Some((year, month)) = value.split_once('-') {
+            if let (4, Ok(year), Ok(month @ 1..=12)) = (year.len(), year.parse::<i32>(), month.parse::<u32>()) {
+                return Self::months(year, month, 1).ok_or_else(|| anyhow!("'{value}' is out of range."));
+            }
+        }
+        bail!("'{value}' is not a date or time. {ACCEPTED}")
+    }
+
+    /// `count` calendar months from `month` of `year`.
+    fn months(year: i32, month: u32, count: u32) -> Option<Self> {
+        let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+        Some(Self::days(first, first.checked_add_months(Months::new(count))?))
+    }
+
+    /// `start` as the API takes it.
+    pub(crate) fn api_start(&self) -> String {
+        self.start.to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+
+    /// `end` as the API takes it.
+    pub(crate) fn api_end(&self) -> String {
+        self.end.to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+}
+
+impl FromStr for Period {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Self::parse_at(value, Utc::now())
+    }
+}
+
+fn midnight(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).expect("midnight exists"))
+}
+
+/// `12h`, `7d` or `2w` as a duration.
+fn ago(value: &str) -> Option<Duration> {
+    let (n, unit) = value.split_at(value.find(|c: char| !c.is_ascii_digit())?);
+    let n: i64 = n.parse().ok()?;
+    match unit {
+        "h" => Duration::try_hours(n),
+        "d" => Duration::try_days(n),
+        "w" => Duration::try_weeks(n),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn period(value: &str) -> (String, String) {
+        let p = Period::parse_at(value, at("2024-05-15T10:30:00Z")).unwrap();
+        (p.api_start(), p.api_end())
+    }
+
+    #[test]
+    fn absolute_forms() {
+        assert_eq!(period("2024-01-01"), ("2024-01-01T00:00:00Z".into(), "2024-01-01T23:59:59Z".into()));
+        assert_eq!(period("2024-01-01T12:00:00+02:00").0, "2024-01-01T10:00:00Z");
+        assert_eq!(period("1704067200").0, "2024-01-01T00:00:00Z");
+        assert_eq!(period("2024-02"), ("2024-02-01T00:00:00Z".into(), "2024-02-29T23:59:59Z".into()));
+        assert_eq!(period("2023-Q4"), ("2023-10-01T00:00:00Z".into(), "2023-12-31T23:59:59Z".into()));
+    }
+
+    #[test]
+    fn relative_forms() {
+        assert_eq!(period("now").0, "2024-05-15T10:30:00Z");
+        assert_eq!(period("today"), ("2024-05-15T00:00:00Z".into(), "2024-05-15T10:30:00Z".into()));
+        assert_eq!(period("yesterday"), ("2024-05-14T00:00:00Z".into(), "2024-05-14T23:59:59Z".into()));
+        assert_eq!(period("YTD"), ("2024-01-01T00:00:00Z".into(), "2024-05-15T10:30:00Z".into()));
+        assert_eq!(period("7d").0, "2024-05-08T10:30:00Z");
+        assert_eq!(period("2w").0, "2024-05-01T10:30:00Z");
+        let later = at("2024-06-01T00:00:00Z");
+        assert_eq!(Period::parse_at("7d", Utc::now()).unwrap().at(later).api_start(), "2024-05-25T00:00:00Z");
+        assert_eq!(Period::parse_at("2024-01-01", Utc::now()).unwrap().at(later).api_start(), "2024-01-01T00:00:00Z");
+        for bad in ["last week", "2024-Q5", "2024-13", "7y", ""] {
+            assert!(Period::parse_at(bad, Utc::now()).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! A local copy of `/coins`, `/exchanges` and `/tags` for offline,
+//! typo-tolerant search and for completing IDs.
+
+use anyhow::Result;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::cache::{self, Cached};
+use crate::resolve::levenshtein;
+use crate::types::{SearchCurrency, SearchExchange, SearchResult, SearchTag};
+use crate::ApiClient;
+
+/// Age after which search warns that the index should be rebuilt.
+pub const INDEX_MAX_AGE: Duration = Duration::days(7);
+
+const INDEX_NAME: &str = "index";
+
+/// How long the cached `/contracts` platform list is trusted.
+const PLATFORMS_MAX_AGE: Duration = Duration::days(7);
+
+/// Entries are stored best-first (by rank, volume or coin count), so list
+/// position breaks ties between equally good matches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalIndex {
+    pub coins: Vec<SearchCurrency>,
+    pub exchanges: Vec<SearchExchange>,
+    pub tags: Vec<SearchTag>,
+}
+
+/// Download the lists and save them as the local index. The coin list also
+/// refreshes the cache used to resolve coin symbols.
+pub async fn update(client: &ApiClient) -> Result<Cached<LocalIndex>> {
+    let (coins, exchanges, tags) = tokio::try_join!(client.coins(), client.exchanges("USD"), client.tags())?;
+    let _ = cache::store(&client.cache_name("coins"), &coins);
+
+    let mut coins: Vec<SearchCurrency> = coins
+        .into_iter()
+        .map(|c| SearchCurrency {
+            id: c.id,
+            name: c.name,
+            symbol: c.symbol,
+            rank: c.rank,
+            is_active: c.is_active,
+            coin_type: c.coin_type,
+        })
+        .collect();
+    coins.sort_by_key(|c| c.rank.filter(|r| *r > 0).unwrap_or(i64::MAX));
+
+    let mut exchanges = exchanges;
+    exchanges.sort_by_key(|e| e.adjusted_rank.filter(|r| *r > 0).unwrap_or(i64::MAX));
+    let exchanges = exchanges
+        .into_iter()
+        .map(|e| SearchExchange { id: Some(e.id), name: Some(e.name) })
+        .collect();
+
+    let mut tags = tags;
+    tags.sort_by_key(|t| std::cmp::Reverse(t.coin_counter.unwrap_or(0)));
+    let tags = tags
+        .into_iter()
+        .map(|t| SearchTag { id: Some(t.id), name: Some(t.name), coin_counter: t.coin_counter, ico_counter: t.ico_counter })
+        .collect();
+
+    let index = LocalIndex { coins, exchanges, tags };
+    cache::store(&client.cache_name(INDEX_NAME), &index)?;
+    Ok(Cached { fetched_at: chrono::Utc::now(), data: index })
+}
+
+/// The index built from the API at `base_url`.
+pub fn load(base_url: &str) -> Option<Cached<LocalIndex>> {
+    cache::load(&cache::scoped(INDEX_NAME, base_url))
+}
+
+/// The kinds of ID that arguments take.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdKind {
+    Coin,
+    Exchange,
+    Tag,
+    Platform,
+}
+
+/// IDs of one kind from what is cached for the API at `base_url`, best
+/// first. Never touches the network, so it is cheap enough to call on every
+/// keystroke.
+pub fn cached_ids(kind: IdKind, base_url: &str) -> Vec<String> {
+    let from_index = |ids: fn(LocalIndex) -> Vec<String>| load(base_url).map(|index| ids(index.data)).unwrap_or_default();
+    match kind {
+        IdKind::Coin => match cache::load::<Vec<crate::types::CoinListItem>>(&cache::scoped("coins", base_url)) {
+            Some(coins) => coins.data.into_iter().map(|c| c.id).collect(),
+            None => from_index(|index| index.coins.into_iter().map(|c| c.id).collect()),
+        },
+        IdKind::Exchange => from_index(|index| index.exchanges.into_iter().filter_map(|e| e.id).collect()),
+        IdKind::Tag => from_index(|index| index.tags.into_iter().filter_map(|t| t.id).collect()),
+        IdKind::Platform => cache::load::<Vec<String>>(&cache::scoped("platforms", base_url)).map(|p| p.data).unwrap_or_default(),
+    }
+}
+
+/// Fetch the lists behind [`cached_ids`] that aren't cached yet.
+pub async fn fill_id_caches(client: &ApiClient) -> Result<()> {
+    if load(client.base_url()).is_none() {
+        update(client).await?;
+    }
+    cache::get_or_fetch(&client.cache_name("platforms"), PLATFORMS_MAX_AGE, || client.platforms()).await?;
+    Ok(())
+}
+
+impl LocalIndex {
+    /// Ranked search mirroring `/search`: `categories` is the same
+    /// comma-separated list, and `symbol_only` mirrors `modifier=symbol_search`.
+    pub fn search(&self, query: &str, categories: Option<&str>, limit: usize, symbol_only: bool) -> SearchResult {
+        let query = query.trim().to_lowercase();
+        let wanted = |category: &str| categories.is_none_or(|c| c.split(',').any(|x| x.trim() == category));
+
+        let currencies = wanted("currencies").then(|| {
+            top(&self.coins, limit, |c| {
+                if symbol_only {
+                    (c.symbol.to_lowercase() == query).then_some(0)
+                } else {
+                    score(&query, &[&c.id, &c.name, &c.symbol])
+                }
+            })
+        });
+        let exchanges = (wanted("exchanges") && !symbol_only).then(|| {
+            top(&self.exchanges, limit, |e| {
+                score(&query, &[e.id.as_deref().unwrap_or(""), e.name.as_deref().unwrap_or("")])
+            })
+        });
+        let tags = (wanted("tags") && !symbol_only).then(|| {
+            top(&self.tags, limit, |t| {
+                score(&query, &[t.id.as_deref().unwrap_or(""), t.name.as_deref().unwrap_or("")])
+            })
+        });
+
+        SearchResult { currencies, exchanges, icos: None, people: None, tags }
+    }
+}
+
+/// The best `limit` entries by score, keeping index order among equals.
+fn top<T: Clone>(entries: &[T], limit: usize, score: impl Fn(&T) -> Option<u32>) -> Vec<T> {
+    let mut scored: Vec<(u32, usize)> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, e)| score(e).map(|s| (s, i)))
+        .collect();
+    scored.sort();
+    scored.into_iter().take(limit).map(|(_, i)| entries[i].clone()).collect()
+}
+
+/// Lower is better; `None` means no match. Exact beats prefix beats
+/// substring beats a typo within a few edits.
+fn score(query: &str, fields: &[&str]) -> Option<u32> {
+    if query.is_empty() {
+        return None;
+    }
+    let max_distance = match query.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    };
+    fields
+        .iter()
+        .map(|f| f.to_lowercase())
+        .filter_map(|field| {
+            if field == query {
+                Some(0)
+            } else if field.starts_with(query) {
+                Some(1)
+            } else if field.split([' ', '-']).any(|word| word.starts_with(query)) {
+                Some(2)
+            } else if field.contains(query) {
+                Some(3)
+            } else {
+                // Typos are checked per word too, so "etherem" still finds
+                // "Ethereum Classic", just below "Ethereum" itself.
+                let whole = levenshtein(query, &field);
+                let word = field.split([' ', '-']).map(|w| levenshtein(query, w)).min().unwrap_or(usize::MAX);
+                if whole <= max_distance {
+                    Some(4 + 2 * whole as u32)
+                } else {
+                    (word <= max_distance).then_some(5 + 2 * word as u32)
+                }
+            }
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn currency(id: &str, name: &str, symbol: &str, rank: i64) -> SearchCurrency {
+        SearchCurrency {
+            id: id.into(),
+            name: name.into(),
+            symbol: symbol.into(),
+            rank: Some(rank),
+            is_active: Some(true),
+            coin_type: Some("coin".into()),
+        }
+    }
+
+    fn index() -> LocalIndex {
+        LocalIndex {
+            coins: vec![
+                currency("btc-bitcoin", "Bitcoin", "BTC", 1),
+                currency("eth-ethereum", "Ethereum", "ETH", 2),
+                currency("bch-bitcoin-cash", "Bitcoin Cash", "BCH", 15),
+                currency("etc-ethereum-classic", "Ethereum Classic", "ETC", 20),
+            ],
+            exchanges: vec![SearchExchange { id: Some("binance".into()), name: Some("Binance".into()) }],
+            tags: vec![SearchTag { id: Some("defi".into()), name: Some("DeFi".into()), coin_counter: Some(10), ico_counter: None }],
+        }
+    }
+
+    fn ids(result: &SearchResult) -> Vec<&str> {
+        result.currencies.iter().flatten().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn exact_symbol_ranks_first() {
+        assert_eq!(ids(&index().search("eth", None, 10, false))[0], "eth-ethereum");
+    }
+
+    #[test]
+    fn tolerates_typos() {
+        assert_eq!(ids(&index().search("etherem", None, 10, false)), ["eth-ethereum", "etc-ethereum-classic"]);
+        assert_eq!(ids(&index().search("bitcoin", None, 10, false)), ["btc-bitcoin", "bch-bitcoin-cash"]);
+    }
+
+    #[test]
+    fn respects_categories() {
+        let result = index().search("binance", Some("exchanges"), 10, false);
+        assert!(result.currencies.is_none());
+        assert_eq!(result.exchanges.unwrap().len(), 1);
+    }
+}
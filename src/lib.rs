@@ -0,0 +1,36 @@
+//! Typed async client for the CoinPaprika API.
+//!
+//! ```no_run
+//! use coinpaprika_cli::{ApiClient, HistoricalParams, Interval};
+//!
+//! # async fn demo() -> anyhow::Result<()> {
+//! let client = ApiClient::new(None);
+//! let btc = client.ticker("btc-bitcoin", "USD").await?;
+//! let candles = client
+//!     .ohlcv_historical("btc-bitcoin", &HistoricalParams::new("2024-01-01".parse()?).interval(Interval::H24).limit(30))
+//!     .await?;
+//! println!("{} has {} candles", btc.name, candles.len());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The `coinpaprika-cli` binary is built on top of this crate and lives
+//! behind the default `cli` feature. Depend on it with
+//! `default-features = false` to get just the client.
+
+pub mod cache;
+pub mod client;
+pub mod config;
+pub mod dates;
+pub mod index;
+pub mod mappings;
+pub mod params;
+pub mod plans;
+pub mod resolve;
+pub mod secret;
+pub mod types;
+pub mod usage;
+
+pub use client::ApiClient;
+pub use dates::Period;
+pub use params::{HistoricalParams, Interval, PageParams, SearchParams, TickersParams};
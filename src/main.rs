@@ -1,11 +1,17 @@
-mod client;
-mod config;
 mod commands;
+mod completion;
+mod mock_server;
 mod output;
 mod shell;
 
-use clap::{Parser, Subcommand};
-use output::OutputFormat;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use coinpaprika_cli::client::CassetteMode;
+use coinpaprika_cli::resolve::Resolver;
+use config::Settings;
+use coinpaprika_cli::{config, ApiClient, HistoricalParams, Interval, Period};
+use commands::CommandOutput;
+use output::{ItemError, OutputFormat};
+use std::path::PathBuf;
 use std::process::ExitCode;
 
 #[derive(Parser)]
@@ -26,7 +32,7 @@ pub(crate) struct Cli {
     #[command(subcommand)]
     command: Commands,
 
-    /// Output format: table or json
+    /// Output format: table, json or csv
     #[arg(short, long, global = true, default_value = "table")]
     pub(crate) output: OutputFormat,
 
@@ -34,9 +40,33 @@ pub(crate) struct Cli {
     #[arg(long, global = true)]
     api_key: Option<String>,
 
+    /// Profile from the config file (env: COINPAPRIKA_PROFILE)
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Table borders for table output
+    #[arg(long, global = true, value_name = "STYLE", default_value = "rounded")]
+    pub(crate) table_style: output::TableStyle,
+
     /// JSON output without _meta wrapper (for scripts/piping)
     #[arg(long, global = true, default_value = "false")]
     pub(crate) raw: bool,
+
+    /// Save every API response as a fixture in DIR
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Serve API responses from fixtures in DIR, without network access
+    #[arg(long, global = true, value_name = "DIR")]
+    replay: Option<PathBuf>,
+
+    /// Require exact coin IDs; don't resolve symbols or names like BTC or bitcoin
+    #[arg(long, global = true)]
+    strict: bool,
+
+    /// API base URL, e.g. a local mock-server (env: COINPAPRIKA_BASE_URL)
+    #[arg(long, global = true, value_name = "URL")]
+    base_url: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -53,20 +83,26 @@ enum Commands {
     },
 
     /// Get detailed info about a specific coin
-    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli coin btc-bitcoin\n  coinpaprika-cli coin eth-ethereum --output json")]
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli coin btc-bitcoin\n  coinpaprika-cli coin eth-ethereum --output json\n  coinpaprika-cli coin btc-bitcoin eth-ethereum sol-solana")]
     Coin {
-        /// Coin ID (e.g., btc-bitcoin, eth-ethereum)
-        coin_id: String,
+        /// Coin IDs (e.g., btc-bitcoin, eth-ethereum)
+        #[arg(value_name = "COIN_ID", required_unless_present = "ids_from")]
+        coin_ids: Vec<String>,
+        #[command(flatten)]
+        batch: commands::multi::BatchArgs,
     },
 
     /// Get events for a coin
-    #[command(name = "coin-events", after_help = "EXAMPLES:\n  coinpaprika-cli coin-events btc-bitcoin\n  coinpaprika-cli coin-events eth-ethereum --limit 5")]
+    #[command(name = "coin-events", after_help = "EXAMPLES:\n  coinpaprika-cli coin-events btc-bitcoin\n  coinpaprika-cli coin-events eth-ethereum --limit 5\n  coinpaprika-cli coin-events --ids-from watchlist.txt --limit 3")]
     CoinEvents {
-        /// Coin ID (e.g., btc-bitcoin)
-        coin_id: String,
-        /// Maximum number of results
+        /// Coin IDs (e.g., btc-bitcoin)
+        #[arg(value_name = "COIN_ID", required_unless_present = "ids_from")]
+        coin_ids: Vec<String>,
+        /// Maximum number of results (per coin)
         #[arg(long, default_value = "50")]
         limit: usize,
+        #[command(flatten)]
+        batch: commands::multi::BatchArgs,
     },
 
     /// Get exchanges where a coin is traded
@@ -104,29 +140,32 @@ enum Commands {
     },
 
     /// Get real-time price data for a specific coin
-    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli ticker btc-bitcoin\n  coinpaprika-cli ticker eth-ethereum --quotes USD,BTC\n  coinpaprika-cli ticker btc-bitcoin --output json")]
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli ticker btc-bitcoin\n  coinpaprika-cli ticker eth-ethereum --quotes USD,BTC\n  coinpaprika-cli ticker btc-bitcoin --output json\n  coinpaprika-cli ticker btc-bitcoin eth-ethereum sol-solana\n  cat watchlist.txt | coinpaprika-cli ticker --ids-from - --parallel 4")]
     Ticker {
-        /// Coin ID in format symbol-name (e.g., btc-bitcoin, eth-ethereum)
-        coin_id: String,
+        /// Coin IDs in format symbol-name (e.g., btc-bitcoin, eth-ethereum)
+        #[arg(value_name = "COIN_ID", required_unless_present = "ids_from")]
+        coin_ids: Vec<String>,
         /// Currency quotes, comma-separated
         #[arg(long, default_value = "USD")]
         quotes: String,
+        #[command(flatten)]
+        batch: commands::multi::BatchArgs,
     },
 
     /// Get historical ticker data for a coin [PAID: Starter+]
-    #[command(name = "ticker-history", after_help = "EXAMPLES:\n  coinpaprika-cli ticker-history btc-bitcoin --start 2024-01-01\n  coinpaprika-cli ticker-history eth-ethereum --start 2024-01-01 --interval 24h --limit 30")]
+    #[command(name = "ticker-history", after_help = "EXAMPLES:\n  coinpaprika-cli ticker-history btc-bitcoin --start 2024-01-01\n  coinpaprika-cli ticker-history eth-ethereum --start 2024-01-01 --interval 24h --limit 30\n  coinpaprika-cli ticker-history btc-bitcoin --start 7d --interval 1h")]
     TickerHistory {
         /// Coin ID (e.g., btc-bitcoin)
         coin_id: String,
-        /// Start date (ISO format, e.g., 2024-01-01)
+        /// Start: 2024-01-01, RFC 3339, unix seconds, 7d, ytd, 2024-Q1, yesterday...
         #[arg(long)]
-        start: String,
-        /// End date (ISO format)
+        start: Period,
+        /// End, in the same forms as --start (default: now)
         #[arg(long)]
-        end: Option<String>,
-        /// Interval (5m, 1h, 24h, 7d, 30d, etc.)
+        end: Option<Period>,
+        /// Interval: 5m, 10m, 15m, 30m, 45m, 1h, 2h, 3h, 6h, 12h, 24h, 1d, 7d, 14d, 30d, 90d, 365d
         #[arg(long, default_value = "24h")]
-        interval: String,
+        interval: Interval,
         /// Maximum number of results
         #[arg(long, default_value = "50")]
         limit: usize,
@@ -136,19 +175,19 @@ enum Commands {
     },
 
     /// Get historical OHLCV data for a coin [PAID: Starter+]
-    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli ohlcv btc-bitcoin --start 2024-01-01\n  coinpaprika-cli ohlcv eth-ethereum --start 2024-01-01 --interval 24h --limit 30")]
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli ohlcv btc-bitcoin --start 2024-01-01\n  coinpaprika-cli ohlcv eth-ethereum --start 2024-01-01 --interval 24h --limit 30\n  coinpaprika-cli ohlcv btc-bitcoin --start 2024-Q1 --end 2024-Q2\n  coinpaprika-cli ohlcv btc-bitcoin --start ytd")]
     Ohlcv {
         /// Coin ID (e.g., btc-bitcoin)
         coin_id: String,
-        /// Start date (ISO format, e.g., 2024-01-01)
+        /// Start: 2024-01-01, RFC 3339, unix seconds, 7d, ytd, 2024-Q1, yesterday...
         #[arg(long)]
-        start: String,
-        /// End date (ISO format)
+        start: Period,
+        /// End, in the same forms as --start (default: now)
         #[arg(long)]
-        end: Option<String>,
-        /// Interval (5m, 15m, 30m, 1h, 6h, 12h, 24h)
+        end: Option<Period>,
+        /// Interval: 5m, 10m, 15m, 30m, 45m, 1h, 2h, 3h, 6h, 12h, 24h, 1d, 7d, 14d, 30d, 90d, 365d
         #[arg(long, default_value = "24h")]
-        interval: String,
+        interval: Interval,
         /// Maximum number of results
         #[arg(long, default_value = "50")]
         limit: usize,
@@ -158,13 +197,16 @@ enum Commands {
     },
 
     /// Get OHLCV data for the last full day
-    #[command(name = "ohlcv-latest", after_help = "EXAMPLES:\n  coinpaprika-cli ohlcv-latest btc-bitcoin")]
+    #[command(name = "ohlcv-latest", after_help = "EXAMPLES:\n  coinpaprika-cli ohlcv-latest btc-bitcoin\n  coinpaprika-cli ohlcv-latest btc-bitcoin eth-ethereum")]
     OhlcvLatest {
-        /// Coin ID (e.g., btc-bitcoin)
-        coin_id: String,
+        /// Coin IDs (e.g., btc-bitcoin)
+        #[arg(value_name = "COIN_ID", required_unless_present = "ids_from")]
+        coin_ids: Vec<String>,
         /// Quote currency
         #[arg(long, default_value = "usd")]
         quote: String,
+        #[command(flatten)]
+        batch: commands::multi::BatchArgs,
     },
 
     /// Get OHLCV data for today (incomplete day)
@@ -234,7 +276,7 @@ enum Commands {
     },
 
     /// Search for coins, exchanges, people, and tags
-    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli search bitcoin\n  coinpaprika-cli search ethereum --categories currencies,exchanges --limit 5")]
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli search bitcoin\n  coinpaprika-cli search ethereum --categories currencies,exchanges --limit 5\n  coinpaprika-cli search etherem --local")]
     Search {
         /// Search query
         query: String,
@@ -247,6 +289,9 @@ enum Commands {
         /// Search modifier (e.g., symbol_search)
         #[arg(long)]
         modifier: Option<String>,
+        /// Search the offline index from `index update` (no API call)
+        #[arg(long)]
+        local: bool,
     },
 
     /// Convert between two currencies
@@ -276,12 +321,15 @@ enum Commands {
     },
 
     /// Get ticker data by contract address
-    #[command(name = "contract-ticker", after_help = "EXAMPLES:\n  coinpaprika-cli contract-ticker eth-ethereum 0xdac17f958d2ee523a2206206994597c13d831ec7")]
+    #[command(name = "contract-ticker", after_help = "EXAMPLES:\n  coinpaprika-cli contract-ticker eth-ethereum 0xdac17f958d2ee523a2206206994597c13d831ec7\n  coinpaprika-cli contract-ticker eth-ethereum --ids-from addresses.txt")]
     ContractTicker {
         /// Platform ID (e.g., eth-ethereum)
         platform_id: String,
-        /// Contract address
-        address: String,
+        /// Contract addresses
+        #[arg(value_name = "ADDRESS", required_unless_present = "ids_from")]
+        addresses: Vec<String>,
+        #[command(flatten)]
+        batch: commands::multi::BatchArgs,
     },
 
     /// Get historical ticker data by contract [PAID: Starter+]
@@ -291,15 +339,15 @@ enum Commands {
         platform_id: String,
         /// Contract address
         address: String,
-        /// Start date (ISO format)
+        /// Start: 2024-01-01, RFC 3339, unix seconds, 7d, ytd, 2024-Q1, yesterday...
         #[arg(long)]
-        start: String,
-        /// End date (ISO format)
+        start: Period,
+        /// End, in the same forms as --start (default: now)
         #[arg(long)]
-        end: Option<String>,
-        /// Interval
+        end: Option<Period>,
+        /// Interval: 5m, 10m, 15m, 30m, 45m, 1h, 2h, 3h, 6h, 12h, 24h, 1d, 7d, 14d, 30d, 90d, 365d
         #[arg(long, default_value = "24h")]
-        interval: String,
+        interval: Interval,
         /// Maximum number of results
         #[arg(long, default_value = "50")]
         limit: usize,
@@ -310,24 +358,43 @@ enum Commands {
     KeyInfo,
 
     /// Get ID mappings across platforms [PAID: Business+]
-    Mappings,
+    #[command(args_conflicts_with_subcommands = true, after_help = "EXAMPLES:\n  coinpaprika-cli mappings --limit 20\n  coinpaprika-cli mappings lookup --from coingecko:bitcoin\n  coinpaprika-cli mappings for btc-bitcoin\n  coinpaprika-cli mappings translate holdings.csv --column id --from cmc")]
+    Mappings {
+        #[command(subcommand)]
+        command: Option<commands::mappings::MappingsCommand>,
+        /// Maximum number of results
+        #[arg(long)]
+        limit: Option<usize>,
+    },
 
     /// Get changelog of coin ID changes [PAID: Starter+]
+    #[command(args_conflicts_with_subcommands = true, after_help = "EXAMPLES:\n  coinpaprika-cli changelog\n  coinpaprika-cli changelog --limit 20\n  coinpaprika-cli changelog apply portfolio.csv --dry-run")]
     Changelog {
-        /// Maximum number of results
-        #[arg(long, default_value = "50")]
-        limit: usize,
-        /// Page number
-        #[arg(long, default_value = "1")]
-        page: usize,
+        #[command(subcommand)]
+        command: Option<commands::changelog::ChangelogCommand>,
+        /// Maximum number of results (default: all, across every page)
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Fetch a single page instead of all of them
+        #[arg(long)]
+        page: Option<usize>,
     },
 
+    /// Manage the offline coin/exchange/tag index
+    #[command(subcommand)]
+    Index(commands::index::IndexCommand),
+
     /// Manage CLI configuration
     #[command(subcommand)]
     Config(commands::config::ConfigCommand),
 
-    /// Check CoinPaprika API health status and response time
-    Status,
+    /// Check CoinPaprika API health: latency percentiles and errors per endpoint
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli status\n  coinpaprika-cli status -n 10 -e /tickers/eth-ethereum -e /global\n  coinpaprika-cli status --watch --every 60 -o json   # exits non-zero when nothing answers")]
+    Status(commands::status::StatusArgs),
+
+    /// Diagnose setup problems: config, key, plan, network, clock and cache
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli doctor\n  coinpaprika-cli doctor -o json   # exits non-zero if a check fails")]
+    Doctor,
 
     /// Get ready-to-paste attribution snippets for CoinPaprika
     Attribution,
@@ -335,15 +402,63 @@ enum Commands {
     /// Interactive shell mode (REPL)
     Shell,
 
+    /// Print a bash, zsh or fish completion script
+    #[command(after_help = "EXAMPLES:\n  source <(coinpaprika-cli completions bash)\n  coinpaprika-cli completions zsh > ~/.zfunc/_coinpaprika-cli\n  coinpaprika-cli completions fish > ~/.config/fish/completions/coinpaprika-cli.fish")]
+    Completions {
+        shell: completion::ShellKind,
+    },
+
+    /// Completion candidates for the words so far; the last is the one being typed
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        words: Vec<String>,
+    },
+
     /// Show free tier details and paid plan overview
     Plans,
 
+    /// Print the JSON Schema of a command's --output json
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli schema ticker\n  coinpaprika-cli schema tickers --raw\n  coinpaprika-cli schema")]
+    Schema {
+        /// Command name (e.g., ticker, coin, search); omit to list all
+        command: Option<String>,
+    },
+
+    /// Serve a local mock of the API with seeded data and injectable errors
+    #[command(name = "mock-server", after_help = "EXAMPLES:\n  coinpaprika-cli mock-server --port 8080\n  coinpaprika-cli mock-server --seed 7 --inject 429:/tickers --error-rate 0.5\n  COINPAPRIKA_BASE_URL=http://127.0.0.1:8080/v1 coinpaprika-cli ticker btc-bitcoin")]
+    MockServer(mock_server::MockServerArgs),
+
+    /// Call any API endpoint, e.g. one without a command of its own
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli api GET /global\n  coinpaprika-cli api GET /tickers/btc-bitcoin -p quotes=USD,EUR -o json\n  coinpaprika-cli api GET /coins/btc-bitcoin/twitter\n  coinpaprika-cli api GET /changelog/ids -p limit=1000 --paginate -o json")]
+    Api(commands::api::ApiArgs),
+
+    /// Run a file of commands with one shared client and response cache
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli batch nightly.txt > results.json\n  coinpaprika-cli batch nightly.txt --parallel 4 --ndjson\n  coinpaprika-cli batch nightly.txt --out-dir results/ -o csv\n  printf 'global\\nticker btc-bitcoin\\n' | coinpaprika-cli batch -")]
+    Batch(commands::batch::BatchFileArgs),
+
+    /// Run commands described as JSON, with results in a JSON envelope
+    #[command(after_help = "EXAMPLES:\n  coinpaprika-cli exec '{\"command\":\"ohlcv\",\"args\":{\"coin_id\":\"btc-bitcoin\",\"start\":\"2024-01-01\"}}'\n  echo '[{\"command\":\"global\"},{\"command\":\"ticker\",\"args\":{\"coin_id\":[\"btc-bitcoin\",\"eth-ethereum\"]}}]' | coinpaprika-cli exec\n  coinpaprika-cli schema exec")]
+    Exec(commands::exec::ExecArgs),
+
+    /// Run Rhai scripts over API data
+    #[command(subcommand)]
+    Script(commands::script::ScriptCommand),
+
+    /// Manage plugins (coinpaprika-cli-NAME executables on PATH)
+    #[command(subcommand)]
+    Plugins(commands::plugins::PluginsCommand),
+
     /// Interactive setup wizard (configure API key)
     Onboard {
         /// API key to save (skips interactive prompts)
         #[arg(long)]
         key: Option<String>,
     },
+
+    /// Any other name runs the `coinpaprika-cli-NAME` plugin
+    #[command(external_subcommand)]
+    External(Vec<String>),
 }
 
 pub(crate) fn run(cli: Cli) -> std::pin::Pin<Box<dyn std::future::Future<Output = anyhow::Result<()>> + Send>> {
@@ -351,66 +466,363 @@ pub(crate) fn run(cli: Cli) -> std::pin::Pin<Box<dyn std::future::Future<Output
 }
 
 async fn run_inner(cli: Cli) -> anyhow::Result<()> {
-    let api_key = config::resolve_api_key(cli.api_key.as_deref());
-    let has_api_key = api_key.is_some();
-    let client = client::ApiClient::new(api_key);
-    let output = cli.output;
-    let raw = cli.raw;
+    output::set_table_style(cli.table_style);
+    let mut settings = Settings::resolve(cli.profile.as_deref(), cli.api_key.as_deref(), cli.base_url.as_deref())?;
+    // Commands that never call the API don't need the key fetched or
+    // decrypted; `doctor` unlocks it itself, to report a failure.
+    let offline = matches!(
+        cli.command,
+        Commands::Config(_)
+            | Commands::Schema { .. }
+            | Commands::Plans
+            | Commands::Attribution
+            | Commands::MockServer(_)
+            | Commands::Plugins(_)
+            | Commands::Doctor
+            | Commands::Completions { .. }
+            | Commands::Complete { .. }
+    );
+    if !offline {
+        settings.unlock(commands::config::ask_passphrase)?;
+    }
+    let api_key = settings.api_key().map(str::to_string);
+    let cassette = match (cli.record, cli.replay) {
+        (Some(dir), _) => Some(CassetteMode::Record(dir)),
+        (None, Some(dir)) => Some(CassetteMode::Replay(dir)),
+        (None, None) => None,
+    };
+    let client = client_for(&settings, cassette.clone());
 
     match cli.command {
-        Commands::Global => commands::global::execute(&client, output, raw).await,
-        Commands::Coins { limit } => commands::coins::execute_list(&client, limit, output, raw).await,
-        Commands::Coin { coin_id } => commands::coins::execute_detail(&client, &coin_id, output, raw).await,
-        Commands::CoinEvents { coin_id, limit } => commands::coins::execute_events(&client, &coin_id, limit, output, raw).await,
-        Commands::CoinExchanges { coin_id, limit } => commands::coins::execute_exchanges(&client, &coin_id, limit, output, raw).await,
-        Commands::CoinMarkets { coin_id, quotes, limit } => commands::coins::execute_markets(&client, &coin_id, &quotes, limit, output, raw).await,
-        Commands::Tickers { limit, quotes } => commands::tickers::execute_list(&client, limit, &quotes, output, raw).await,
-        Commands::Ticker { coin_id, quotes } => commands::tickers::execute_detail(&client, &coin_id, &quotes, output, raw).await,
+        Commands::Shell => {
+            shell::run_shell(client, settings, cassette).await;
+            Ok(())
+        }
+        Commands::Completions { shell } => {
+            print!("{}", completion::script(shell));
+            Ok(())
+        }
+        Commands::Complete { words } => {
+            completion::print_candidates(&words, client.base_url());
+            Ok(())
+        }
+        Commands::Onboard { key } => commands::onboard::execute(key).await,
+        Commands::MockServer(args) => mock_server::run(args).await,
+        Commands::Script(cmd) => {
+            let client = std::sync::Arc::new(client.with_response_cache());
+            let quotes = settings.quotes.as_ref().map(|q| q.value.as_str());
+            let result = tokio::task::block_in_place(|| commands::script::execute(client, cmd, quotes))?;
+            output::render(&result, cli.output, cli.raw)
+        }
+        Commands::External(args) => tokio::task::block_in_place(|| {
+            commands::plugins::run(&args, api_key.as_deref(), client.base_url(), cli.output, cli.raw, cli.strict)
+        }),
+        Commands::Batch(args) => {
+            let client = client.with_response_cache();
+            commands::batch::run(args, &client, &settings, cli.strict, cli.output, cli.raw).await
+        }
+        Commands::Exec(args) => {
+            let client = client.with_response_cache();
+            commands::exec::run(args, &client, &settings, cli.strict).await
+        }
+        Commands::Status(args) if args.watch => {
+            commands::status::watch(&client, &args, api_key.is_some(), cli.output, cli.raw).await
+        }
+        Commands::Status(args) => {
+            let result = commands::status::execute(&client, &args, api_key.is_some()).await?;
+            output::render(&result, cli.output, cli.raw)?;
+            commands::status::exit_status(&result)
+        }
+        Commands::Doctor => {
+            let result = commands::doctor::execute(&settings).await;
+            output::render(&result, cli.output, cli.raw)?;
+            commands::doctor::exit_status(&result)
+        }
+        command => {
+            let result = execute(command, &client, &settings, cli.strict).await?;
+            output::render(&result, cli.output, cli.raw)
+        }
+    }
+}
+
+/// The client for `settings`, logging usage for `doctor`.
+pub(crate) fn client_for(settings: &Settings, cassette: Option<CassetteMode>) -> ApiClient {
+    let mut client = ApiClient::new(settings.api_key().map(str::to_string)).with_usage_log();
+    if let Some(url) = &settings.base_url {
+        client = client.with_base_url(&url.value);
+    }
+    if let Some(mode) = cassette {
+        client = client.with_cassette(mode);
+    }
+    client
+}
+
+/// Run a non-interactive command and return its result unrendered.
+async fn execute(mut command: Commands, client: &ApiClient, settings: &Settings, strict: bool) -> anyhow::Result<CommandOutput> {
+    read_batch_ids(&mut command)?;
+    let unresolved = if strict { Vec::new() } else { resolve_coin_ids(&mut command, client).await? };
+    let result = match command {
+        Commands::Global => commands::global::execute(client).await,
+        Commands::Coins { limit } => commands::coins::execute_list(client, limit).await,
+        Commands::Coin { coin_ids, batch } => match batch.single(&coin_ids) {
+            Some(coin_id) => commands::coins::execute_detail(client, coin_id).await,
+            None => commands::coins::execute_detail_many(client, &coin_ids, batch.parallel).await,
+        },
+        Commands::CoinEvents { coin_ids, limit, batch } => match batch.single(&coin_ids) {
+            Some(coin_id) => commands::coins::execute_events(client, coin_id, limit).await,
+            None => commands::coins::execute_events_many(client, &coin_ids, limit, batch.parallel).await,
+        },
+        Commands::CoinExchanges { coin_id, limit } => commands::coins::execute_exchanges(client, &coin_id, limit).await,
+        Commands::CoinMarkets { coin_id, quotes, limit } => commands::coins::execute_markets(client, &coin_id, &quotes, limit).await,
+        Commands::Tickers { limit, quotes } => commands::tickers::execute_list(client, limit, &quotes).await,
+        Commands::Ticker { coin_ids, quotes, batch } => match batch.single(&coin_ids) {
+            Some(coin_id) => commands::tickers::execute_detail(client, coin_id, &quotes).await,
+            None => commands::tickers::execute_many(client, &coin_ids, &quotes, batch.parallel).await,
+        },
         Commands::TickerHistory { coin_id, start, end, interval, limit, quote } => {
-            commands::tickers::execute_history(&client, &coin_id, &start, end.as_deref(), &interval, limit, &quote, output, raw).await
+            let params = historical_params(start, end, interval, limit).quote(quote);
+            commands::tickers::execute_history(client, &coin_id, &params).await
         }
         Commands::Ohlcv { coin_id, start, end, interval, limit, quote } => {
-            commands::ohlcv::execute_historical(&client, &coin_id, &start, end.as_deref(), &interval, limit, &quote, output, raw).await
+            let params = historical_params(start, end, interval, limit).quote(quote);
+            commands::ohlcv::execute_historical(client, &coin_id, &params).await
+        }
+        Commands::OhlcvLatest { coin_ids, quote, batch } => match batch.single(&coin_ids) {
+            Some(coin_id) => commands::ohlcv::execute_latest(client, coin_id, &quote).await,
+            None => commands::ohlcv::execute_latest_many(client, &coin_ids, &quote, batch.parallel).await,
+        },
+        Commands::OhlcvToday { coin_id, quote } => commands::ohlcv::execute_today(client, &coin_id, &quote).await,
+        Commands::Exchanges { limit, quotes } => commands::exchanges::execute_list(client, limit, &quotes).await,
+        Commands::Exchange { exchange_id, quotes } => commands::exchanges::execute_detail(client, &exchange_id, &quotes).await,
+        Commands::ExchangeMarkets { exchange_id, limit, quotes } => commands::exchanges::execute_markets(client, &exchange_id, limit, &quotes).await,
+        Commands::Tags { limit } => commands::tags::execute_list(client, limit).await,
+        Commands::Tag { tag_id } => commands::tags::execute_detail(client, &tag_id).await,
+        Commands::Person { person_id } => commands::people::execute(client, &person_id).await,
+        Commands::Search { query, categories, limit, modifier, local: true } => {
+            commands::search::execute_local(client.base_url(), &query, categories.as_deref(), limit, modifier.as_deref())
         }
-        Commands::OhlcvLatest { coin_id, quote } => commands::ohlcv::execute_latest(&client, &coin_id, &quote, output, raw).await,
-        Commands::OhlcvToday { coin_id, quote } => commands::ohlcv::execute_today(&client, &coin_id, &quote, output, raw).await,
-        Commands::Exchanges { limit, quotes } => commands::exchanges::execute_list(&client, limit, &quotes, output, raw).await,
-        Commands::Exchange { exchange_id, quotes } => commands::exchanges::execute_detail(&client, &exchange_id, &quotes, output, raw).await,
-        Commands::ExchangeMarkets { exchange_id, limit, quotes } => commands::exchanges::execute_markets(&client, &exchange_id, limit, &quotes, output, raw).await,
-        Commands::Tags { limit } => commands::tags::execute_list(&client, limit, output, raw).await,
-        Commands::Tag { tag_id } => commands::tags::execute_detail(&client, &tag_id, output, raw).await,
-        Commands::Person { person_id } => commands::people::execute(&client, &person_id, output, raw).await,
-        Commands::Search { query, categories, limit, modifier } => {
-            commands::search::execute(&client, &query, categories.as_deref(), limit, modifier.as_deref(), output, raw).await
+        Commands::Search { query, categories, limit, modifier, local: false } => {
+            commands::search::execute(client, &query, categories.as_deref(), limit, modifier.as_deref()).await
         }
-        Commands::Convert { base_id, quote_id, amount } => commands::convert::execute(&client, &base_id, &quote_id, amount, output, raw).await,
-        Commands::Platforms => commands::contracts::execute_platforms(&client, output, raw).await,
-        Commands::Contracts { platform_id, limit } => commands::contracts::execute_contracts(&client, &platform_id, limit, output, raw).await,
-        Commands::ContractTicker { platform_id, address } => commands::contracts::execute_ticker(&client, &platform_id, &address, output, raw).await,
+        Commands::Convert { base_id, quote_id, amount } => commands::convert::execute(client, &base_id, &quote_id, amount).await,
+        Commands::Platforms => commands::contracts::execute_platforms(client).await,
+        Commands::Contracts { platform_id, limit } => commands::contracts::execute_contracts(client, &platform_id, limit).await,
+        Commands::ContractTicker { platform_id, addresses, batch } => match batch.single(&addresses) {
+            Some(address) => commands::contracts::execute_ticker(client, &platform_id, address).await,
+            None => commands::contracts::execute_ticker_many(client, &platform_id, &addresses, batch.parallel).await,
+        },
         Commands::ContractHistory { platform_id, address, start, end, interval, limit } => {
-            commands::contracts::execute_history(&client, &platform_id, &address, &start, end.as_deref(), &interval, limit, output, raw).await
+            let params = historical_params(start, end, interval, limit);
+            commands::contracts::execute_history(client, &platform_id, &address, &params).await
         }
-        Commands::KeyInfo => commands::api_management::execute_key_info(&client, has_api_key, output, raw).await,
-        Commands::Mappings => commands::api_management::execute_mappings(&client, output, raw).await,
-        Commands::Changelog { limit, page } => commands::api_management::execute_changelog(&client, limit, page, output, raw).await,
-        Commands::Config(cmd) => commands::config::execute(cmd, output, raw).await,
-        Commands::Status => commands::status::execute(&client, output, raw).await,
-        Commands::Attribution => commands::attribution::execute(output, raw),
-        Commands::Shell => {
-            shell::run_shell().await;
-            Ok(())
+        Commands::KeyInfo => commands::api_management::execute_key_info(client, settings.api_key().is_some()).await,
+        Commands::Mappings { command: Some(cmd), .. } => commands::mappings::execute(client, cmd).await,
+        Commands::Mappings { command: None, limit } => commands::mappings::execute_list(client, limit).await,
+        Commands::Changelog { command: Some(cmd), .. } => commands::changelog::execute(client, cmd).await,
+        Commands::Changelog { command: None, limit, page } => commands::changelog::execute_list(client, limit, page).await,
+        Commands::Index(cmd) => commands::index::execute(client, cmd).await,
+        Commands::Config(cmd) => commands::config::execute(cmd, settings),
+        Commands::Status(args) => commands::status::execute(client, &args, settings.api_key().is_some()).await,
+        Commands::Doctor => Ok(commands::doctor::execute(settings).await),
+        Commands::Attribution => Ok(commands::attribution::execute()),
+        Commands::Plans => Ok(commands::plans::execute()),
+        Commands::Schema { command } => commands::schema::execute(command.as_deref()),
+        Commands::Api(args) => commands::api::execute(client, args).await,
+        Commands::Plugins(cmd) => commands::plugins::execute(cmd),
+        Commands::External(args) => Err(commands::plugins::not_runnable_here(&args)),
+        Commands::Shell
+        | Commands::Completions { .. }
+        | Commands::Complete { .. }
+        | Commands::Onboard { .. }
+        | Commands::MockServer(_)
+        | Commands::Batch(_)
+        | Commands::Exec(_)
+        | Commands::Script(_) => {
+            anyhow::bail!("interactive commands cannot be run here")
         }
-        Commands::Plans => commands::plans::execute(output, raw),
-        Commands::Onboard { key } => commands::onboard::execute(key).await,
+    };
+    commands::multi::with_errors(result, unresolved)
+}
+
+/// Append IDs from `--ids-from` to commands that take several.
+fn read_batch_ids(command: &mut Commands) -> anyhow::Result<()> {
+    match command {
+        Commands::Coin { coin_ids, batch }
+        | Commands::CoinEvents { coin_ids, batch, .. }
+        | Commands::Ticker { coin_ids, batch, .. }
+        | Commands::OhlcvLatest { coin_ids, batch, .. }
+        | Commands::ContractTicker { addresses: coin_ids, batch, .. } => batch.extend_ids(coin_ids),
+        _ => Ok(()),
+    }
+}
+
+/// Replace symbols and names in coin-ID arguments (`BTC`, `bitcoin`) with
+/// CoinPaprika IDs, noting each substitution on stderr. Commands over
+/// several IDs drop the ones that can't be resolved and return them, to be
+/// reported alongside fetch failures.
+async fn resolve_coin_ids(command: &mut Commands, client: &ApiClient) -> anyhow::Result<Vec<ItemError>> {
+    let mut resolver = Resolver::new(client);
+    let ids: Vec<&mut String> = match command {
+        Commands::Coin { coin_ids, batch }
+        | Commands::CoinEvents { coin_ids, batch, .. }
+        | Commands::Ticker { coin_ids, batch, .. }
+        | Commands::OhlcvLatest { coin_ids, batch, .. }
+            if batch.single(coin_ids).is_none() =>
+        {
+            let mut resolved = Vec::new();
+            let mut errors = Vec::new();
+            for id in coin_ids.drain(..) {
+                match resolve_coin_id(&mut resolver, &id).await {
+                    Ok(coin_id) => resolved.push(coin_id),
+                    Err(e) => errors.push(ItemError { id, error: e.to_string() }),
+                }
+            }
+            if resolved.is_empty() {
+                return Err(commands::multi::AllFailed(errors).into());
+            }
+            batch.dropped_ids = !errors.is_empty();
+            *coin_ids = resolved;
+            return Ok(errors);
+        }
+        Commands::Coin { coin_ids, .. }
+        | Commands::CoinEvents { coin_ids, .. }
+        | Commands::Ticker { coin_ids, .. }
+        | Commands::OhlcvLatest { coin_ids, .. } => coin_ids.iter_mut().collect(),
+        Commands::CoinExchanges { coin_id, .. }
+        | Commands::CoinMarkets { coin_id, .. }
+        | Commands::TickerHistory { coin_id, .. }
+        | Commands::Ohlcv { coin_id, .. }
+        | Commands::OhlcvToday { coin_id, .. }
+        | Commands::Mappings { command: Some(commands::mappings::MappingsCommand::For { coin_id }), .. } => vec![coin_id],
+        Commands::Convert { base_id, quote_id, .. } => vec![base_id, quote_id],
+        _ => return Ok(Vec::new()),
+    };
+    for id in ids {
+        *id = resolve_coin_id(&mut resolver, id).await?;
     }
+    Ok(Vec::new())
+}
+
+async fn resolve_coin_id(resolver: &mut Resolver<'_>, id: &str) -> anyhow::Result<String> {
+    let Some(resolution) = resolver.resolve(id).await? else {
+        return Ok(id.to_string());
+    };
+    if resolution.id != id {
+        let rank = resolution.rank.filter(|r| *r > 0).map(|r| format!(", rank {r}")).unwrap_or_default();
+        eprintln!("Resolved '{id}' to {} ({}{rank})", resolution.id, resolution.kind);
+    }
+    Ok(resolution.id)
+}
+
+fn historical_params(start: Period, end: Option<Period>, interval: Interval, limit: usize) -> HistoricalParams {
+    let params = HistoricalParams::new(start).interval(interval).limit(limit);
+    match end {
+        Some(end) => params.end(end),
+        None => params,
+    }
+}
+
+/// Parse arguments, listing plugins in `--help`. PATH is only scanned when
+/// help is asked for.
+fn parse_cli() -> Cli {
+    let argv: Vec<String> = std::env::args().collect();
+    let mut command = cli_command(&defaults_for(&argv));
+    let wants_help = argv.len() == 1 || argv.iter().skip(1).any(|a| a == "-h" || a == "--help" || a == "help");
+    if wants_help {
+        if let Some(section) = commands::plugins::help_section() {
+            command = command.after_help(section);
+        }
+    }
+    Cli::from_arg_matches(&command.get_matches_from(argv)).unwrap_or_else(|e| e.exit())
+}
+
+/// Parse `argv` with the profile's quotes and output as defaults.
+pub(crate) fn try_parse(argv: &[String], settings: &Settings) -> Result<Cli, clap::Error> {
+    Cli::from_arg_matches(&cli_command(settings).try_get_matches_from(argv)?)
+}
+
+/// The CLI definition with configured defaults in place of the built-in
+/// ones, so explicit flags still win. Lowest first: global `defaults`, the
+/// profile, then per-command `defaults` such as `tickers.limit`.
+pub(crate) fn cli_command(settings: &Settings) -> clap::Command {
+    let mut command = Cli::command();
+    let (global, per_command): (Vec<_>, Vec<_>) = settings.defaults.iter().partition(|(key, _)| !key.contains('.'));
+    for (id, value) in global {
+        command = set_default(command, id, value);
+    }
+    if let Some(output) = &settings.output {
+        command = set_default(command, "output", &output.value);
+    }
+    if let Some(quotes) = &settings.quotes {
+        command = set_default(command, "quotes", &quotes.value);
+    }
+    for (key, value) in per_command {
+        if let Some((path, id)) = key.rsplit_once('.') {
+            let path: Vec<&str> = path.split('.').collect();
+            command = set_command_default(command, &path, id, value);
+        }
+    }
+    command
+}
+
+/// Set the default of `id` in `command` and all its subcommands.
+fn set_default(mut command: clap::Command, id: &str, value: &str) -> clap::Command {
+    if command.get_arguments().any(|a| a.get_id() == id) {
+        command = command.mut_arg(id, |a| a.default_value(value.to_string()));
+    }
+    let names: Vec<String> = command.get_subcommands().map(|c| c.get_name().to_string()).collect();
+    for name in names {
+        command = command.mut_subcommand(name, |c| set_default(c, id, value));
+    }
+    command
+}
+
+/// Set the default of `id` in the subcommand at `path` only.
+fn set_command_default(command: clap::Command, path: &[&str], id: &str, value: &str) -> clap::Command {
+    match path.split_first() {
+        None if command.get_arguments().any(|a| a.get_id() == id) => command.mut_arg(id, |a| a.default_value(value.to_string())),
+        None => command,
+        Some((name, rest)) if command.get_subcommands().any(|c| c.get_name() == *name) => {
+            command.mut_subcommand(*name, |c| set_command_default(c, rest, id, value))
+        }
+        Some(_) => command,
+    }
+}
+
+/// Settings for picking defaults before `argv` is parsed: only `--profile`
+/// is read from it. A bad profile is reported once the command runs.
+pub(crate) fn defaults_for(argv: &[String]) -> Settings {
+    let mut args = argv.iter().take_while(|a| *a != "--");
+    let mut profile = None;
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            profile = args.next().cloned();
+        } else if let Some(name) = arg.strip_prefix("--profile=") {
+            profile = Some(name.to_string());
+        }
+    }
+    Settings::resolve(profile.as_deref(), None, None).unwrap_or_default()
 }
 
 #[tokio::main]
 async fn main() -> ExitCode {
-    let cli = Cli::parse();
+    // Completion runs on every Tab, so it only reads.
+    if std::env::args().nth(1).as_deref() == Some("__complete") {
+        coinpaprika_cli::config::read_only();
+    } else {
+        coinpaprika_cli::config::adopt_legacy_dirs();
+    }
+    let cli = parse_cli();
     let output = cli.output;
 
     if let Err(e) = run(cli).await {
+        if let Some(exit) = e.downcast_ref::<commands::plugins::PluginExit>() {
+            return ExitCode::from(u8::try_from(exit.code).unwrap_or(1));
+        }
+        if let Some(reported) = e.downcast_ref::<commands::AlreadyReported>() {
+            eprintln!("Error: {reported}");
+            return ExitCode::FAILURE;
+        }
         match output {
             OutputFormat::Json => {
                 println!(
@@ -418,7 +830,7 @@ async fn main() -> ExitCode {
                     serde_json::json!({"error": e.to_string()})
                 );
             }
-            OutputFormat::Table => {
+            OutputFormat::Table | OutputFormat::Csv => {
                 eprintln!("Error: {e}");
             }
         }
@@ -426,3 +838,24 @@ async fn main() -> ExitCode {
     }
     ExitCode::SUCCESS
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::{Setting, Source};
+
+    #[test]
+    fn profile_defaults_yield_to_flags() {
+        let profile = |value: &str| Some(Setting { value: value.into(), source: Source::Profile("team".into()) });
+        let settings = Settings { quotes: profile("EUR"), output: profile("json"), ..Settings::default() };
+        let argv = |line: &str| line.split(' ').map(String::from).collect::<Vec<_>>();
+
+        let cli = try_parse(&argv("coinpaprika-cli ticker btc-bitcoin"), &settings).unwrap();
+        assert!(matches!(cli.output, OutputFormat::Json));
+        assert!(matches!(cli.command, Commands::Ticker { quotes, .. } if quotes == "EUR"));
+
+        let cli = try_parse(&argv("coinpaprika-cli ticker btc-bitcoin --quotes USD -o csv"), &settings).unwrap();
+        assert!(matches!(cli.output, OutputFormat::Csv));
+        assert!(matches!(cli.command, Commands::Ticker { quotes, .. } if quotes == "USD"));
+    }
+}
@@ -0,0 +1,89 @@
+//! Cached `/coins/mappings` with lookups by external provider IDs.
+
+use anyhow::{bail, Result};
+use chrono::Duration;
+
+use crate::cache;
+use crate::types::CoinMapping;
+use crate::ApiClient;
+
+/// How long the cached mappings are trusted before they are refetched.
+pub const MAPPINGS_MAX_AGE: Duration = Duration::hours(24);
+
+/// Short names accepted by `--from`, e.g. `cmc:1`.
+const PROVIDER_ALIASES: &[(&str, &str)] = &[
+    ("cmc", "coinmarketcap"),
+    ("cg", "coingecko"),
+    ("gecko", "coingecko"),
+    ("cc", "cryptocompare"),
+    ("paprika", "coinpaprika"),
+];
+
+pub async fn cached_mappings(client: &ApiClient) -> Result<Vec<CoinMapping>> {
+    Ok(cache::get_or_fetch(&client.cache_name("mappings"), MAPPINGS_MAX_AGE, || client.mappings()).await?.data)
+}
+
+/// Expand an alias such as `cmc` to the provider key used by the API.
+pub fn provider_name(alias: &str) -> String {
+    let alias = alias.trim().to_lowercase();
+    PROVIDER_ALIASES
+        .iter()
+        .find(|(short, _)| *short == alias)
+        .map(|(_, full)| full.to_string())
+        .unwrap_or(alias)
+}
+
+/// Split `provider:id` into an expanded provider name and the ID.
+pub fn parse_reference(reference: &str) -> Result<(String, String)> {
+    match reference.split_once(':') {
+        Some((provider, id)) if !provider.is_empty() && !id.is_empty() => Ok((provider_name(provider), id.to_string())),
+        _ => bail!("Expected PROVIDER:ID, e.g. coingecko:bitcoin or cmc:1, got '{reference}'"),
+    }
+}
+
+/// Every provider named in `mappings`, plus `coinpaprika`.
+pub fn providers(mappings: &[CoinMapping]) -> Vec<String> {
+    let mut names: Vec<String> = mappings.iter().flat_map(|m| m.providers.keys().cloned()).collect();
+    names.sort();
+    names.dedup();
+    names.insert(0, "coinpaprika".into());
+    names
+}
+
+/// Mappings whose `provider` ID equals `id` (case-insensitive).
+pub fn lookup<'a>(mappings: &'a [CoinMapping], provider: &str, id: &str) -> Vec<&'a CoinMapping> {
+    mappings.iter().filter(|m| m.id_for(provider).is_some_and(|v| v.eq_ignore_ascii_case(id))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mappings() -> Vec<CoinMapping> {
+        serde_json::from_str(
+            r#"[
+                {"coinpaprika": "btc-bitcoin", "coinmarketcap": 1, "coingecko": "bitcoin", "isin": null},
+                {"coinpaprika": "eth-ethereum", "coinmarketcap": "1027", "coingecko": "ethereum"}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn numeric_and_string_ids_both_parse() {
+        let m = mappings();
+        assert_eq!(m[0].id_for("coinmarketcap"), Some("1"));
+        assert_eq!(m[1].id_for("coinmarketcap"), Some("1027"));
+        assert!(!m[0].providers.contains_key("isin"));
+    }
+
+    #[test]
+    fn looks_up_by_alias() {
+        let m = mappings();
+        let (provider, id) = parse_reference("cmc:1027").unwrap();
+        assert_eq!(lookup(&m, &provider, &id)[0].coinpaprika, "eth-ethereum");
+        let (provider, id) = parse_reference("coingecko:Bitcoin").unwrap();
+        assert_eq!(lookup(&m, &provider, &id)[0].coinpaprika, "btc-bitcoin");
+        assert!(parse_reference("bitcoin").is_err());
+    }
+}
@@ -0,0 +1,839 @@
+//! Canned, seed-deterministic responses for every endpoint the CLI calls.
+
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// (id, name, symbol, approximate USD price)
+const COINS: &[(&str, &str, &str, f64)] = &[
+    ("btc-bitcoin", "Bitcoin", "BTC", 64_000.0),
+    ("eth-ethereum", "Ethereum", "ETH", 3_100.0),
+    ("usdt-tether", "Tether", "USDT", 1.0),
+    ("bnb-binance-coin", "BNB", "BNB", 560.0),
+    ("sol-solana", "Solana", "SOL", 145.0),
+    ("usdc-usd-coin", "USDC", "USDC", 1.0),
+    ("xrp-xrp", "XRP", "XRP", 0.52),
+    ("doge-dogecoin", "Dogecoin", "DOGE", 0.13),
+    ("ada-cardano", "Cardano", "ADA", 0.45),
+    ("trx-tron", "TRON", "TRX", 0.12),
+    ("avax-avalanche", "Avalanche", "AVAX", 28.0),
+    ("link-chainlink", "Chainlink", "LINK", 14.0),
+    ("dot-polkadot", "Polkadot", "DOT", 6.2),
+    ("matic-polygon", "Polygon", "MATIC", 0.62),
+    ("ltc-litecoin", "Litecoin", "LTC", 78.0),
+    ("bch-bitcoin-cash", "Bitcoin Cash", "BCH", 390.0),
+    ("uni-uniswap", "Uniswap", "UNI", 7.8),
+    ("xlm-stellar", "Stellar", "XLM", 0.1),
+    ("atom-cosmos", "Cosmos", "ATOM", 7.1),
+    ("etc-ethereum-classic", "Ethereum Classic", "ETC", 24.0),
+];
+
+const FIATS: &[(&str, &str, &str, f64)] = &[
+    ("usd-us-dollars", "US Dollars", "USD", 1.0),
+    ("eur-euro", "Euro", "EUR", 1.08),
+];
+
+/// (id, name, 24h adjusted USD volume)
+const EXCHANGES: &[(&str, &str, f64)] = &[
+    ("binance", "Binance", 9.5e9),
+    ("coinbase", "Coinbase", 2.1e9),
+    ("kraken", "Kraken", 1.1e9),
+    ("okx", "OKX", 2.8e9),
+    ("bybit", "Bybit", 2.4e9),
+    ("kucoin", "KuCoin", 6.0e8),
+    ("bitstamp", "Bitstamp", 1.9e8),
+    ("gemini", "Gemini", 1.2e8),
+];
+
+/// (id, name, coins)
+const TAGS: &[(&str, &str, &[&str])] = &[
+    ("cryptocurrency", "Cryptocurrency", &["btc-bitcoin", "ltc-litecoin", "bch-bitcoin-cash", "doge-dogecoin", "xrp-xrp"]),
+    ("smart-contracts", "Smart Contracts", &["eth-ethereum", "sol-solana", "ada-cardano", "avax-avalanche", "dot-polkadot"]),
+    ("defi", "DeFi", &["uni-uniswap", "link-chainlink"]),
+    ("stablecoin", "Stablecoin", &["usdt-tether", "usdc-usd-coin"]),
+    ("layer-1", "Layer 1", &["btc-bitcoin", "eth-ethereum", "sol-solana", "avax-avalanche", "atom-cosmos"]),
+    ("payments", "Payments", &["xrp-xrp", "xlm-stellar", "ltc-litecoin"]),
+];
+
+/// (coin_id, position)
+type Role = (&'static str, &'static str);
+
+/// (id, name, roles)
+const PEOPLE: &[(&str, &str, &[Role])] = &[
+    ("vitalik-buterin", "Vitalik Buterin", &[("eth-ethereum", "Founder")]),
+    ("satoshi-nakamoto", "Satoshi Nakamoto", &[("btc-bitcoin", "Founder")]),
+    ("charles-hoskinson", "Charles Hoskinson", &[("ada-cardano", "Founder"), ("eth-ethereum", "Co-Founder")]),
+];
+
+/// (platform_id, address, coin_id)
+const CONTRACTS: &[(&str, &str, &str)] = &[
+    ("eth-ethereum", "0xdac17f958d2ee523a2206206994597c13d831ec7", "usdt-tether"),
+    ("eth-ethereum", "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "usdc-usd-coin"),
+    ("eth-ethereum", "0x514910771af9ca656af840dff83e8264ecf986ca", "link-chainlink"),
+    ("eth-ethereum", "0x1f9840a85d5af5bf1d1762f925bdddc4201f984", "uni-uniswap"),
+    ("bnb-binance-coin", "0x55d398326f99059ff775485246999027b3197955", "usdt-tether"),
+    ("sol-solana", "es9vmfrzacermjfrf4h2fyd4kconky11mcce8benwnyb", "usdt-tether"),
+];
+
+/// Symbols of coins that live on another chain.
+const TOKENS: &[&str] = &["USDT", "USDC", "LINK", "UNI"];
+
+/// (coinmarketcap id, coingecko id) per coin, in `COINS` order.
+const EXTERNAL_IDS: &[(u32, &str)] = &[
+    (1, "bitcoin"),
+    (1027, "ethereum"),
+    (825, "tether"),
+    (1839, "binancecoin"),
+    (5426, "solana"),
+    (3408, "usd-coin"),
+    (52, "ripple"),
+    (74, "dogecoin"),
+    (2010, "cardano"),
+    (1958, "tron"),
+    (5805, "avalanche-2"),
+    (1975, "chainlink"),
+    (6636, "polkadot"),
+    (3890, "matic-network"),
+    (2, "litecoin"),
+    (1831, "bitcoin-cash"),
+    (7083, "uniswap"),
+    (512, "stellar"),
+    (3794, "cosmos"),
+    (1321, "ethereum-classic"),
+];
+
+/// A request the mock can answer: path below `/v1` plus decoded query params.
+pub struct Request<'a> {
+    pub path: &'a str,
+    pub params: &'a HashMap<String, String>,
+    pub authenticated: bool,
+}
+
+impl Request<'_> {
+    fn param(&self, key: &str) -> Option<&str> {
+        self.params.get(key).map(String::as_str)
+    }
+
+    fn limit(&self) -> Option<usize> {
+        self.param("limit").and_then(|l| l.parse().ok())
+    }
+}
+
+/// splitmix64: tiny, seedable, and good enough for fake market data.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// A generator whose stream depends only on `seed` and `key`, so the same
+    /// entity looks the same no matter which request asked for it.
+    pub fn for_key(seed: u64, key: &str) -> Self {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for b in key.bytes() {
+            hash ^= u64::from(b);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        Self(seed ^ hash)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn range(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.next_f64()
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n.max(1) as u64) as usize
+    }
+}
+
+/// Answer a request with `(status, body)`.
+pub fn respond(seed: u64, req: &Request) -> (u16, Value) {
+    let segments: Vec<&str> = req.path.trim_matches('/').split('/').filter(|s| !s.is_empty()).collect();
+    let found = match segments.as_slice() {
+        ["global"] => Some(global(seed)),
+        ["coins"] => Some(coins()),
+        ["coins", "mappings"] => Some(mappings()),
+        ["coins", id] => coin(id),
+        ["coins", id, "events"] => coin_index(id).map(|_| events(seed, id)),
+        ["coins", id, "exchanges"] => coin_index(id).map(|_| coin_exchanges(seed, id)),
+        ["coins", id, "markets"] => coin_index(id).map(|_| coin_markets(seed, id, req)),
+        ["coins", id, "ohlcv", "historical"] => coin_index(id).map(|_| ohlcv_historical(seed, id, req)),
+        ["coins", id, "ohlcv", "latest"] => coin_index(id).map(|_| ohlcv_day(seed, id, day_start(Utc::now()) - Duration::days(1))),
+        ["coins", id, "ohlcv", "today"] => coin_index(id).map(|_| ohlcv_day(seed, id, day_start(Utc::now()))),
+        ["tickers"] => Some(tickers(seed, req)),
+        ["tickers", id] => coin_index(id).map(|i| ticker(seed, i, req)),
+        ["tickers", id, "historical"] => coin_index(id).map(|_| ticker_history(seed, id, req)),
+        ["exchanges"] => Some(exchanges(seed)),
+        ["exchanges", id] => EXCHANGES.iter().position(|e| e.0 == *id).map(|i| exchange(seed, i)),
+        ["exchanges", id, "markets"] => EXCHANGES.iter().any(|e| e.0 == *id).then(|| exchange_markets(seed, id)),
+        ["tags"] => Some(tags()),
+        ["tags", id] => TAGS.iter().find(|t| t.0 == *id).map(tag),
+        ["people", id] => PEOPLE.iter().find(|p| p.0 == *id).map(person),
+        ["search"] => Some(search(req)),
+        ["price-converter"] => price_converter(seed, req),
+        ["contracts"] => Some(platforms()),
+        ["contracts", platform] => CONTRACTS.iter().any(|c| c.0 == *platform).then(|| contracts(platform)),
+        ["contracts", platform, address] => contract(platform, address).map(|i| ticker(seed, i, req)),
+        ["contracts", platform, address, "historical"] => contract(platform, address).map(|i| ticker_history(seed, COINS[i].0, req)),
+        ["key", "info"] => {
+            if !req.authenticated {
+                return (403, json!({"error": "invalid API key"}));
+            }
+            Some(key_info(seed))
+        }
+        ["changelog", "ids"] => Some(changelog(req)),
+        _ => None,
+    };
+    match found {
+        Some(body) => (200, body),
+        None => (404, json!({"error": "id not found"})),
+    }
+}
+
+fn coin_index(id: &str) -> Option<usize> {
+    COINS.iter().position(|c| c.0 == id)
+}
+
+fn contract(platform: &str, address: &str) -> Option<usize> {
+    let address = address.to_lowercase();
+    CONTRACTS
+        .iter()
+        .find(|c| c.0 == platform && c.1 == address)
+        .and_then(|c| coin_index(c.2))
+}
+
+/// Today's price: the reference price moved by up to ±5%.
+fn price(seed: u64, index: usize) -> f64 {
+    let (id, _, _, base) = COINS[index];
+    base * Rng::for_key(seed, id).range(0.95, 1.05)
+}
+
+fn usd_price(seed: u64, id: &str) -> Option<f64> {
+    if let Some(i) = coin_index(id) {
+        return Some(price(seed, i));
+    }
+    FIATS.iter().find(|f| f.0 == id).map(|f| f.3)
+}
+
+fn supply(index: usize) -> f64 {
+    // Market cap ranks follow COINS order, so scale supply to keep it that way.
+    let target_cap = 1.2e12 / (index as f64 + 1.0).powf(1.6);
+    (target_cap / COINS[index].3).round()
+}
+
+fn timestamp(t: DateTime<Utc>) -> String {
+    t.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+fn day_start(t: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&t.date_naive().and_hms_opt(0, 0, 0).expect("midnight is valid"))
+}
+
+fn global(seed: u64) -> Value {
+    let mut rng = Rng::for_key(seed, "global");
+    let market_cap: f64 = (0..COINS.len()).map(|i| price(seed, i) * supply(i)).sum::<f64>() * 1.15;
+    json!({
+        "market_cap_usd": market_cap.round(),
+        "volume_24h_usd": (market_cap * rng.range(0.03, 0.06)).round(),
+        "bitcoin_dominance_percentage": (price(seed, 0) * supply(0) / market_cap * 10_000.0).round() / 100.0,
+        "cryptocurrencies_number": 8_000 + rng.below(2_000),
+        "market_cap_ath_value": (market_cap * 1.2).round(),
+        "market_cap_ath_date": "2021-11-10T16:05:00Z",
+        "volume_24h_ath_value": 3.3e11,
+        "volume_24h_ath_date": "2021-05-19T12:00:00Z",
+        "volume_24h_percent_from_ath": -72.5,
+        "volume_24h_percent_to_ath": 264.1,
+        "market_cap_change_24h": (rng.range(-4.0, 4.0) * 100.0).round() / 100.0,
+        "volume_24h_change_24h": (rng.range(-15.0, 15.0) * 100.0).round() / 100.0,
+        "last_updated": Utc::now().timestamp(),
+    })
+}
+
+fn coin_summary(index: usize) -> Value {
+    let (id, name, symbol, _) = COINS[index];
+    json!({
+        "id": id,
+        "name": name,
+        "symbol": symbol,
+        "rank": index + 1,
+        "is_new": false,
+        "is_active": true,
+        "type": if TOKENS.contains(&symbol) { "token" } else { "coin" },
+    })
+}
+
+fn coins() -> Value {
+    Value::Array((0..COINS.len()).map(coin_summary).collect())
+}
+
+fn coin(id: &str) -> Option<Value> {
+    let index = coin_index(id)?;
+    let mut detail = coin_summary(index);
+    let (_, name, _, _) = COINS[index];
+    let tags: Vec<Value> = TAGS
+        .iter()
+        .filter(|t| t.2.contains(&id))
+        .map(|t| json!({"id": t.0, "name": t.1, "coin_counter": t.2.len(), "ico_counter": 0}))
+        .collect();
+    let team: Vec<Value> = PEOPLE
+        .iter()
+        .filter_map(|p| p.2.iter().find(|(c, _)| *c == id).map(|(_, pos)| json!({"id": p.0, "name": p.1, "position": pos})))
+        .collect();
+    let extra = json!({
+        "logo": format!("https://static.coinpaprika.com/coin/{id}/logo.png"),
+        "description": format!("{name} is a mock cryptocurrency served by coinpaprika-cli mock-server."),
+        "open_source": true,
+        "started_at": "2015-07-30T00:00:00Z",
+        "development_status": "Working product",
+        "hardware_wallet": true,
+        "proof_type": if index == 0 { "Proof of Work" } else { "Proof of Stake" },
+        "org_structure": "Decentralized",
+        "hash_algorithm": if index == 0 { "SHA256" } else { "" },
+        "tags": tags,
+        "team": team,
+        "links": {"website": [format!("https://{}.example", id)]},
+        "whitepaper": {"link": format!("https://static.coinpaprika.com/storage/cdn/whitepapers/{id}.pdf"), "thumbnail": null},
+        "first_data_at": "2013-04-28T18:47:21Z",
+        "last_data_at": timestamp(Utc::now()),
+    });
+    if let (Value::Object(d), Value::Object(e)) = (&mut detail, extra) {
+        d.extend(e);
+    }
+    Some(detail)
+}
+
+fn events(seed: u64, id: &str) -> Value {
+    let mut rng = Rng::for_key(seed, &format!("events/{id}"));
+    let anchor = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).single().expect("valid date");
+    let kinds = ["Mainnet upgrade", "Conference keynote", "Community AMA", "Exchange listing", "Hackathon"];
+    Value::Array(
+        (0..5)
+            .map(|i| {
+                let date = anchor - Duration::days(rng.below(400) as i64);
+                let name = kinds[i % kinds.len()];
+                json!({
+                    "id": format!("{}-event-{}", id, i + 1),
+                    "date": timestamp(date),
+                    "date_to": null,
+                    "name": name,
+                    "description": format!("{name} for {id}"),
+                    "is_conference": name.contains("Conference"),
+                    "link": format!("https://example.com/{id}/events/{}", i + 1),
+                    "proof_image_link": null,
+                })
+            })
+            .collect(),
+    )
+}
+
+fn coin_exchanges(seed: u64, id: &str) -> Value {
+    let mut rng = Rng::for_key(seed, &format!("coin-exchanges/{id}"));
+    let mut shares: Vec<f64> = EXCHANGES.iter().map(|_| rng.range(1.0, 10.0)).collect();
+    let total: f64 = shares.iter().sum();
+    shares.iter_mut().for_each(|s| *s = (*s / total * 10_000.0).round() / 100.0);
+    Value::Array(
+        EXCHANGES
+            .iter()
+            .zip(shares)
+            .map(|((eid, name, _), share)| {
+                json!({
+                    "id": eid,
+                    "name": name,
+                    "adjusted_volume_24h_share": share,
+                    "fiats": [{"name": "US Dollars", "symbol": "USD"}],
+                })
+            })
+            .collect(),
+    )
+}
+
+fn quote_block(seed: u64, index: usize, quote_id_price: f64, key: &str) -> Value {
+    let mut rng = Rng::for_key(seed, key);
+    let usd = price(seed, index);
+    let p = usd / quote_id_price;
+    let market_cap = p * supply(index);
+    json!({
+        "price": p,
+        "volume_24h": market_cap * rng.range(0.01, 0.08),
+        "volume_24h_change_24h": (rng.range(-20.0, 20.0) * 100.0).round() / 100.0,
+        "market_cap": market_cap.round(),
+        "market_cap_change_24h": (rng.range(-5.0, 5.0) * 100.0).round() / 100.0,
+        "percent_change_15m": (rng.range(-0.5, 0.5) * 100.0).round() / 100.0,
+        "percent_change_30m": (rng.range(-0.8, 0.8) * 100.0).round() / 100.0,
+        "percent_change_1h": (rng.range(-1.5, 1.5) * 100.0).round() / 100.0,
+        "percent_change_6h": (rng.range(-3.0, 3.0) * 100.0).round() / 100.0,
+        "percent_change_12h": (rng.range(-4.0, 4.0) * 100.0).round() / 100.0,
+        "percent_change_24h": (rng.range(-6.0, 6.0) * 100.0).round() / 100.0,
+        "percent_change_7d": (rng.range(-12.0, 12.0) * 100.0).round() / 100.0,
+        "percent_change_30d": (rng.range(-25.0, 25.0) * 100.0).round() / 100.0,
+        "percent_change_1y": (rng.range(-50.0, 150.0) * 100.0).round() / 100.0,
+        "ath_price": p * rng.range(1.05, 2.5),
+        "ath_date": "2021-11-10T16:50:00Z",
+        "percent_from_price_ath": -(rng.range(5.0, 60.0) * 100.0).round() / 100.0,
+    })
+}
+
+/// USD price of a quote currency symbol (`USD`, `EUR`, `BTC`, `ETH`, ...).
+fn quote_price(seed: u64, symbol: &str) -> Option<f64> {
+    let symbol = symbol.to_uppercase();
+    if let Some(f) = FIATS.iter().find(|f| f.2 == symbol) {
+        return Some(f.3);
+    }
+    COINS.iter().position(|c| c.2 == symbol).map(|i| price(seed, i))
+}
+
+fn quotes_for(seed: u64, index: usize, req: &Request) -> Value {
+    let requested = req.param("quotes").unwrap_or("USD");
+    let mut quotes = serde_json::Map::new();
+    for symbol in requested.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        if let Some(qp) = quote_price(seed, symbol) {
+            let key = format!("ticker/{}/{}", COINS[index].0, symbol.to_uppercase());
+            quotes.insert(symbol.to_uppercase(), quote_block(seed, index, qp, &key));
+        }
+    }
+    Value::Object(quotes)
+}
+
+fn ticker(seed: u64, index: usize, req: &Request) -> Value {
+    let (id, name, symbol, _) = COINS[index];
+    let supply = supply(index);
+    json!({
+        "id": id,
+        "name": name,
+        "symbol": symbol,
+        "rank": index + 1,
+        "circulating_supply": supply,
+        "total_supply": supply,
+        "max_supply": if index == 0 { 21_000_000.0 } else { 0.0 },
+        "beta_value": (Rng::for_key(seed, &format!("beta/{id}")).range(0.5, 1.5) * 1000.0).round() / 1000.0,
+        "first_data_at": "2013-04-28T18:47:21Z",
+        "last_updated": timestamp(Utc::now()),
+        "quotes": quotes_for(seed, index, req),
+    })
+}
+
+fn tickers(seed: u64, req: &Request) -> Value {
+    let limit = req.limit().unwrap_or(COINS.len());
+    Value::Array((0..COINS.len()).take(limit).map(|i| ticker(seed, i, req)).collect())
+}
+
+fn coin_markets(seed: u64, id: &str, req: &Request) -> Value {
+    let Some(index) = coin_index(id) else { return json!([]) };
+    let symbol = COINS[index].2;
+    let mut rng = Rng::for_key(seed, &format!("markets/{id}"));
+    Value::Array(
+        EXCHANGES
+            .iter()
+            .map(|(eid, ename, _)| {
+                json!({
+                    "exchange_id": eid,
+                    "exchange_name": ename,
+                    "pair": format!("{symbol}/USDT"),
+                    "base_currency_id": id,
+                    "base_currency_name": COINS[index].1,
+                    "quote_currency_id": "usdt-tether",
+                    "quote_currency_name": "Tether",
+                    "market_url": format!("https://{eid}.example/trade/{symbol}_USDT"),
+                    "category": "Spot",
+                    "fee_type": "Percentage",
+                    "outlier": false,
+                    "adjusted_volume_24h_share": (rng.range(0.5, 20.0) * 100.0).round() / 100.0,
+                    "quotes": quotes_for(seed, index, req),
+                    "trust_score": if rng.next_f64() > 0.2 { "high" } else { "medium" },
+                    "last_updated": timestamp(Utc::now()),
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Parse `start`/`end` the way the API does: dates, RFC3339 or unix seconds.
+fn parse_time(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Some(t.with_timezone(&Utc));
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Some(Utc.from_utc_datetime(&d.and_hms_opt(0, 0, 0)?));
+    }
+    s.parse::<i64>().ok().and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+}
+
+fn parse_interval(s: &str) -> Option<Duration> {
+    let (n, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit())?);
+    let n: i64 = n.parse().ok()?;
+    match unit {
+        "m" => Some(Duration::minutes(n)),
+        "h" => Some(Duration::hours(n)),
+        "d" => Some(Duration::days(n)),
+        _ => None,
+    }
+}
+
+/// Timestamps from `start` to `end` (or now) at `interval`, capped at `limit`.
+fn series(req: &Request, default_interval: &str, default_limit: usize) -> Vec<DateTime<Utc>> {
+    let start = req.param("start").and_then(parse_time).unwrap_or_else(|| Utc::now() - Duration::days(30));
+    let end = req.param("end").and_then(parse_time).unwrap_or_else(Utc::now).min(Utc::now());
+    let step = req.param("interval").and_then(parse_interval).unwrap_or_else(|| parse_interval(default_interval).expect("valid default"));
+    let limit = req.limit().unwrap_or(default_limit);
+    let mut points = Vec::new();
+    let mut t = start;
+    while t <= end && points.len() < limit {
+        points.push(t);
+        t += step;
+    }
+    points
+}
+
+/// Random walk around today's price, stable per coin and timestamp.
+fn price_at(seed: u64, id: &str, t: DateTime<Utc>) -> f64 {
+    let index = coin_index(id).unwrap_or(0);
+    let drift = Rng::for_key(seed, &format!("{id}@{}", t.timestamp())).range(0.9, 1.1);
+    price(seed, index) * drift
+}
+
+fn ohlcv_point(seed: u64, id: &str, open_at: DateTime<Utc>, length: Duration) -> Value {
+    let mut rng = Rng::for_key(seed, &format!("ohlcv/{id}/{}", open_at.timestamp()));
+    let open = price_at(seed, id, open_at);
+    let close = price_at(seed, id, open_at + length);
+    let high = open.max(close) * rng.range(1.0, 1.03);
+    let low = open.min(close) * rng.range(0.97, 1.0);
+    let index = coin_index(id).unwrap_or(0);
+    json!({
+        "time_open": timestamp(open_at),
+        "time_close": timestamp(open_at + length - Duration::seconds(1)),
+        "open": open,
+        "high": high,
+        "low": low,
+        "close": close,
+        "volume": (close * supply(index) * rng.range(0.01, 0.05)).round(),
+        "market_cap": (close * supply(index)).round(),
+    })
+}
+
+fn ohlcv_historical(seed: u64, id: &str, req: &Request) -> Value {
+    let step = req.param("interval").and_then(parse_interval).unwrap_or_else(|| Duration::days(1));
+    Value::Array(series(req, "24h", 50).into_iter().map(|t| ohlcv_point(seed, id, t, step)).collect())
+}
+
+fn ohlcv_day(seed: u64, id: &str, day: DateTime<Utc>) -> Value {
+    json!([ohlcv_point(seed, id, day, Duration::days(1))])
+}
+
+fn ticker_history(seed: u64, id: &str, req: &Request) -> Value {
+    let index = coin_index(id).unwrap_or(0);
+    Value::Array(
+        series(req, "24h", 50)
+            .into_iter()
+            .map(|t| {
+                let p = price_at(seed, id, t);
+                json!({
+                    "timestamp": timestamp(t),
+                    "price": p,
+                    "volume_24h": (p * supply(index) * 0.03).round(),
+                    "market_cap": (p * supply(index)).round(),
+                })
+            })
+            .collect(),
+    )
+}
+
+fn exchange(seed: u64, index: usize) -> Value {
+    let (id, name, volume) = EXCHANGES[index];
+    let mut rng = Rng::for_key(seed, &format!("exchange/{id}"));
+    let v24 = volume * rng.range(0.9, 1.1);
+    json!({
+        "id": id,
+        "name": name,
+        "description": format!("{name} is a mock exchange served by coinpaprika-cli mock-server."),
+        "active": true,
+        "website_status": true,
+        "api_status": true,
+        "message": "",
+        "links": {"website": [format!("https://{id}.example")]},
+        "markets_data_fetched": true,
+        "adjusted_rank": index + 1,
+        "reported_rank": index + 1,
+        "currencies": 100 + rng.below(400),
+        "markets": 300 + rng.below(1500),
+        "fiats": [{"name": "US Dollars", "symbol": "USD"}],
+        "quotes": {"USD": {
+            "reported_volume_24h": v24 * 1.3,
+            "adjusted_volume_24h": v24,
+            "reported_volume_7d": v24 * 7.5,
+            "adjusted_volume_7d": v24 * 7.0,
+            "reported_volume_30d": v24 * 32.0,
+            "adjusted_volume_30d": v24 * 30.0,
+        }},
+        "last_updated": timestamp(Utc::now()),
+        "confidence_score": (rng.range(0.6, 0.99) * 1000.0).round() / 1000.0,
+    })
+}
+
+fn exchanges(seed: u64) -> Value {
+    // The real endpoint is unsorted; reverse so clients have to rank.
+    Value::Array((0..EXCHANGES.len()).rev().map(|i| exchange(seed, i)).collect())
+}
+
+fn exchange_markets(seed: u64, exchange_id: &str) -> Value {
+    let mut rng = Rng::for_key(seed, &format!("exchange-markets/{exchange_id}"));
+    Value::Array(
+        COINS
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.2 != "USDT")
+            .map(|(i, (id, name, symbol, _))| {
+                let p = price(seed, i);
+                json!({
+                    "pair": format!("{symbol}/USDT"),
+                    "base_currency_id": id,
+                    "base_currency_name": name,
+                    "quote_currency_id": "usdt-tether",
+                    "quote_currency_name": "Tether",
+                    "market_url": format!("https://{exchange_id}.example/trade/{symbol}_USDT"),
+                    "category": "Spot",
+                    "fee_type": "Percentage",
+                    "outlier": false,
+                    "reported_volume_24h_share": (rng.range(0.1, 15.0) * 100.0).round() / 100.0,
+                    "quotes": {"USD": {"price": p, "volume_24h": (p * supply(i) * rng.range(0.001, 0.01)).round()}},
+                    "trust_score": "high",
+                    "last_updated": timestamp(Utc::now()),
+                })
+            })
+            .collect(),
+    )
+}
+
+fn tag(t: &(&str, &str, &[&str])) -> Value {
+    json!({
+        "id": t.0,
+        "name": t.1,
+        "description": format!("Coins tagged {}", t.1),
+        "type": "technical",
+        "coin_counter": t.2.len(),
+        "ico_counter": 0,
+        "coins": t.2,
+    })
+}
+
+fn tags() -> Value {
+    Value::Array(TAGS.iter().map(tag).collect())
+}
+
+fn person(p: &(&str, &str, &[Role])) -> Value {
+    let positions: Vec<Value> = p
+        .2
+        .iter()
+        .map(|(coin_id, position)| {
+            let name = COINS.iter().find(|c| c.0 == *coin_id).map(|c| c.1).unwrap_or("");
+            json!({"coin_id": coin_id, "coin_name": name, "position": position})
+        })
+        .collect();
+    json!({
+        "id": p.0,
+        "name": p.1,
+        "description": format!("{} is a mock person served by coinpaprika-cli mock-server.", p.1),
+        "teams_count": p.2.len(),
+        "links": {},
+        "positions": positions,
+    })
+}
+
+fn search(req: &Request) -> Value {
+    let q = req.param("q").unwrap_or("").to_lowercase();
+    let limit = req.limit().unwrap_or(6);
+    let categories = req.param("categories").unwrap_or("currencies,exchanges,icos,people,tags");
+    let symbol_only = req.param("modifier") == Some("symbol_search");
+    let wanted = |c: &str| categories.split(',').any(|x| x.trim() == c);
+    let hit = |id: &str, name: &str| id.to_lowercase().contains(&q) || name.to_lowercase().contains(&q);
+
+    let mut result = serde_json::Map::new();
+    if wanted("currencies") {
+        let currencies: Vec<Value> = COINS
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| if symbol_only { c.2.to_lowercase() == q } else { hit(c.0, c.1) || c.2.to_lowercase() == q })
+            .take(limit)
+            .map(|(i, _)| coin_summary(i))
+            .collect();
+        result.insert("currencies".into(), Value::Array(currencies));
+    }
+    if wanted("exchanges") {
+        let exchanges: Vec<Value> = EXCHANGES
+            .iter()
+            .filter(|e| hit(e.0, e.1))
+            .take(limit)
+            .map(|e| json!({"id": e.0, "name": e.1, "rank": EXCHANGES.iter().position(|x| x.0 == e.0).map(|p| p + 1)}))
+            .collect();
+        result.insert("exchanges".into(), Value::Array(exchanges));
+    }
+    if wanted("icos") {
+        result.insert("icos".into(), json!([]));
+    }
+    if wanted("people") {
+        let people: Vec<Value> = PEOPLE
+            .iter()
+            .filter(|p| hit(p.0, p.1))
+            .take(limit)
+            .map(|p| json!({"id": p.0, "name": p.1, "teams_count": p.2.len()}))
+            .collect();
+        result.insert("people".into(), Value::Array(people));
+    }
+    if wanted("tags") {
+        let tags: Vec<Value> = TAGS
+            .iter()
+            .filter(|t| hit(t.0, t.1))
+            .take(limit)
+            .map(|t| json!({"id": t.0, "name": t.1, "coin_counter": t.2.len(), "ico_counter": 0}))
+            .collect();
+        result.insert("tags".into(), Value::Array(tags));
+    }
+    Value::Object(result)
+}
+
+fn price_converter(seed: u64, req: &Request) -> Option<Value> {
+    let base = req.param("base_currency_id")?;
+    let quote = req.param("quote_currency_id")?;
+    let amount: f64 = req.param("amount").and_then(|a| a.parse().ok()).unwrap_or(1.0);
+    let base_price = usd_price(seed, base)?;
+    let quote_price = usd_price(seed, quote)?;
+    let name = |id: &str| {
+        COINS.iter().map(|c| (c.0, c.1)).chain(FIATS.iter().map(|f| (f.0, f.1))).find(|(i, _)| *i == id).map(|(_, n)| n)
+    };
+    Some(json!({
+        "base_currency_id": base,
+        "base_currency_name": name(base),
+        "base_price_last_updated": timestamp(Utc::now()),
+        "quote_currency_id": quote,
+        "quote_currency_name": name(quote),
+        "quote_price_last_updated": timestamp(Utc::now()),
+        "amount": amount,
+        "price": amount * base_price / quote_price,
+    }))
+}
+
+fn platforms() -> Value {
+    let mut platforms: Vec<&str> = CONTRACTS.iter().map(|c| c.0).collect();
+    platforms.dedup();
+    json!(platforms)
+}
+
+fn contracts(platform: &str) -> Value {
+    Value::Array(
+        CONTRACTS
+            .iter()
+            .filter(|c| c.0 == platform)
+            .map(|c| json!({"address": c.1, "type": if platform == "eth-ethereum" { "ERC20" } else { "BEP20" }, "id": c.2, "active": true}))
+            .collect(),
+    )
+}
+
+fn key_info(seed: u64) -> Value {
+    let used = 1_000 + Rng::for_key(seed, "key-info").below(50_000);
+    json!({
+        "plan": "business",
+        "plan_started_at": "2024-01-01T00:00:00Z",
+        "plan_status": "active",
+        "portal_url": "https://coinpaprika.com/api/panel",
+        "usage": {
+            "message": "",
+            "current_month": {"requests_made": used, "requests_left": 3_000_000 - used},
+        },
+    })
+}
+
+fn mappings() -> Value {
+    Value::Array(
+        COINS
+            .iter()
+            .zip(EXTERNAL_IDS)
+            .map(|((id, _, symbol, _), (cmc, gecko))| {
+                json!({
+                    "coinpaprika": id,
+                    "coinmarketcap": cmc.to_string(),
+                    "coingecko": gecko,
+                    "cryptocompare": symbol,
+                })
+            })
+            .collect(),
+    )
+}
+
+/// 120 rename events, newest first, so pagination has several pages.
+fn changelog(req: &Request) -> Value {
+    let limit = req.limit().unwrap_or(50).max(1);
+    let page: usize = req.param("page").and_then(|p| p.parse().ok()).unwrap_or(1).max(1);
+    let base = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).single().expect("valid date");
+    let entries: Vec<Value> = (0..120)
+        .map(|i| {
+            let (id, _, _, _) = COINS[i % COINS.len()];
+            let old_id = format!("{id}-v{}", i / COINS.len() + 1);
+            json!({
+                "currency_id": id,
+                "old_id": old_id,
+                "new_id": id,
+                "changed_at": timestamp(base - Duration::days(i as i64 * 3)),
+            })
+        })
+        .collect();
+    Value::Array(entries.into_iter().skip((page - 1) * limit).take(limit).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(seed: u64, path: &str, params: &[(&str, &str)]) -> (u16, Value) {
+        let params: HashMap<String, String> = params.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        respond(seed, &Request { path, params: &params, authenticated: false })
+    }
+
+    #[test]
+    fn same_seed_same_prices() {
+        let a = get(7, "/tickers/btc-bitcoin", &[]).1["quotes"]["USD"]["price"].clone();
+        let b = get(7, "/tickers/btc-bitcoin", &[]).1["quotes"]["USD"]["price"].clone();
+        let c = get(8, "/tickers/btc-bitcoin", &[]).1["quotes"]["USD"]["price"].clone();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn unknown_ids_are_404() {
+        assert_eq!(get(1, "/tickers/nope-nope", &[]).0, 404);
+        assert_eq!(get(1, "/no/such/endpoint", &[]).0, 404);
+    }
+
+    #[test]
+    fn key_info_requires_auth() {
+        assert_eq!(get(1, "/key/info", &[]).0, 403);
+    }
+
+    #[test]
+    fn ohlcv_respects_range_and_limit() {
+        let (_, body) = get(1, "/coins/btc-bitcoin/ohlcv/historical", &[("start", "2024-01-01"), ("end", "2024-01-10"), ("limit", "5")]);
+        let points = body.as_array().unwrap();
+        assert_eq!(points.len(), 5);
+        assert_eq!(points[0]["time_open"], "2024-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn changelog_paginates() {
+        let page1 = get(1, "/changelog/ids", &[("limit", "50"), ("page", "1")]).1;
+        let page3 = get(1, "/changelog/ids", &[("limit", "50"), ("page", "3")]).1;
+        assert_eq!(page1.as_array().unwrap().len(), 50);
+        assert_eq!(page3.as_array().unwrap().len(), 20);
+    }
+}
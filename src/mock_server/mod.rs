@@ -0,0 +1,404 @@
+//! A local stand-in for the CoinPaprika API, for offline development and
+//! for exercising error handling (`--inject 429`, `--inject slow`, ...).
+
+mod data;
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use clap::Args;
+use data::{Request, Rng};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+#[derive(Args, Debug, Clone)]
+pub struct MockServerArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+
+    /// Port to listen on (0 picks a free port)
+    #[arg(long, default_value = "8080")]
+    port: u16,
+
+    /// Seed for generated data; the same seed always serves the same numbers
+    #[arg(long, default_value = "42")]
+    seed: u64,
+
+    /// Inject a fault: 402, 403, 404, 429, 500, 502, 503, 5xx, slow or malformed,
+    /// optionally limited to a path prefix (e.g. 429:/tickers). Repeatable.
+    #[arg(long, value_name = "KIND[:PATH]")]
+    inject: Vec<Fault>,
+
+    /// Fraction of matching requests that get an injected fault (0.0-1.0)
+    #[arg(long, default_value = "1.0")]
+    error_rate: f64,
+
+    /// Retry-After seconds sent with injected 429 responses
+    #[arg(long, default_value = "30")]
+    retry_after: u64,
+
+    /// Delay in milliseconds for injected slow responses
+    #[arg(long, default_value = "5000")]
+    slow_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FaultKind {
+    Status(u16),
+    /// One of 500, 502 or 503, picked per request.
+    ServerError,
+    Slow,
+    Malformed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Fault {
+    kind: FaultKind,
+    path_prefix: Option<String>,
+}
+
+impl FromStr for Fault {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, path_prefix) = match s.split_once(':') {
+            Some((kind, path)) => (kind, Some(normalize_path(path).to_string())),
+            None => (s, None),
+        };
+        let kind = match kind.to_lowercase().as_str() {
+            "402" | "403" | "404" | "429" | "500" | "502" | "503" => FaultKind::Status(kind.parse().expect("matched digits")),
+            "5xx" => FaultKind::ServerError,
+            "slow" => FaultKind::Slow,
+            "malformed" => FaultKind::Malformed,
+            other => {
+                return Err(format!(
+                    "unknown fault '{other}' (expected 402, 403, 404, 429, 500, 502, 503, 5xx, slow or malformed)"
+                ))
+            }
+        };
+        Ok(Self { kind, path_prefix })
+    }
+}
+
+impl std::fmt::Display for Fault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            FaultKind::Status(status) => write!(f, "{status}")?,
+            FaultKind::ServerError => f.write_str("5xx")?,
+            FaultKind::Slow => f.write_str("slow")?,
+            FaultKind::Malformed => f.write_str("malformed")?,
+        }
+        write!(f, " on {}", self.path_prefix.as_deref().unwrap_or("all paths"))
+    }
+}
+
+impl Fault {
+    fn matches(&self, path: &str) -> bool {
+        self.path_prefix.as_deref().is_none_or(|prefix| path.starts_with(prefix))
+    }
+}
+
+struct Server {
+    args: MockServerArgs,
+    requests: AtomicU64,
+}
+
+struct Response {
+    status: u16,
+    body: String,
+    retry_after: Option<u64>,
+}
+
+pub async fn run(args: MockServerArgs) -> Result<()> {
+    if !(0.0..=1.0).contains(&args.error_rate) {
+        bail!("--error-rate must be between 0.0 and 1.0, got {}", args.error_rate);
+    }
+    let listener = TcpListener::bind((args.host.as_str(), args.port))
+        .await
+        .with_context(|| format!("Could not listen on {}:{}", args.host, args.port))?;
+    let addr = listener.local_addr()?;
+
+    println!("http://{addr}/v1");
+    eprintln!("Mock CoinPaprika API listening (seed {}). Point the CLI at it with:", args.seed);
+    eprintln!("  export COINPAPRIKA_BASE_URL=http://{addr}/v1");
+    for fault in &args.inject {
+        eprintln!("  injecting {fault}");
+    }
+
+    serve(listener, Arc::new(Server { args, requests: AtomicU64::new(0) })).await
+}
+
+async fn serve(listener: TcpListener, server: Arc<Server>) -> Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let server = Arc::clone(&server);
+        tokio::spawn(async move {
+            if let Err(e) = server.handle(stream).await {
+                eprintln!("connection error: {e}");
+            }
+        });
+    }
+}
+
+/// A mock server on a free local port, running in the background, for tests.
+#[cfg(test)]
+pub struct TestServer {
+    pub base_url: String,
+    server: Arc<Server>,
+}
+
+#[cfg(test)]
+impl TestServer {
+    /// Start one with `mock-server` flags, e.g. `["--inject", "5xx"]`.
+    pub async fn start(flags: &[&str]) -> Self {
+        #[derive(clap::Parser)]
+        struct Flags {
+            #[command(flatten)]
+            args: MockServerArgs,
+        }
+        let argv = ["mock-server", "--port", "0"].into_iter().chain(flags.iter().copied());
+        let args = <Flags as clap::Parser>::parse_from(argv).args;
+        let listener = TcpListener::bind((args.host.as_str(), args.port)).await.expect("bind a free port");
+        let base_url = format!("http://{}/v1", listener.local_addr().expect("bound address"));
+        let server = Arc::new(Server { args, requests: AtomicU64::new(0) });
+        tokio::spawn(serve(listener, Arc::clone(&server)));
+        Self { base_url, server }
+    }
+
+    /// Requests served so far.
+    pub fn requests(&self) -> u64 {
+        self.server.requests.load(Ordering::Relaxed)
+    }
+}
+
+impl Server {
+    async fn handle(&self, stream: TcpStream) -> Result<()> {
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).await? == 0 {
+            return Ok(());
+        }
+        let mut authenticated = false;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                authenticated |= name.eq_ignore_ascii_case("authorization") && !value.trim().is_empty();
+            }
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default();
+        let target = parts.next().unwrap_or("/");
+        let response = if method == "GET" {
+            self.respond(target, authenticated).await
+        } else {
+            Response { status: 405, body: r#"{"error":"method not allowed"}"#.into(), retry_after: None }
+        };
+        eprintln!("{method} {target} -> {}", response.status);
+
+        let mut stream = reader.into_inner();
+        stream.write_all(&encode(&response)).await?;
+        stream.shutdown().await?;
+        Ok(())
+    }
+
+    async fn respond(&self, target: &str, authenticated: bool) -> Response {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let path = normalize_path(path);
+        let n = self.requests.fetch_add(1, Ordering::Relaxed);
+        let mut rng = Rng::new(self.args.seed ^ n.wrapping_mul(0x9e3779b97f4a7c15));
+
+        let fault = self
+            .args
+            .inject
+            .iter()
+            .find(|f| f.matches(path))
+            .filter(|_| rng.next_f64() < self.args.error_rate)
+            .map(|f| f.kind);
+
+        if fault == Some(FaultKind::Slow) {
+            tokio::time::sleep(Duration::from_millis(self.args.slow_ms)).await;
+        }
+        if let Some(FaultKind::Status(status)) = fault {
+            return self.error(status);
+        }
+        if fault == Some(FaultKind::ServerError) {
+            return self.error([500, 502, 503][rng.below(3)]);
+        }
+
+        let params = parse_query(query);
+        let (status, body) = data::respond(self.args.seed, &Request { path, params: &params, authenticated });
+        let mut body = body.to_string();
+        if fault == Some(FaultKind::Malformed) && status == 200 {
+            body.truncate(body.len() / 2);
+        }
+        Response { status, body, retry_after: None }
+    }
+
+    fn error(&self, status: u16) -> Response {
+        let message = match status {
+            402 => "Your current plan does not include this endpoint",
+            403 => "invalid API key",
+            404 => "id not found",
+            429 => "too many requests",
+            _ => "internal server error",
+        };
+        Response {
+            status,
+            body: serde_json::json!({ "error": message }).to_string(),
+            retry_after: (status == 429).then_some(self.args.retry_after),
+        }
+    }
+}
+
+/// Drop the `/v1` prefix the real API uses so both base URL styles work.
+fn normalize_path(path: &str) -> &str {
+    match path.strip_prefix("/v1") {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+        _ => path,
+    }
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => match s.get(i + 1..i + 3).and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                Some(b) => {
+                    out.push(b);
+                    i += 2;
+                }
+                None => out.push(b'%'),
+            },
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn encode(response: &Response) -> Vec<u8> {
+    let reason = match response.status {
+        200 => "OK",
+        402 => "Payment Required",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    };
+    let mut head = format!(
+        "HTTP/1.1 {} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nDate: {}\r\nConnection: close\r\n",
+        response.status,
+        response.body.len(),
+        Utc::now().format("%a, %d %b %Y %H:%M:%S GMT"),
+    );
+    if let Some(secs) = response.retry_after {
+        head.push_str(&format!("Retry-After: {secs}\r\n"));
+    }
+    head.push_str("\r\n");
+    let mut bytes = head.into_bytes();
+    bytes.extend_from_slice(response.body.as_bytes());
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_faults() {
+        assert_eq!("429".parse::<Fault>().unwrap(), Fault { kind: FaultKind::Status(429), path_prefix: None });
+        assert_eq!(
+            "5xx:/v1/tickers".parse::<Fault>().unwrap(),
+            Fault { kind: FaultKind::ServerError, path_prefix: Some("/tickers".into()) }
+        );
+        assert!("418".parse::<Fault>().is_err());
+    }
+
+    #[test]
+    fn decodes_queries() {
+        let params = parse_query("quotes=USD%2CBTC&q=bit+coin&start=2024-01-01T00%3A00%3A00Z");
+        assert_eq!(params["quotes"], "USD,BTC");
+        assert_eq!(params["q"], "bit coin");
+        assert_eq!(params["start"], "2024-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn strips_v1_prefix() {
+        assert_eq!(normalize_path("/v1/tickers"), "/tickers");
+        assert_eq!(normalize_path("/tickers"), "/tickers");
+        assert_eq!(normalize_path("/v12"), "/v12");
+    }
+
+    #[tokio::test]
+    async fn client_retries_transient_failures() {
+        let mock = TestServer::start(&["--inject", "5xx"]).await;
+        let client = coinpaprika_cli::ApiClient::new(None).with_base_url(&mock.base_url);
+        assert!(client.global().await.is_err());
+        assert_eq!(mock.requests(), 3);
+        assert!(client.probe("/global").await.is_ok());
+        assert_eq!(mock.requests(), 4);
+        assert!(client.with_retries(0).global().await.is_err());
+        assert_eq!(mock.requests(), 5);
+
+        let mock = TestServer::start(&["--inject", "429", "--retry-after", "0"]).await;
+        let client = coinpaprika_cli::ApiClient::new(None).with_base_url(&mock.base_url).with_retries(1);
+        assert!(client.global().await.is_err());
+        assert_eq!(mock.requests(), 2);
+
+        let mock = TestServer::start(&["--inject", "429", "--retry-after", "30"]).await;
+        let client = coinpaprika_cli::ApiClient::new(None).with_base_url(&mock.base_url);
+        assert!(client.global().await.is_err());
+        assert_eq!(mock.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn cassettes_replay_relative_dates() {
+        use coinpaprika_cli::client::CassetteMode;
+        use coinpaprika_cli::{HistoricalParams, Period};
+
+        let dir = std::env::temp_dir().join(format!("coinpaprika-cassette-relative-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mock = TestServer::start(&[]).await;
+        let recorder = coinpaprika_cli::ApiClient::new(None)
+            .with_base_url(&mock.base_url)
+            .with_cassette(CassetteMode::Record(dir.clone()));
+        let recorded = recorder.ohlcv_historical("btc-bitcoin", &HistoricalParams::new("7d".parse().unwrap())).await.unwrap();
+
+        // A later run resolves `7d` to a different instant, but replays what was recorded.
+        let later = Period::parse_at("7d", chrono::Utc::now() + chrono::Duration::hours(5)).unwrap();
+        let player = coinpaprika_cli::ApiClient::new(None)
+            .with_base_url(&mock.base_url)
+            .with_cassette(CassetteMode::Replay(dir.clone()));
+        let replayed = player.ohlcv_historical("btc-bitcoin", &HistoricalParams::new(later)).await.unwrap();
+        assert_eq!(serde_json::to_value(&replayed).unwrap(), serde_json::to_value(&recorded).unwrap());
+        assert_eq!(mock.requests(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,4 +1,4 @@
-use crate::commands::api_management::KeyInfo;
+use coinpaprika_cli::types::api_management::KeyInfo;
 use crate::output::{detail_field, print_coinpaprika_footer, print_detail_table};
 
 pub fn print_key_info(info: &KeyInfo) {
@@ -15,3 +15,4 @@ pub fn print_key_info(info: &KeyInfo) {
     print_detail_table(rows);
     print_coinpaprika_footer();
 }
+
@@ -0,0 +1,17 @@
+pub fn print_attribution() {
+    println!("  ─── CoinPaprika attribution snippets (copy & paste) ───\n");
+    println!("  HTML:");
+    println!("    <a href=\"https://coinpaprika.com\">Powered by CoinPaprika</a>\n");
+    println!("  React/JSX:");
+    println!("    <a href=\"https://coinpaprika.com\" target=\"_blank\" rel=\"noopener\">");
+    println!("      Powered by CoinPaprika");
+    println!("    </a>\n");
+    println!("  Markdown:");
+    println!("    [Powered by CoinPaprika](https://coinpaprika.com)\n");
+    println!("  Plain text:");
+    println!("    Data provided by CoinPaprika (https://coinpaprika.com)\n");
+    println!("  GitHub README badge:");
+    println!("    [![CoinPaprika](https://img.shields.io/badge/data-CoinPaprika-green)](https://coinpaprika.com)\n");
+    println!("  Data is free forever. Attribution is appreciated, not required.");
+    println!("  API: api.coinpaprika.com");
+}
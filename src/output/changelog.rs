@@ -0,0 +1,55 @@
+use tabled::{Table, Tabled};
+
+use coinpaprika_cli::types::ChangelogEntry;
+use crate::commands::changelog::ApplyReport;
+use crate::output::table_style;
+use crate::output::print_coinpaprika_footer;
+
+#[derive(Tabled)]
+struct ChangelogRow {
+    #[tabled(rename = "Old ID")]
+    old_id: String,
+    #[tabled(rename = "New ID")]
+    new_id: String,
+    #[tabled(rename = "Changed")]
+    changed_at: String,
+}
+
+pub fn print_changelog_table(entries: &[ChangelogEntry]) {
+    let rows: Vec<ChangelogRow> = entries.iter().map(|e| ChangelogRow {
+        old_id: e.old_id.clone(),
+        new_id: e.new_id.clone(),
+        changed_at: e.changed_at.as_deref().map(|d| d.chars().take(10).collect()).unwrap_or_else(|| "—".into()),
+    }).collect();
+
+    let table = Table::new(rows).with(table_style()).to_string();
+    println!("{table}");
+    print_coinpaprika_footer();
+}
+
+/// A line-level diff followed by a summary of the renames.
+pub fn print_apply_report(report: &ApplyReport) {
+    if report.changes.is_empty() {
+        println!("No stale coin IDs in {}.", report.file);
+        return;
+    }
+
+    println!("--- {}", report.file);
+    println!("+++ {} (migrated)", report.file);
+    for change in &report.changes {
+        println!("@@ line {} @@", change.line);
+        println!("-{}", change.before);
+        println!("+{}", change.after);
+    }
+
+    println!();
+    for rename in &report.renames {
+        println!("  {} -> {} ({}x)", rename.old_id, rename.new_id, rename.occurrences);
+    }
+    let total: usize = report.renames.iter().map(|r| r.occurrences).sum();
+    if report.written {
+        println!("\nUpdated {total} reference(s) in {}.", report.file);
+    } else {
+        println!("\nDry run: {total} reference(s) would change. Run again without --dry-run to write {}.", report.file);
+    }
+}
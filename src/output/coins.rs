@@ -1,8 +1,8 @@
 use tabled::{Table, Tabled};
-use tabled::settings::Style;
 
-use crate::commands::coins::{CoinListItem, CoinDetail, CoinEvent, CoinExchange, CoinMarket};
-use crate::output::{detail_field, truncate, format_usd, print_coinpaprika_footer, print_detail_table};
+use coinpaprika_cli::types::coins::{CoinListItem, CoinDetail, CoinEvent, CoinExchange, CoinMarket};
+use crate::commands::multi::ForCoin;
+use crate::output::{detail_field, truncate, format_usd, print_coinpaprika_footer, print_detail_table, table_style};
 
 #[derive(Tabled)]
 struct CoinRow {
@@ -27,7 +27,7 @@ pub fn print_coins_table(coins: &[CoinListItem]) {
         active: c.is_active.map(|a| if a { "Yes" } else { "No" }.to_string()).unwrap_or_else(|| "—".into()),
     }).collect();
 
-    let table = Table::new(rows).with(Style::rounded()).to_string();
+    let table = Table::new(rows).with(table_style()).to_string();
     println!("{table}");
     print_coinpaprika_footer();
 }
@@ -68,6 +68,40 @@ pub fn print_coin_detail(coin: &CoinDetail) {
     print_coinpaprika_footer();
 }
 
+#[derive(Tabled)]
+struct CoinDetailRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Rank")]
+    rank: String,
+    #[tabled(rename = "Symbol")]
+    symbol: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Type")]
+    coin_type: String,
+    #[tabled(rename = "Started")]
+    started: String,
+    #[tabled(rename = "Proof Type")]
+    proof_type: String,
+}
+
+pub fn print_coin_details_table(coins: &[CoinDetail]) {
+    let rows: Vec<CoinDetailRow> = coins.iter().map(|c| CoinDetailRow {
+        id: c.id.clone(),
+        rank: c.rank.map(|r| r.to_string()).unwrap_or_else(|| "—".into()),
+        symbol: c.symbol.clone(),
+        name: truncate(&c.name, 30),
+        coin_type: c.coin_type.clone().unwrap_or_else(|| "—".into()),
+        started: c.started_at.as_deref().unwrap_or("—").chars().take(10).collect(),
+        proof_type: c.proof_type.clone().unwrap_or_else(|| "—".into()),
+    }).collect();
+
+    let table = Table::new(rows).with(table_style()).to_string();
+    println!("{table}");
+    print_coinpaprika_footer();
+}
+
 #[derive(Tabled)]
 struct EventRow {
     #[tabled(rename = "Date")]
@@ -85,7 +119,32 @@ pub fn print_events_table(events: &[CoinEvent]) {
         is_conference: e.is_conference.map(|v| if v { "Yes" } else { "No" }.to_string()).unwrap_or_else(|| "—".into()),
     }).collect();
 
-    let table = Table::new(rows).with(Style::rounded()).to_string();
+    let table = Table::new(rows).with(table_style()).to_string();
+    println!("{table}");
+    print_coinpaprika_footer();
+}
+
+#[derive(Tabled)]
+struct CoinEventRow {
+    #[tabled(rename = "Coin")]
+    coin_id: String,
+    #[tabled(rename = "Date")]
+    date: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Conference")]
+    is_conference: String,
+}
+
+pub fn print_events_by_coin_table(events: &[ForCoin<CoinEvent>]) {
+    let rows: Vec<CoinEventRow> = events.iter().map(|e| CoinEventRow {
+        coin_id: e.coin_id.clone(),
+        date: e.item.date.clone().unwrap_or_else(|| "—".into()),
+        name: truncate(&e.item.name.clone().unwrap_or_else(|| "—".into()), 50),
+        is_conference: e.item.is_conference.map(|v| if v { "Yes" } else { "No" }.to_string()).unwrap_or_else(|| "—".into()),
+    }).collect();
+
+    let table = Table::new(rows).with(table_style()).to_string();
     println!("{table}");
     print_coinpaprika_footer();
 }
@@ -104,7 +163,7 @@ pub fn print_coin_exchanges_table(exchanges: &[CoinExchange]) {
         volume_share: e.adjusted_volume_24h_share.map(|v| format!("{v:.2}%")).unwrap_or_else(|| "—".into()),
     }).collect();
 
-    let table = Table::new(rows).with(Style::rounded()).to_string();
+    let table = Table::new(rows).with(table_style()).to_string();
     println!("{table}");
     print_coinpaprika_footer();
 }
@@ -143,7 +202,7 @@ pub fn print_markets_table(markets: &[CoinMarket]) {
         }
     }).collect();
 
-    let table = Table::new(rows).with(Style::rounded()).to_string();
+    let table = Table::new(rows).with(table_style()).to_string();
     println!("{table}");
     print_coinpaprika_footer();
 }
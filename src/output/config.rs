@@ -1,15 +1,66 @@
-use crate::output::{detail_field, print_detail_table};
-use crate::config::mask_key;
+use tabled::{Table, Tabled};
 
-pub fn print_config_show(config_path: &str, api_key: Option<&str>, key_source: &str) {
+use crate::commands::config::{ConfigInfo, ProfileInfo};
+use crate::output::{detail_field, print_detail_table, table_style};
+
+#[allow(clippy::vec_init_then_push)]
+pub fn print_config_show(info: &ConfigInfo) {
     let mut rows: Vec<[String; 2]> = Vec::new();
-    detail_field!(rows, "Config File", config_path.to_string());
-    detail_field!(rows, "API Key", api_key.map(mask_key).unwrap_or_else(|| "Not set".into()));
-    detail_field!(rows, "Key Source", key_source.to_string());
-    detail_field!(rows, "CoinPaprika URL", if api_key.is_some() {
-        "https://api-pro.coinpaprika.com/v1".to_string()
-    } else {
-        "https://api.coinpaprika.com/v1".to_string()
-    });
+    detail_field!(rows, "Config File", info.config_file.clone());
+    if let (Some(profile), Some(source)) = (&info.profile, &info.profile_source) {
+        detail_field!(rows, "Profile", format!("{profile} — {source}"));
+    }
+    detail_field!(rows, "API Key", info.api_key.clone().unwrap_or_else(|| "Not set".into()));
+    detail_field!(rows, "Key Source", info.key_source.clone());
+    detail_field!(rows, "CoinPaprika URL", info.coinpaprika_url.clone());
+    detail_field!(rows, "URL Source", info.url_source.clone());
+    detail_field!(rows, "Default Quotes", with_source(&info.quotes, &info.quotes_source, "USD"));
+    detail_field!(rows, "Default Output", with_source(&info.output, &info.output_source, "table"));
+    // Quotes and output are shown above, with their source.
+    for (key, value) in info.defaults.iter().filter(|(key, _)| *key != "quotes" && *key != "output") {
+        detail_field!(rows, key.clone(), value.clone());
+    }
     print_detail_table(rows);
 }
+
+fn with_source(value: &Option<String>, source: &Option<String>, default: &str) -> String {
+    match (value, source) {
+        (Some(value), Some(source)) => format!("{value} — {source}"),
+        _ => format!("{default} (built-in)"),
+    }
+}
+
+#[derive(Tabled)]
+struct ProfileRow {
+    #[tabled(rename = "")]
+    active: &'static str,
+    #[tabled(rename = "Profile")]
+    name: String,
+    #[tabled(rename = "API Key")]
+    api_key: String,
+    #[tabled(rename = "Base URL")]
+    base_url: String,
+    #[tabled(rename = "Quotes")]
+    quotes: String,
+    #[tabled(rename = "Output")]
+    output: String,
+}
+
+pub fn print_profiles_table(profiles: &[ProfileInfo]) {
+    if profiles.is_empty() {
+        println!("No profiles yet. Add one with: coinpaprika-cli config profile add NAME --key <KEY>");
+        return;
+    }
+    let dash = || "—".to_string();
+    let rows: Vec<ProfileRow> = profiles.iter().map(|p| ProfileRow {
+        active: if p.active { "*" } else { "" },
+        name: p.name.clone(),
+        api_key: p.api_key.clone().unwrap_or_else(|| "Not set (free tier)".into()),
+        base_url: p.base_url.clone().unwrap_or_else(dash),
+        quotes: p.quotes.clone().unwrap_or_else(dash),
+        output: p.output.clone().unwrap_or_else(dash),
+    }).collect();
+
+    let table = Table::new(rows).with(table_style()).to_string();
+    println!("{table}");
+}
@@ -1,8 +1,7 @@
 use tabled::{Table, Tabled};
-use tabled::settings::Style;
 
-use crate::commands::contracts::{Contract, ContractTicker, ContractHistoryPoint};
-use crate::output::{detail_field, format_percent, format_price, format_supply, format_usd, print_coinpaprika_footer, print_detail_table, truncate_address};
+use coinpaprika_cli::types::contracts::{Contract, ContractTicker, ContractHistoryPoint};
+use crate::output::{detail_field, format_percent, format_price, format_supply, format_usd, print_coinpaprika_footer, print_detail_table, truncate, truncate_address, table_style};
 
 pub fn print_platforms(platforms: &[String]) {
     println!("Contract Platforms:");
@@ -32,7 +31,38 @@ pub fn print_contracts_table(contracts: &[Contract]) {
         active: c.active.map(|a| if a { "Yes" } else { "No" }.to_string()).unwrap_or_else(|| "—".into()),
     }).collect();
 
-    let table = Table::new(rows).with(Style::rounded()).to_string();
+    let table = Table::new(rows).with(table_style()).to_string();
+    println!("{table}");
+    print_coinpaprika_footer();
+}
+
+#[derive(Tabled)]
+struct ContractTickerRow {
+    #[tabled(rename = "Symbol")]
+    symbol: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Price (USD)")]
+    price: String,
+    #[tabled(rename = "Market Cap")]
+    market_cap: String,
+    #[tabled(rename = "Change (24h)")]
+    change_24h: String,
+}
+
+pub fn print_contract_tickers_table(tickers: &[ContractTicker]) {
+    let rows: Vec<ContractTickerRow> = tickers.iter().map(|t| {
+        let usd = t.quotes.as_ref().and_then(|q| q.get("USD"));
+        ContractTickerRow {
+            symbol: t.symbol.clone().unwrap_or_else(|| "—".into()),
+            name: truncate(&t.name.clone().unwrap_or_else(|| "—".into()), 30),
+            price: usd.and_then(|q| q.price).map(format_price).unwrap_or_else(|| "—".into()),
+            market_cap: usd.and_then(|q| q.market_cap).map(format_usd).unwrap_or_else(|| "—".into()),
+            change_24h: usd.and_then(|q| q.percent_change_24h).map(format_percent).unwrap_or_else(|| "—".into()),
+        }
+    }).collect();
+
+    let table = Table::new(rows).with(table_style()).to_string();
     println!("{table}");
     print_coinpaprika_footer();
 }
@@ -80,7 +110,7 @@ pub fn print_contract_history(data: &[ContractHistoryPoint]) {
         market_cap: d.market_cap.map(format_usd).unwrap_or_else(|| "—".into()),
     }).collect();
 
-    let table = Table::new(rows).with(Style::rounded()).to_string();
+    let table = Table::new(rows).with(table_style()).to_string();
     println!("{table}");
     print_coinpaprika_footer();
 }
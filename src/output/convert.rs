@@ -1,4 +1,4 @@
-use crate::commands::convert::ConvertResult;
+use coinpaprika_cli::types::convert::ConvertResult;
 use crate::output::{detail_field, print_coinpaprika_footer, print_detail_table};
 
 pub fn print_convert_result(result: &ConvertResult) {
@@ -0,0 +1,108 @@
+use serde_json::{Map, Value};
+
+/// Flatten JSON into CSV: one row per array element (or a single row for an
+/// object), nested objects become dotted columns (`quotes.USD.price`) and
+/// arrays are embedded as JSON text.
+pub fn to_csv(value: &Value) -> String {
+    let rows: Vec<Map<String, Value>> = match value {
+        Value::Array(items) => items.iter().map(flatten_row).collect(),
+        other => vec![flatten_row(other)],
+    };
+
+    let mut columns: Vec<String> = Vec::new();
+    for row in &rows {
+        for key in row.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str(&columns.iter().map(|c| escape(c)).collect::<Vec<_>>().join(","));
+    out.push('\n');
+    for row in &rows {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|c| row.get(c).map(cell).unwrap_or_default())
+            .collect();
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn flatten_row(value: &Value) -> Map<String, Value> {
+    let mut row = Map::new();
+    match value {
+        Value::Object(_) => flatten_into(&mut row, "", value),
+        other => {
+            row.insert("value".into(), other.clone());
+        }
+    }
+    row
+}
+
+fn flatten_into(row: &mut Map<String, Value>, prefix: &str, value: &Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map {
+                let name = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+                flatten_into(row, &name, v);
+            }
+        }
+        other => {
+            row.insert(prefix.to_string(), other.clone());
+        }
+    }
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => escape(s),
+        Value::Bool(_) | Value::Number(_) => value.to_string(),
+        other => escape(&other.to_string()),
+    }
+}
+
+fn escape(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn array_of_objects_becomes_rows() {
+        let csv = to_csv(&json!([
+            {"id": "btc-bitcoin", "rank": 1},
+            {"id": "eth-ethereum", "rank": 2}
+        ]));
+        assert_eq!(csv, "id,rank\nbtc-bitcoin,1\neth-ethereum,2\n");
+    }
+
+    #[test]
+    fn nested_objects_flatten_to_dotted_columns() {
+        let csv = to_csv(&json!({"id": "btc-bitcoin", "quotes": {"USD": {"price": 1.5}}}));
+        assert_eq!(csv, "id,quotes.USD.price\nbtc-bitcoin,1.5\n");
+    }
+
+    #[test]
+    fn fields_with_commas_and_quotes_are_escaped() {
+        let csv = to_csv(&json!([{"name": "a, \"b\"", "tags": ["x", "y"]}]));
+        assert_eq!(csv, "name,tags\n\"a, \"\"b\"\"\",\"[\"\"x\"\",\"\"y\"\"]\"\n");
+    }
+
+    #[test]
+    fn nulls_are_empty_and_missing_columns_filled() {
+        let csv = to_csv(&json!([{"a": null}, {"a": 1, "b": true}]));
+        assert_eq!(csv, "a,b\n,\n1,true\n");
+    }
+}
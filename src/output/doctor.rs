@@ -0,0 +1,38 @@
+use tabled::settings::object::Columns;
+use tabled::settings::{Modify, Width};
+use tabled::{Table, Tabled};
+
+use super::table_style;
+use crate::commands::doctor::{CheckStatus, DoctorReport};
+
+#[derive(Tabled)]
+struct CheckRow {
+    #[tabled(rename = "Check")]
+    name: String,
+    #[tabled(rename = "Status")]
+    status: &'static str,
+    #[tabled(rename = "Details")]
+    detail: String,
+    #[tabled(rename = "Fix")]
+    fix: String,
+}
+
+pub fn print_doctor_report(report: &DoctorReport) {
+    let rows: Vec<CheckRow> = report.checks.iter().map(|c| CheckRow {
+        name: c.name.clone(),
+        status: match c.status {
+            CheckStatus::Pass => "pass",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        },
+        detail: c.detail.clone(),
+        fix: c.fix.clone().unwrap_or_default(),
+    }).collect();
+
+    let table = Table::new(rows)
+        .with(table_style())
+        .with(Modify::new(Columns::new(2..4)).with(Width::wrap(50)))
+        .to_string();
+    println!("{table}");
+    println!("\n{} passed, {} warnings, {} failed", report.passed, report.warnings, report.failed);
+}
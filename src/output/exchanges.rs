@@ -1,8 +1,7 @@
 use tabled::{Table, Tabled};
-use tabled::settings::Style;
 
-use crate::commands::exchanges::{Exchange, ExchangeMarket};
-use crate::output::{detail_field, format_usd, print_coinpaprika_footer, print_detail_table, truncate};
+use coinpaprika_cli::types::exchanges::{Exchange, ExchangeMarket};
+use crate::output::{detail_field, format_usd, print_coinpaprika_footer, print_detail_table, truncate, table_style};
 
 #[derive(Tabled)]
 struct ExchangeRow {
@@ -37,7 +36,7 @@ pub fn print_exchanges_table(exchanges: &[Exchange]) {
         }
     }).collect();
 
-    let table = Table::new(rows).with(Style::rounded()).to_string();
+    let table = Table::new(rows).with(table_style()).to_string();
     println!("{table}");
     print_coinpaprika_footer();
 }
@@ -102,7 +101,7 @@ pub fn print_exchange_markets_table(markets: &[ExchangeMarket]) {
         }
     }).collect();
 
-    let table = Table::new(rows).with(Style::rounded()).to_string();
+    let table = Table::new(rows).with(table_style()).to_string();
     println!("{table}");
     print_coinpaprika_footer();
 }
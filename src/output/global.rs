@@ -1,4 +1,4 @@
-use crate::commands::global::GlobalData;
+use coinpaprika_cli::types::global::GlobalData;
 use crate::output::{detail_field, format_percent, format_usd, print_coinpaprika_footer, print_detail_table};
 
 pub fn print_global(data: &GlobalData) {
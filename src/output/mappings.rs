@@ -0,0 +1,29 @@
+use tabled::builder::Builder;
+
+use coinpaprika_cli::mappings::providers;
+use coinpaprika_cli::types::CoinMapping;
+use crate::output::{detail_field, print_coinpaprika_footer, print_detail_table, table_style};
+
+/// One column per provider that appears anywhere in the list.
+pub fn print_mappings_table(mappings: &[CoinMapping]) {
+    let columns = providers(mappings);
+    let mut builder = Builder::default();
+    builder.push_record(columns.iter().map(String::as_str));
+    for m in mappings {
+        builder.push_record(columns.iter().map(|p| m.id_for(p).unwrap_or("—").to_string()));
+    }
+
+    let table = builder.build().with(table_style()).to_string();
+    println!("{table}");
+    print_coinpaprika_footer();
+}
+
+pub fn print_mapping_detail(mapping: &CoinMapping) {
+    let mut rows: Vec<[String; 2]> = Vec::new();
+    detail_field!(rows, "coinpaprika", mapping.coinpaprika.clone());
+    for (provider, id) in &mapping.providers {
+        detail_field!(rows, provider.as_str(), id.clone());
+    }
+    print_detail_table(rows);
+    print_coinpaprika_footer();
+}
@@ -1,41 +1,104 @@
 use anyhow::Result;
 use chrono::Utc;
+use coinpaprika_cli::types::SCHEMA_VERSION;
+use schemars::JsonSchema;
 use serde::Serialize;
 use tabled::settings::object::Columns;
-use tabled::settings::{Modify, Style, Width};
+use std::sync::RwLock;
+use tabled::settings::{Modify, Style, Theme, Width};
 use tabled::Table;
 
+use crate::commands::{CommandOutput, OutputData};
+
 #[derive(Clone, Copy, Debug, clap::ValueEnum)]
 pub enum OutputFormat {
     Table,
     Json,
+    Csv,
+}
+
+/// Table borders, from `--table-style` or the `table_style` default.
+#[derive(Clone, Copy, Debug, Default, clap::ValueEnum)]
+pub enum TableStyle {
+    #[default]
+    Rounded,
+    Modern,
+    Sharp,
+    Ascii,
+    Psql,
+    Markdown,
+    Blank,
+}
+
+static TABLE_STYLE: RwLock<TableStyle> = RwLock::new(TableStyle::Rounded);
+
+pub fn set_table_style(style: TableStyle) {
+    *TABLE_STYLE.write().unwrap_or_else(|e| e.into_inner()) = style;
+}
+
+/// The selected table borders, for `Table::with`.
+pub fn table_style() -> Theme {
+    match *TABLE_STYLE.read().unwrap_or_else(|e| e.into_inner()) {
+        TableStyle::Rounded => Style::rounded().into(),
+        TableStyle::Modern => Style::modern().into(),
+        TableStyle::Sharp => Style::sharp().into(),
+        TableStyle::Ascii => Style::ascii().into(),
+        TableStyle::Psql => Style::psql().into(),
+        TableStyle::Markdown => Style::markdown().into(),
+        TableStyle::Blank => Style::blank().into(),
+    }
 }
 
 // --- Attribution / _meta wrapper ---
 
-#[derive(Serialize)]
-pub struct WrappedResponse<'a, T: Serialize> {
+#[derive(Serialize, JsonSchema)]
+pub struct WrappedResponse<'a, T> {
     pub data: &'a T,
-    pub _meta: ResponseMeta,
+    pub _meta: &'a ResponseMeta,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct ResponseMeta {
+    pub schema_version: String,
     pub source: String,
     pub url: String,
     pub api_docs: String,
     pub attribution: String,
     pub timestamp: String,
+    /// Items of a multi-ID request that failed; the rest are in `data`.
+    /// Left out when none did.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ItemError>,
+    /// What `--start`, `--end` and `--interval` resolved to, for historical data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<RangeMeta>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RangeMeta {
+    pub start: String,
+    /// Now when no `--end` was given.
+    pub end: String,
+    pub interval: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ItemError {
+    pub id: String,
+    pub error: String,
 }
 
 impl ResponseMeta {
     pub fn coinpaprika(entity_path: &str) -> Self {
         Self {
+            schema_version: SCHEMA_VERSION.into(),
             source: "CoinPaprika".into(),
             url: format!("https://coinpaprika.com{entity_path}"),
             api_docs: "https://api.coinpaprika.com".into(),
             attribution: "Powered by CoinPaprika · Free crypto market data".into(),
             timestamp: Utc::now().to_rfc3339(),
+            errors: Vec::new(),
+            range: None,
         }
     }
 }
@@ -102,13 +165,12 @@ pub fn format_supply(n: f64) -> String {
     }
 }
 
-#[allow(dead_code)]
 pub fn print_json<T: Serialize>(data: &T) -> Result<()> {
     println!("{}", serde_json::to_string_pretty(data)?);
     Ok(())
 }
 
-pub fn print_json_wrapped<T: Serialize>(data: &T, meta: ResponseMeta, raw: bool) -> Result<()> {
+pub fn print_json_wrapped<T: Serialize>(data: &T, meta: &ResponseMeta, raw: bool) -> Result<()> {
     if raw {
         println!("{}", serde_json::to_string_pretty(data)?);
     } else {
@@ -120,7 +182,7 @@ pub fn print_json_wrapped<T: Serialize>(data: &T, meta: ResponseMeta, raw: bool)
 
 pub fn print_detail_table(rows: Vec<[String; 2]>) {
     let table = Table::from_iter(rows)
-        .with(Style::rounded())
+        .with(table_style())
         .with(Modify::new(Columns::first()).with(Width::wrap(20)))
         .with(Modify::new(Columns::last()).with(Width::wrap(80)))
         .to_string();
@@ -133,14 +195,6 @@ pub fn print_coinpaprika_footer() {
     );
 }
 
-macro_rules! detail_field {
-    ($rows:expr, $label:expr, $val:expr) => {
-        $rows.push([$label.into(), $val]);
-    };
-}
-
-pub(crate) use detail_field;
-
 pub mod global;
 pub mod coins;
 pub mod tickers;
@@ -152,9 +206,108 @@ pub mod search;
 pub mod convert;
 pub mod contracts;
 pub mod api_management;
+pub mod changelog;
+pub mod mappings;
 pub mod config;
+pub mod doctor;
 pub mod status;
 
+pub mod plans;
+pub mod plugins;
+pub mod value;
+pub mod attribution;
+pub mod csv;
+
+// --- Rendering ---
+
+/// Render a command result in the requested format.
+pub fn render(result: &CommandOutput, format: OutputFormat, raw: bool) -> Result<()> {
+    if let OutputData::Message(text) = &result.data {
+        println!("{text}");
+        return Ok(());
+    }
+    // A schema is JSON whatever the output format; --raw picks the payload.
+    if let OutputData::Schema(schema) = &result.data {
+        let schema = if raw { &schema.payload } else { &schema.envelope };
+        println!("{}", serde_json::to_string_pretty(schema)?);
+        return Ok(());
+    }
+    match format {
+        OutputFormat::Table => print_table(&result.data),
+        OutputFormat::Json => print_json_wrapped(&result.data, &result.meta, raw)?,
+        OutputFormat::Csv => print!("{}", csv::to_csv(&serde_json::to_value(&result.data)?)),
+    }
+    for e in &result.meta.errors {
+        eprintln!("Error for {}: {}", e.id, e.error.lines().next().unwrap_or_default());
+    }
+    Ok(())
+}
+
+/// `result` as file contents with the matching extension: CSV for
+/// [`OutputFormat::Csv`], JSON otherwise, and plain text for a message.
+pub fn to_file(result: &CommandOutput, format: OutputFormat, raw: bool) -> Result<(&'static str, String)> {
+    Ok(match (format, &result.data) {
+        (_, OutputData::Message(text)) => ("txt", format!("{text}\n")),
+        (OutputFormat::Csv, data) => ("csv", csv::to_csv(&serde_json::to_value(data)?)),
+        (_, data) if raw => ("json", serde_json::to_string_pretty(data)?),
+        (_, data) => ("json", serde_json::to_string_pretty(&serde_json::json!({ "data": data, "_meta": result.meta }))?),
+    })
+}
+
+fn print_table(data: &OutputData) {
+    match data {
+        OutputData::Global(d) => global::print_global(d),
+        OutputData::Coins(d) => coins::print_coins_table(d),
+        OutputData::Coin(d) => coins::print_coin_detail(d),
+        OutputData::CoinDetails(d) => coins::print_coin_details_table(d),
+        OutputData::CoinEvents(d) => coins::print_events_table(d),
+        OutputData::CoinEventsByCoin(d) => coins::print_events_by_coin_table(d),
+        OutputData::CoinExchanges(d) => coins::print_coin_exchanges_table(d),
+        OutputData::CoinMarkets(d) => coins::print_markets_table(d),
+        OutputData::Tickers(d) => tickers::print_tickers_table(d),
+        OutputData::Ticker(d) => tickers::print_ticker_detail(d),
+        OutputData::TickerHistory(d) => tickers::print_history_table(d),
+        OutputData::Ohlcv(d) => ohlcv::print_ohlcv_table(d),
+        OutputData::OhlcvByCoin(d) => ohlcv::print_ohlcv_by_coin_table(d),
+        OutputData::Exchanges(d) => exchanges::print_exchanges_table(d),
+        OutputData::Exchange(d) => exchanges::print_exchange_detail(d),
+        OutputData::ExchangeMarkets(d) => exchanges::print_exchange_markets_table(d),
+        OutputData::Tags(d) => tags::print_tags_table(d),
+        OutputData::Tag(d) => tags::print_tag_detail(d),
+        OutputData::Person(d) => people::print_person_detail(d),
+        OutputData::Search(d) => search::print_search_results(d),
+        OutputData::Convert(d) => convert::print_convert_result(d),
+        OutputData::Platforms(d) => contracts::print_platforms(d),
+        OutputData::Contracts(d) => contracts::print_contracts_table(d),
+        OutputData::ContractTicker(d) => contracts::print_contract_ticker(d),
+        OutputData::ContractTickers(d) => contracts::print_contract_tickers_table(d),
+        OutputData::ContractHistory(d) => contracts::print_contract_history(d),
+        OutputData::KeyInfo(d) => api_management::print_key_info(d),
+        OutputData::Mappings(d) => mappings::print_mappings_table(d),
+        OutputData::Mapping(d) => mappings::print_mapping_detail(d),
+        OutputData::Changelog(d) => changelog::print_changelog_table(d),
+        OutputData::ChangelogApply(d) => changelog::print_apply_report(d),
+        OutputData::Config(d) => config::print_config_show(d),
+        OutputData::Profiles(d) => config::print_profiles_table(d),
+        OutputData::Status(d) => status::print_status(d),
+        OutputData::Doctor(d) => doctor::print_doctor_report(d),
+        OutputData::Attribution(_) => attribution::print_attribution(),
+        OutputData::Plans(info) => plans::print_plans(info),
+        OutputData::Plugins(d) => plugins::print_plugins_table(d),
+        OutputData::Script(d) | OutputData::Api(d) => value::print_value(d),
+        OutputData::Schema(schema) => print_json(&schema.envelope).unwrap_or_default(),
+        OutputData::Message(text) => println!("{text}"),
+    }
+}
+
+macro_rules! detail_field {
+    ($rows:expr, $label:expr, $val:expr) => {
+        $rows.push([$label.into(), $val]);
+    };
+}
+
+pub(crate) use detail_field;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,8 +1,8 @@
 use tabled::{Table, Tabled};
-use tabled::settings::Style;
 
-use crate::commands::ohlcv::OhlcvData;
-use crate::output::{format_price, format_usd, print_coinpaprika_footer};
+use coinpaprika_cli::types::ohlcv::OhlcvData;
+use crate::commands::multi::ForCoin;
+use crate::output::{format_price, format_usd, print_coinpaprika_footer, table_style};
 
 #[derive(Tabled)]
 struct OhlcvRow {
@@ -20,17 +20,40 @@ struct OhlcvRow {
     volume: String,
 }
 
-pub fn print_ohlcv_table(data: &[OhlcvData]) {
-    let rows: Vec<OhlcvRow> = data.iter().map(|d| OhlcvRow {
+fn ohlcv_row(d: &OhlcvData) -> OhlcvRow {
+    OhlcvRow {
         date: d.time_open.as_deref().unwrap_or("—").chars().take(10).collect(),
         open: d.open.map(format_price).unwrap_or_else(|| "—".into()),
         high: d.high.map(format_price).unwrap_or_else(|| "—".into()),
         low: d.low.map(format_price).unwrap_or_else(|| "—".into()),
         close: d.close.map(format_price).unwrap_or_else(|| "—".into()),
         volume: d.volume.map(format_usd).unwrap_or_else(|| "—".into()),
+    }
+}
+
+pub fn print_ohlcv_table(data: &[OhlcvData]) {
+    let rows: Vec<OhlcvRow> = data.iter().map(ohlcv_row).collect();
+
+    let table = Table::new(rows).with(table_style()).to_string();
+    println!("{table}");
+    print_coinpaprika_footer();
+}
+
+#[derive(Tabled)]
+struct CoinOhlcvRow {
+    #[tabled(rename = "Coin")]
+    coin_id: String,
+    #[tabled(inline)]
+    ohlcv: OhlcvRow,
+}
+
+pub fn print_ohlcv_by_coin_table(data: &[ForCoin<OhlcvData>]) {
+    let rows: Vec<CoinOhlcvRow> = data.iter().map(|d| CoinOhlcvRow {
+        coin_id: d.coin_id.clone(),
+        ohlcv: ohlcv_row(&d.item),
     }).collect();
 
-    let table = Table::new(rows).with(Style::rounded()).to_string();
+    let table = Table::new(rows).with(table_style()).to_string();
     println!("{table}");
     print_coinpaprika_footer();
 }
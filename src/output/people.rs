@@ -1,4 +1,4 @@
-use crate::commands::people::Person;
+use coinpaprika_cli::types::people::Person;
 use crate::output::{detail_field, print_coinpaprika_footer, print_detail_table, truncate};
 
 pub fn print_person_detail(person: &Person) {
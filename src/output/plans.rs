@@ -0,0 +1,86 @@
+use coinpaprika_cli::plans::Plan;
+use coinpaprika_cli::Interval;
+use tabled::{Table, Tabled};
+
+use crate::commands::plans::{history, PlansInfo};
+use crate::output::table_style;
+
+#[derive(Tabled)]
+struct PlanRow {
+    #[tabled(rename = "Plan")]
+    name: &'static str,
+    #[tabled(rename = "Intervals")]
+    intervals: String,
+    #[tabled(rename = "Daily history")]
+    daily: String,
+    #[tabled(rename = "Intraday history")]
+    intraday: String,
+    #[tabled(rename = "Ticker history")]
+    ticker_history: &'static str,
+    #[tabled(rename = "Mappings")]
+    mappings: &'static str,
+    #[tabled(rename = "Changelog")]
+    changelog: &'static str,
+}
+
+fn yes_no(b: bool) -> &'static str {
+    if b { "yes" } else { "no" }
+}
+
+fn plan_row(plan: &Plan) -> PlanRow {
+    let intervals = if plan.intervals == Interval::ALL { "all (5m–365d)".into() } else { plan.interval_list() };
+    let strip = |s: String| s.trim_start_matches("up to ").trim_end_matches(" back").to_string();
+    PlanRow {
+        name: plan.name,
+        intervals,
+        daily: strip(history(plan.daily_history_days)),
+        intraday: strip(history(plan.intraday_history_days)),
+        ticker_history: yes_no(plan.ticker_history),
+        mappings: yes_no(plan.mappings),
+        changelog: yes_no(plan.changelog),
+    }
+}
+
+pub fn print_plans(info: &PlansInfo) {
+    let free = &info.free_tier;
+    println!();
+    println!("  ─── Free tier ($0/mo, no API key needed) ───");
+    println!();
+    println!("  Rate limits");
+    println!("    20,000 calls/month");
+    println!("    Data updates every ~10 minutes");
+    println!();
+    println!("  Coverage");
+    println!("    25+ endpoints");
+    println!("    2,000 assets");
+    println!("    Personal use");
+    println!();
+    println!("  Historical data");
+    println!("    Daily OHLCV:    {}", free.daily_ohlcv_history);
+    println!("    Hourly OHLCV:   {}", free.hourly_ohlcv);
+    println!("    OHLCV interval: {}", free.ohlcv_intervals);
+    println!("    5-min / ticker history: not available");
+    println!();
+    println!("  Not included");
+    println!("    Circulating supply");
+    println!("    API ID mappings");
+    println!("    WebSockets");
+    println!("    Redistribution rights");
+    println!("    SLA / dedicated infrastructure");
+    println!();
+    println!("  ─── What each plan allows ───");
+    println!();
+    let rows: Vec<PlanRow> = info.plans.iter().map(plan_row).collect();
+    println!("{}", Table::new(rows).with(table_style()));
+    println!();
+    println!("  Requests outside your plan are rejected before they are sent.");
+    println!();
+    println!("  ─── Need more? ───");
+    println!();
+    println!("  Paid plans add: full history, 5-min intervals, circulating supply,");
+    println!("  higher limits, WebSockets, commercial use, and priority support.");
+    println!();
+    println!("  See current pricing:  {}", info.paid_plans_url);
+    println!("  Set your API key:     coinpaprika-cli config set-key <KEY>");
+    println!();
+}
@@ -0,0 +1,26 @@
+use tabled::{Table, Tabled};
+
+use crate::commands::plugins::{Plugin, PREFIX};
+use crate::output::table_style;
+
+#[derive(Tabled)]
+struct PluginRow {
+    #[tabled(rename = "Command")]
+    name: String,
+    #[tabled(rename = "Path")]
+    path: String,
+}
+
+pub fn print_plugins_table(plugins: &[Plugin]) {
+    if plugins.is_empty() {
+        println!("No plugins found. Put an executable named {PREFIX}NAME on PATH to add `coinpaprika-cli NAME`.");
+        return;
+    }
+    let rows: Vec<PluginRow> = plugins.iter().map(|p| PluginRow {
+        name: p.name.clone(),
+        path: p.path.clone(),
+    }).collect();
+
+    let table = Table::new(rows).with(table_style()).to_string();
+    println!("{table}");
+}
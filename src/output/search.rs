@@ -1,8 +1,7 @@
 use tabled::{Table, Tabled};
-use tabled::settings::Style;
 
-use crate::commands::search::SearchResult;
-use crate::output::{print_coinpaprika_footer, truncate};
+use coinpaprika_cli::types::search::SearchResult;
+use crate::output::{print_coinpaprika_footer, truncate, table_style};
 
 #[derive(Tabled)]
 struct CurrencyRow {
@@ -26,7 +25,7 @@ pub fn print_search_results(result: &SearchResult) {
                 name: truncate(&c.name, 30),
                 id: c.id.clone(),
             }).collect();
-            let table = Table::new(rows).with(Style::rounded()).to_string();
+            let table = Table::new(rows).with(table_style()).to_string();
             println!("{table}\n");
         }
     }
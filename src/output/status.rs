@@ -1,10 +1,46 @@
+use tabled::{Table, Tabled};
+
+use super::table_style;
 use crate::commands::status::StatusResult;
 use crate::output::{detail_field, print_detail_table};
 
+#[derive(Tabled)]
+struct EndpointRow {
+    #[tabled(rename = "Endpoint")]
+    endpoint: String,
+    #[tabled(rename = "p50")]
+    p50: String,
+    #[tabled(rename = "p90")]
+    p90: String,
+    #[tabled(rename = "p99")]
+    p99: String,
+    #[tabled(rename = "Errors")]
+    errors: String,
+    #[tabled(rename = "Responses")]
+    statuses: String,
+}
+
 pub fn print_status(result: &StatusResult) {
     let mut rows: Vec<[String; 2]> = Vec::new();
-    detail_field!(rows, "CoinPaprika API", format!("{} ({}ms)", result.coinpaprika.status, result.coinpaprika.response_time_ms));
+    detail_field!(rows, "CoinPaprika API", format!("{} (median {}ms)", result.coinpaprika.status, result.coinpaprika.response_time_ms));
     detail_field!(rows, "API Key", if result.api_key_configured { "Configured".to_string() } else { "Not set (free tier)".to_string() });
     detail_field!(rows, "Plan", if result.api_key_configured { "Paid (run key-info for details)".to_string() } else { "Free (20,000 calls/mo)".to_string() });
     print_detail_table(rows);
+
+    let ms = |v: Option<u128>| v.map_or_else(|| "—".to_string(), |v| format!("{v}ms"));
+    let rows: Vec<EndpointRow> = result.endpoints.iter().map(|e| EndpointRow {
+        endpoint: e.endpoint.clone(),
+        p50: ms(e.p50_ms),
+        p90: ms(e.p90_ms),
+        p99: ms(e.p99_ms),
+        errors: format!("{:.0}%", e.error_rate * 100.0),
+        statuses: e.statuses.iter().map(|(status, n)| format!("{status}×{n}")).collect::<Vec<_>>().join(", "),
+    }).collect();
+    println!("{}", Table::new(rows).with(table_style()));
+
+    let upgrade: Vec<&str> = result.endpoints.iter().filter(|e| e.requires_upgrade).map(|e| e.endpoint.as_str()).collect();
+    if !upgrade.is_empty() {
+        println!("\nNot in your plan (402): {}", upgrade.join(", "));
+        println!("Compare plans: coinpaprika-cli plans");
+    }
 }
@@ -1,8 +1,7 @@
 use tabled::{Table, Tabled};
-use tabled::settings::Style;
 
-use crate::commands::tags::Tag;
-use crate::output::{detail_field, print_coinpaprika_footer, print_detail_table, truncate};
+use coinpaprika_cli::types::tags::Tag;
+use crate::output::{detail_field, print_coinpaprika_footer, print_detail_table, truncate, table_style};
 
 #[derive(Tabled)]
 struct TagRow {
@@ -27,11 +26,12 @@ pub fn print_tags_table(tags: &[Tag]) {
         icos: t.ico_counter.map(|c| c.to_string()).unwrap_or_else(|| "—".into()),
     }).collect();
 
-    let table = Table::new(rows).with(Style::rounded()).to_string();
+    let table = Table::new(rows).with(table_style()).to_string();
     println!("{table}");
     print_coinpaprika_footer();
 }
 
+#[allow(clippy::vec_init_then_push)]
 pub fn print_tag_detail(tag: &Tag) {
     let mut rows: Vec<[String; 2]> = Vec::new();
     detail_field!(rows, "ID", tag.id.clone());
@@ -1,8 +1,7 @@
 use tabled::{Table, Tabled};
-use tabled::settings::Style;
 
-use crate::commands::tickers::{Ticker, TickerHistoryPoint};
-use crate::output::{detail_field, format_percent, format_price, format_supply, format_usd, print_coinpaprika_footer, print_detail_table};
+use coinpaprika_cli::types::tickers::{Ticker, TickerHistoryPoint};
+use crate::output::{detail_field, format_percent, format_price, format_supply, format_usd, print_coinpaprika_footer, print_detail_table, table_style};
 
 #[derive(Tabled)]
 struct TickerRow {
@@ -36,7 +35,7 @@ pub fn print_tickers_table(tickers: &[Ticker]) {
         }
     }).collect();
 
-    let table = Table::new(rows).with(Style::rounded()).to_string();
+    let table = Table::new(rows).with(table_style()).to_string();
     println!("{table}");
     print_coinpaprika_footer();
 }
@@ -91,7 +90,7 @@ pub fn print_history_table(history: &[TickerHistoryPoint]) {
         market_cap: h.market_cap.map(format_usd).unwrap_or_else(|| "—".into()),
     }).collect();
 
-    let table = Table::new(rows).with(Style::rounded()).to_string();
+    let table = Table::new(rows).with(table_style()).to_string();
     println!("{table}");
     print_coinpaprika_footer();
 }
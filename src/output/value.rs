@@ -0,0 +1,46 @@
+use serde_json::Value;
+use tabled::builder::Builder;
+
+use crate::output::{print_coinpaprika_footer, print_detail_table, truncate, table_style};
+
+/// Tables for arbitrary JSON (script output, `api` responses): an array of
+/// maps gets one column per key, a map becomes a detail table and anything
+/// else is printed as is.
+pub fn print_value(value: &Value) {
+    match value {
+        Value::Null => return,
+        Value::Array(rows) if !rows.is_empty() && rows.iter().all(Value::is_object) => {
+            let mut columns: Vec<&str> = Vec::new();
+            for key in rows.iter().filter_map(Value::as_object).flat_map(|row| row.keys()) {
+                if !columns.contains(&key.as_str()) {
+                    columns.push(key);
+                }
+            }
+            let mut builder = Builder::default();
+            builder.push_record(columns.iter().copied());
+            for row in rows {
+                builder.push_record(columns.iter().map(|c| row.get(*c).map(cell).unwrap_or_else(|| "—".into())));
+            }
+            println!("{}", builder.build().with(table_style()));
+        }
+        Value::Object(map) => {
+            print_detail_table(map.iter().map(|(k, v)| [k.clone(), cell(v)]).collect());
+        }
+        Value::Array(items) => {
+            for item in items {
+                println!("{}", cell(item));
+            }
+        }
+        other => println!("{}", cell(other)),
+    }
+    print_coinpaprika_footer();
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => "—".into(),
+        Value::String(s) => s.clone(),
+        Value::Array(_) | Value::Object(_) => truncate(&value.to_string(), 80),
+        other => other.to_string(),
+    }
+}
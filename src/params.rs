@@ -0,0 +1,388 @@
+//! Query parameter builders for endpoints that take more than an ID.
+//!
+//! Only the parameters that were set are sent, so the API's own defaults
+//! apply to everything else.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::dates::Period;
+use crate::plans::{History, Need};
+
+/// Candle or sample spacing for the historical endpoints, all of which take
+/// every interval; what a plan allows is checked separately. Ordered
+/// shortest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+pub enum Interval {
+    #[serde(rename = "5m")]
+    M5,
+    #[serde(rename = "10m")]
+    M10,
+    #[serde(rename = "15m")]
+    M15,
+    #[serde(rename = "30m")]
+    M30,
+    #[serde(rename = "45m")]
+    M45,
+    #[serde(rename = "1h")]
+    H1,
+    #[serde(rename = "2h")]
+    H2,
+    #[serde(rename = "3h")]
+    H3,
+    #[serde(rename = "6h")]
+    H6,
+    #[serde(rename = "12h")]
+    H12,
+    #[serde(rename = "24h")]
+    H24,
+    #[serde(rename = "1d")]
+    D1,
+    #[serde(rename = "7d")]
+    D7,
+    #[serde(rename = "14d")]
+    D14,
+    #[serde(rename = "30d")]
+    D30,
+    #[serde(rename = "90d")]
+    D90,
+    #[serde(rename = "365d")]
+    D365,
+}
+
+impl Interval {
+    pub const ALL: &'static [Interval] = &[
+        Interval::M5,
+        Interval::M10,
+        Interval::M15,
+        Interval::M30,
+        Interval::M45,
+        Interval::H1,
+        Interval::H2,
+        Interval::H3,
+        Interval::H6,
+        Interval::H12,
+        Interval::H24,
+        Interval::D1,
+        Interval::D7,
+        Interval::D14,
+        Interval::D30,
+        Interval::D90,
+        Interval::D365,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Interval::M5 => "5m",
+            Interval::M10 => "10m",
+            Interval::M15 => "15m",
+            Interval::M30 => "30m",
+            Interval::M45 => "45m",
+            Interval::H1 => "1h",
+            Interval::H2 => "2h",
+            Interval::H3 => "3h",
+            Interval::H6 => "6h",
+            Interval::H12 => "12h",
+            Interval::H24 => "24h",
+            Interval::D1 => "1d",
+            Interval::D7 => "7d",
+            Interval::D14 => "14d",
+            Interval::D30 => "30d",
+            Interval::D90 => "90d",
+            Interval::D365 => "365d",
+        }
+    }
+
+    /// A day or longer; shorter intervals are intraday.
+    pub fn is_daily(self) -> bool {
+        self >= Interval::H24
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Interval {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let value = value.trim().to_lowercase();
+        match Interval::ALL.iter().find(|i| i.as_str() == value) {
+            Some(interval) => Ok(*interval),
+            None => {
+                let all: Vec<&str> = Interval::ALL.iter().map(|i| i.as_str()).collect();
+                bail!("'{value}' is not an interval. Use one of {}.", all.join(", "))
+            }
+        }
+    }
+}
+
+/// Parameters for the historical endpoints: OHLCV, ticker history and
+/// contract history.
+#[derive(Debug, Clone)]
+pub struct HistoricalParams {
+    start: Period,
+    end: Option<Period>,
+    interval: Option<Interval>,
+    limit: Option<usize>,
+    quote: Option<String>,
+}
+
+impl HistoricalParams {
+    /// Where the range starts, e.g. `"2024-01-01".parse()?` or `"7d".parse()?`;
+    /// see [`Period`]. Required by every historical endpoint.
+    pub fn new(start: Period) -> Self {
+        Self {
+            start,
+            end: None,
+            interval: None,
+            limit: None,
+            quote: None,
+        }
+    }
+
+    /// Where the range ends; a day or longer span ends at its last second.
+    pub fn end(mut self, end: Period) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    pub fn interval(mut self, interval: Interval) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Quote currency. Not accepted by contract history.
+    pub fn quote(mut self, quote: impl Into<String>) -> Self {
+        self.quote = Some(quote.into());
+        self
+    }
+
+    /// The requested range, ending now when no end was given.
+    pub fn range(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        (self.start.start, self.end.map_or_else(Utc::now, |end| end.end))
+    }
+
+    pub fn requested_interval(&self) -> Option<Interval> {
+        self.interval
+    }
+
+    /// Fail unless the range starts before it ends.
+    pub(crate) fn check_range(&self) -> Result<()> {
+        let (start, end) = self.range();
+        if start >= end {
+            let fmt = |t: DateTime<Utc>| t.format("%Y-%m-%d %H:%M:%S UTC").to_string();
+            match self.end {
+                Some(_) => bail!("The range starts at {} but ends at {}; --start must be before --end.", fmt(start), fmt(end)),
+                None => bail!("The range starts at {}, which is in the future; --start must be in the past.", fmt(start)),
+            }
+        }
+        Ok(())
+    }
+
+    /// What the request needs from the plan.
+    pub(crate) fn need(&self, kind: History) -> Need {
+        Need::History { kind, interval: self.interval, start: self.start.start }
+    }
+
+    /// The same range with relative `--start`/`--end` values counted from `now`.
+    pub(crate) fn at(&self, now: DateTime<Utc>) -> Self {
+        Self { start: self.start.at(now), end: self.end.map(|end| end.at(now)), ..self.clone() }
+    }
+
+    pub(crate) fn query(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("start", self.start.api_start())];
+        if let Some(interval) = self.interval {
+            params.push(("interval", interval.to_string()));
+        }
+        if let Some(limit) = self.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        if let Some(quote) = &self.quote {
+            params.push(("quote", quote.clone()));
+        }
+        if let Some(end) = &self.end {
+            params.push(("end", end.api_end()));
+        }
+        params
+    }
+}
+
+/// Parameters for `/tickers`.
+#[derive(Debug, Clone, Default)]
+pub struct TickersParams {
+    quotes: Option<String>,
+    limit: Option<usize>,
+}
+
+impl TickersParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Currency quotes, comma-separated (e.g., `USD,BTC`).
+    pub fn quotes(mut self, quotes: impl Into<String>) -> Self {
+        self.quotes = Some(quotes.into());
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub(crate) fn query(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(quotes) = &self.quotes {
+            params.push(("quotes", quotes.clone()));
+        }
+        if let Some(limit) = self.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        params
+    }
+}
+
+/// Parameters for `/search`.
+#[derive(Debug, Clone)]
+pub struct SearchParams {
+    query: String,
+    categories: Option<String>,
+    limit: Option<usize>,
+    modifier: Option<String>,
+}
+
+impl SearchParams {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            categories: None,
+            limit: None,
+            modifier: None,
+        }
+    }
+
+    /// Categories to search, comma-separated (currencies,exchanges,icos,people,tags).
+    pub fn categories(mut self, categories: impl Into<String>) -> Self {
+        self.categories = Some(categories.into());
+        self
+    }
+
+    /// Maximum number of results per category.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Search modifier (e.g., `symbol_search`).
+    pub fn modifier(mut self, modifier: impl Into<String>) -> Self {
+        self.modifier = Some(modifier.into());
+        self
+    }
+
+    pub(crate) fn query(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("q", self.query.clone())];
+        if let Some(limit) = self.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        if let Some(categories) = &self.categories {
+            params.push(("categories", categories.clone()));
+        }
+        if let Some(modifier) = &self.modifier {
+            params.push(("modifier", modifier.clone()));
+        }
+        params
+    }
+}
+
+/// Paging parameters for list endpoints such as `/changelog/ids`.
+#[derive(Debug, Clone, Default)]
+pub struct PageParams {
+    limit: Option<usize>,
+    page: Option<usize>,
+}
+
+impl PageParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Page number, starting at 1.
+    pub fn page(mut self, page: usize) -> Self {
+        self.page = Some(page);
+        self
+    }
+
+    pub(crate) fn query(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(limit) = self.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        if let Some(page) = self.page {
+            params.push(("page", page.to_string()));
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn period(value: &str) -> Period {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn historical_params_only_sends_what_was_set() {
+        let params = HistoricalParams::new(period("2024-01-01")).interval(Interval::H24).query();
+        assert_eq!(params, vec![("start", "2024-01-01T00:00:00Z".to_string()), ("interval", "24h".to_string())]);
+    }
+
+    #[test]
+    fn historical_params_end_goes_last() {
+        let params = HistoricalParams::new(period("2024-01-01")).end(period("2024-02-01")).limit(5).quote("usd").query();
+        let keys: Vec<&str> = params.iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec!["start", "limit", "quote", "end"]);
+        assert_eq!(params[3].1, "2024-02-01T23:59:59Z");
+    }
+
+    #[test]
+    fn historical_range_must_be_ordered() {
+        assert!(HistoricalParams::new(period("2024-01-01")).end(period("2024-01-01")).check_range().is_ok());
+        let err = HistoricalParams::new(period("2024-02-01")).end(period("2024-01-01")).check_range().unwrap_err();
+        assert!(err.to_string().contains("--start must be before --end"), "{err}");
+        assert!(HistoricalParams::new(Period::parse_at("1d", Utc::now() + chrono::Duration::days(2)).unwrap()).check_range().is_err());
+    }
+
+    #[test]
+    fn intervals_parse() {
+        assert_eq!("1H".parse::<Interval>().unwrap(), Interval::H1);
+        assert!(Interval::H24.is_daily() && !Interval::H12.is_daily());
+        assert!("4h".parse::<Interval>().unwrap_err().to_string().contains("5m, 10m"));
+    }
+
+    #[test]
+    fn search_params_query_first() {
+        let params = SearchParams::new("btc").modifier("symbol_search").query();
+        assert_eq!(params[0], ("q", "btc".to_string()));
+        assert_eq!(params[1], ("modifier", "symbol_search".to_string()));
+    }
+}
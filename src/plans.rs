@@ -0,0 +1,267 @@
+//! What each plan allows, as data, so requests a plan can't serve fail
+//! before they spend a call.
+//!
+//! The matrix mirrors <https://coinpaprika.com/api/pricing>. The API has the
+//! final word: anything these checks let through still gets the usual 402
+//! handling.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::params::Interval;
+
+pub const PRICING_URL: &str = "https://coinpaprika.com/api/pricing";
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Plan {
+    pub name: &'static str,
+    /// Intervals the historical endpoints serve.
+    pub intervals: &'static [Interval],
+    /// Days of daily history; `None` is the full history.
+    pub daily_history_days: Option<i64>,
+    /// Days of hourly and finer data; `None` is the full history.
+    pub intraday_history_days: Option<i64>,
+    /// `/tickers/{id}/historical` and contract history.
+    pub ticker_history: bool,
+    /// `/coins/mappings`
+    pub mappings: bool,
+    /// `/changelog/ids`
+    pub changelog: bool,
+}
+
+/// As `plans` has always published it: a year of daily OHLCV at 24h, the
+/// last 24 hours of hourly OHLCV, and no 5-minute or ticker history.
+pub const FREE: Plan = Plan {
+    name: "free",
+    intervals: &[Interval::H24, Interval::D1],
+    daily_history_days: Some(365),
+    intraday_history_days: Some(1),
+    ticker_history: false,
+    mappings: false,
+    changelog: false,
+};
+
+/// Cheapest first, so the first plan that allows something is the upgrade hint.
+///
+/// The pricing page lists full history and 5-minute intervals for every
+/// paid plan without a per-plan depth, so paid plans get no history limit
+/// here and the API decides.
+pub const PLANS: &[Plan] = &[
+    FREE,
+    // Pricing page: historical data, ticker history and the ID changelog.
+    Plan {
+        name: "starter",
+        intervals: Interval::ALL,
+        daily_history_days: None,
+        intraday_history_days: None,
+        ticker_history: true,
+        mappings: false,
+        changelog: true,
+    },
+    // Pricing page: between Starter and Business, so Starter's features; ID
+    // mappings start at Business.
+    Plan {
+        name: "pro",
+        intervals: Interval::ALL,
+        daily_history_days: None,
+        intraday_history_days: None,
+        ticker_history: true,
+        mappings: false,
+        changelog: true,
+    },
+    // Pricing page: everything in Starter, plus ID mappings.
+    Plan {
+        name: "business",
+        intervals: Interval::ALL,
+        daily_history_days: None,
+        intraday_history_days: None,
+        ticker_history: true,
+        mappings: true,
+        changelog: true,
+    },
+    // Pricing page: everything in Business, with custom limits.
+    Plan {
+        name: "enterprise",
+        intervals: Interval::ALL,
+        daily_history_days: None,
+        intraday_history_days: None,
+        ticker_history: true,
+        mappings: true,
+        changelog: true,
+    },
+];
+
+/// The plan `/key/info` names, ignoring case and a trailing " plan".
+pub fn find(name: &str) -> Option<&'static Plan> {
+    let name = name.trim().to_lowercase();
+    let name = name.strip_suffix(" plan").unwrap_or(&name);
+    PLANS.iter().find(|p| p.name == name)
+}
+
+/// The historical endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum History {
+    Ohlcv,
+    Ticker,
+    Contract,
+}
+
+impl History {
+    fn label(self) -> &'static str {
+        match self {
+            History::Ohlcv => "OHLCV history",
+            History::Ticker => "ticker history",
+            History::Contract => "contract history",
+        }
+    }
+
+    /// What the API uses when no interval is given.
+    fn default_interval(self) -> Interval {
+        match self {
+            History::Ohlcv => Interval::H24,
+            History::Ticker | History::Contract => Interval::M5,
+        }
+    }
+}
+
+/// Something a request needs from the plan.
+#[derive(Debug, Clone, Copy)]
+pub enum Need {
+    History { kind: History, interval: Option<Interval>, start: DateTime<Utc> },
+    Mappings,
+    Changelog,
+}
+
+impl Need {
+    /// What calling `endpoint` needs at the least, for endpoints in the form
+    /// the usage log keeps them (`/tickers/{id}/historical`). `None` for
+    /// endpoints every plan has, and for OHLCV history, whose limits depend
+    /// on the request.
+    pub fn for_endpoint(endpoint: &str) -> Option<Need> {
+        let history = |kind| Need::History { kind, interval: None, start: Utc::now() };
+        match endpoint {
+            "/tickers/{id}/historical" => Some(history(History::Ticker)),
+            "/contracts/{id}/{id}/historical" => Some(history(History::Contract)),
+            "/coins/mappings" => Some(Need::Mappings),
+            "/changelog/ids" => Some(Need::Changelog),
+            _ => None,
+        }
+    }
+}
+
+impl Plan {
+    /// The intervals as "24h, 1d".
+    pub fn interval_list(&self) -> String {
+        self.intervals.iter().map(|i| i.as_str()).collect::<Vec<_>>().join(", ")
+    }
+
+    fn history_days(&self, interval: Interval) -> Option<i64> {
+        if interval.is_daily() {
+            self.daily_history_days
+        } else {
+            self.intraday_history_days
+        }
+    }
+
+    /// Fail with what to do instead if this plan can't serve `need`.
+    pub fn check(&self, need: Need) -> Result<()> {
+        match need {
+            Need::Mappings if !self.mappings => self.missing("ID mappings", |p| p.mappings),
+            Need::Changelog if !self.changelog => self.missing("The ID changelog", |p| p.changelog),
+            Need::History { kind, .. } if kind != History::Ohlcv && !self.ticker_history => {
+                self.missing(&capitalize(kind.label()), |p| p.ticker_history)
+            }
+            Need::History { kind, interval, start } => {
+                let interval = interval.unwrap_or(kind.default_interval());
+                if !self.intervals.contains(&interval) {
+                    let hint = self.upgrade_hint(|p| p.intervals.contains(&interval));
+                    bail!(
+                        "The {} plan doesn't offer the {interval} interval for {}; it has {}.\n\n\
+                         Use --interval {}, or {hint}",
+                        self.name,
+                        kind.label(),
+                        self.interval_list(),
+                        self.intervals[0],
+                    );
+                }
+                let Some(days) = self.history_days(interval) else {
+                    return Ok(());
+                };
+                let earliest = Utc::now().date_naive() - Duration::days(days);
+                let start = start.date_naive();
+                if start < earliest {
+                    let back = (Utc::now().date_naive() - start).num_days();
+                    let hint = self.upgrade_hint(|p| p.history_days(interval).is_none_or(|d| d >= back));
+                    bail!(
+                        "The {} plan's {interval} history goes back {days} days, to {earliest}; --start {start} is earlier.\n\n\
+                         Use --start {earliest} or later, or {hint}",
+                        self.name,
+                    );
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn missing(&self, what: &str, allows: impl Fn(&Plan) -> bool) -> Result<()> {
+        bail!("{what} isn't included in the {} plan.\n\n{}", self.name, capitalize(&self.upgrade_hint(allows)))
+    }
+
+    /// "upgrade to the X plan or higher: URL", for the cheapest plan that allows it.
+    fn upgrade_hint(&self, allows: impl Fn(&Plan) -> bool) -> String {
+        let plan = PLANS.iter().find(|p| allows(p)).map_or("enterprise", |p| p.name);
+        let mut hint = format!("upgrade to the {plan} plan or higher: {PRICING_URL}\nCompare plans:  coinpaprika-cli plans");
+        if self.name == FREE.name {
+            hint.push_str("\nSet your key:   coinpaprika-cli config set-key <KEY>");
+        }
+        hint
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(kind: History, interval: &str, start: &str) -> Need {
+        let start = start.parse::<crate::dates::Period>().unwrap().start;
+        Need::History { kind, interval: Some(interval.parse().unwrap()), start }
+    }
+
+    #[test]
+    fn free_tier_limits() {
+        let recent = "30d";
+        assert!(FREE.check(history(History::Ohlcv, "24h", recent)).is_ok());
+
+        let err = FREE.check(history(History::Ohlcv, "5m", recent)).unwrap_err().to_string();
+        assert!(err.contains("doesn't offer the 5m interval") && err.contains("starter plan"), "{err}");
+
+        let err = FREE.check(history(History::Ohlcv, "24h", "2015-01-01")).unwrap_err().to_string();
+        assert!(err.contains("goes back 365 days") && err.contains("starter plan"), "{err}");
+
+        let err = FREE.check(history(History::Ticker, "24h", recent)).unwrap_err().to_string();
+        assert!(err.starts_with("Ticker history isn't included in the free plan"), "{err}");
+        assert!(FREE.check(Need::Mappings).is_err());
+    }
+
+    #[test]
+    fn plans_by_name() {
+        assert_eq!(find("Business").map(|p| p.name), Some("business"));
+        assert_eq!(find("starter plan").map(|p| p.name), Some("starter"));
+        assert!(find("platinum").is_none());
+        let business = find("business").unwrap();
+        assert!(business.check(Need::Mappings).is_ok());
+        assert!(find("starter").unwrap().check(Need::Mappings).unwrap_err().to_string().contains("business plan"));
+
+        let ticker_history = Need::for_endpoint("/tickers/{id}/historical").unwrap();
+        assert!(FREE.check(ticker_history).is_err() && find("starter").unwrap().check(ticker_history).is_ok());
+        assert!(Need::for_endpoint("/global").is_none());
+    }
+}
@@ -0,0 +1,233 @@
+//! Turn `BTC`, `bitcoin` or `Bitcoin Cash` into a CoinPaprika coin ID.
+
+use anyhow::{bail, Result};
+use chrono::Duration;
+use std::fmt;
+
+use crate::cache;
+use crate::types::CoinListItem;
+use crate::ApiClient;
+
+/// How long the cached `/coins` list is trusted before it is refetched.
+pub const COINS_MAX_AGE: Duration = Duration::hours(24);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    Id,
+    Symbol,
+    Name,
+    /// Within a small edit distance of an ID or name.
+    NearMiss,
+}
+
+impl fmt::Display for MatchKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MatchKind::Id => "id",
+            MatchKind::Symbol => "symbol",
+            MatchKind::Name => "name",
+            MatchKind::NearMiss => "closest match",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    pub id: String,
+    pub kind: MatchKind,
+    pub rank: Option<i64>,
+}
+
+/// The `/coins` list, cached on disk for [`COINS_MAX_AGE`].
+pub async fn cached_coins(client: &ApiClient) -> Result<Vec<CoinListItem>> {
+    Ok(cache::get_or_fetch(&client.cache_name("coins"), COINS_MAX_AGE, || client.coins()).await?.data)
+}
+
+/// Resolves coin arguments, fetching the coin list at most once and only
+/// when an argument is not already shaped like an ID.
+pub struct Resolver<'a> {
+    client: &'a ApiClient,
+    coins: Option<Vec<CoinListItem>>,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(client: &'a ApiClient) -> Self {
+        Self { client, coins: None }
+    }
+
+    /// `Ok(None)` means nothing matched and the input should be passed on
+    /// unchanged, e.g. fiat IDs, which are not in `/coins`.
+    pub async fn resolve(&mut self, input: &str) -> Result<Option<Resolution>> {
+        if looks_like_id(input) {
+            return Ok(None);
+        }
+        if self.coins.is_none() {
+            self.coins = Some(cached_coins(self.client).await?);
+        }
+        resolve(input, self.coins.as_deref().unwrap_or_default())
+    }
+}
+
+/// `symbol-name` in lowercase, e.g. `btc-bitcoin`. These are sent as-is so
+/// the common case needs no coin list at all.
+fn looks_like_id(input: &str) -> bool {
+    input.contains('-') && input.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Match `input` against `coins` by ID, symbol, name and finally edit
+/// distance. Symbol and name collisions go to the best-ranked active coin;
+/// anything still ambiguous is an error listing the candidates.
+pub fn resolve(input: &str, coins: &[CoinListItem]) -> Result<Option<Resolution>> {
+    let needle = input.trim().to_lowercase();
+    if needle.is_empty() {
+        return Ok(None);
+    }
+
+    if let Some(coin) = coins.iter().find(|c| c.id == needle) {
+        return Ok(Some(resolution(coin, MatchKind::Id)));
+    }
+
+    let by_symbol: Vec<&CoinListItem> = coins.iter().filter(|c| c.symbol.to_lowercase() == needle).collect();
+    if !by_symbol.is_empty() {
+        return pick(input, by_symbol, MatchKind::Symbol).map(Some);
+    }
+
+    let by_name: Vec<&CoinListItem> = coins.iter().filter(|c| c.name.to_lowercase() == needle).collect();
+    if !by_name.is_empty() {
+        return pick(input, by_name, MatchKind::Name).map(Some);
+    }
+
+    let max_distance = match needle.chars().count() {
+        0..=4 => return Ok(None),
+        5..=8 => 1,
+        _ => 2,
+    };
+    let mut best = usize::MAX;
+    let mut near: Vec<&CoinListItem> = Vec::new();
+    for coin in coins {
+        let distance = levenshtein(&needle, &coin.id).min(levenshtein(&needle, &coin.name.to_lowercase()));
+        if distance > max_distance || distance > best {
+            continue;
+        }
+        if distance < best {
+            best = distance;
+            near.clear();
+        }
+        near.push(coin);
+    }
+    match near.len() {
+        0 => Ok(None),
+        1 => Ok(Some(resolution(near[0], MatchKind::NearMiss))),
+        _ => ambiguous(input, near),
+    }
+}
+
+fn resolution(coin: &CoinListItem, kind: MatchKind) -> Resolution {
+    Resolution { id: coin.id.clone(), kind, rank: coin.rank }
+}
+
+fn is_ranked_active(coin: &CoinListItem) -> bool {
+    coin.is_active != Some(false) && coin.rank.is_some_and(|r| r > 0)
+}
+
+fn pick(input: &str, candidates: Vec<&CoinListItem>, kind: MatchKind) -> Result<Resolution> {
+    if let Some(best) = candidates.iter().filter(|c| is_ranked_active(c)).min_by_key(|c| c.rank) {
+        return Ok(resolution(best, kind));
+    }
+    if let [only] = candidates.as_slice() {
+        return Ok(resolution(only, kind));
+    }
+    ambiguous(input, candidates)
+}
+
+fn ambiguous<T>(input: &str, mut candidates: Vec<&CoinListItem>) -> Result<T> {
+    candidates.sort_by_key(|c| (!is_ranked_active(c), c.rank.unwrap_or(i64::MAX)));
+    let list: Vec<String> = candidates
+        .iter()
+        .take(10)
+        .map(|c| match c.rank.filter(|r| *r > 0) {
+            Some(rank) => format!("  {:<30} {} ({}), rank {rank}", c.id, c.name, c.symbol),
+            None => format!("  {:<30} {} ({})", c.id, c.name, c.symbol),
+        })
+        .collect();
+    bail!(
+        "'{input}' matches more than one coin:\n{}\n\nPass one of these IDs instead.",
+        list.join("\n")
+    )
+}
+
+/// Edit distance between two strings, by characters.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            row[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coin(id: &str, name: &str, symbol: &str, rank: i64, active: bool) -> CoinListItem {
+        CoinListItem {
+            id: id.into(),
+            name: name.into(),
+            symbol: symbol.into(),
+            rank: Some(rank),
+            is_new: Some(false),
+            is_active: Some(active),
+            coin_type: Some("coin".into()),
+        }
+    }
+
+    fn coins() -> Vec<CoinListItem> {
+        vec![
+            coin("btc-bitcoin", "Bitcoin", "BTC", 1, true),
+            coin("btc-bitcoin-token", "Bitcoin Token", "BTC", 0, false),
+            coin("eth-ethereum", "Ethereum", "ETH", 2, true),
+            coin("bch-bitcoin-cash", "Bitcoin Cash", "BCH", 15, true),
+            coin("abc-alpha", "Alpha", "ABC", 0, true),
+            coin("abc-abacus", "Abacus", "ABC", 0, true),
+        ]
+    }
+
+    #[test]
+    fn symbol_collision_prefers_best_ranked_active() {
+        let r = resolve("btc", &coins()).unwrap().unwrap();
+        assert_eq!(r.id, "btc-bitcoin");
+        assert_eq!(r.kind, MatchKind::Symbol);
+    }
+
+    #[test]
+    fn resolves_names_and_near_misses() {
+        assert_eq!(resolve("Bitcoin Cash", &coins()).unwrap().unwrap().id, "bch-bitcoin-cash");
+        let r = resolve("etherum", &coins()).unwrap().unwrap();
+        assert_eq!((r.id.as_str(), r.kind), ("eth-ethereum", MatchKind::NearMiss));
+    }
+
+    #[test]
+    fn ambiguous_symbol_lists_candidates() {
+        let err = resolve("ABC", &coins()).unwrap_err().to_string();
+        assert!(err.contains("abc-alpha") && err.contains("abc-abacus"));
+    }
+
+    #[test]
+    fn short_unknown_input_is_left_alone() {
+        assert_eq!(resolve("usd", &coins()).unwrap(), None);
+        assert!(looks_like_id("usd-us-dollars"));
+        assert!(!looks_like_id("BTC"));
+    }
+
+    #[test]
+    fn levenshtein_distance() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+}
@@ -0,0 +1,132 @@
+//! API keys kept out of the plaintext config: fetched from a command such as
+//! `pass show coinpaprika`, or stored in a file encrypted with a passphrase
+//! (AES-256-GCM, key derived with PBKDF2-HMAC-SHA256).
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroU32;
+use std::process::Command;
+
+/// Where the passphrase comes from when there's no one to prompt.
+pub const PASSPHRASE_ENV: &str = "COINPAPRIKA_PASSPHRASE";
+
+const ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+
+/// The contents of an encrypted key file.
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedKey {
+    version: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Encrypt `key` with `passphrase`, returning the file contents.
+pub fn encrypt(key: &str, passphrase: &str) -> Result<String> {
+    encrypt_with(key, passphrase, ITERATIONS)
+}
+
+fn encrypt_with(key: &str, passphrase: &str, iterations: u32) -> Result<String> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt).map_err(|_| anyhow!("Failed to generate a random salt"))?;
+    rng.fill(&mut nonce).map_err(|_| anyhow!("Failed to generate a random nonce"))?;
+
+    let mut ciphertext = key.as_bytes().to_vec();
+    cipher(passphrase, &salt, iterations)?
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut ciphertext)
+        .map_err(|_| anyhow!("Failed to encrypt the API key"))?;
+
+    let file = EncryptedKey {
+        version: 1,
+        kdf: "pbkdf2-sha256".into(),
+        iterations,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+    Ok(serde_json::to_string_pretty(&file)?)
+}
+
+/// Decrypt the contents of a key file written by [`encrypt`].
+pub fn decrypt(contents: &str, passphrase: &str) -> Result<String> {
+    let file: EncryptedKey = serde_json::from_str(contents).context("Not an encrypted key file")?;
+    if file.version != 1 || file.kdf != "pbkdf2-sha256" {
+        bail!("Unsupported key file (version {}, {}). Re-create it with: coinpaprika-cli config set-key --encrypt", file.version, file.kdf);
+    }
+    let salt = BASE64.decode(&file.salt).context("Corrupt key file: salt")?;
+    let nonce: [u8; NONCE_LEN] = BASE64
+        .decode(&file.nonce)
+        .ok()
+        .and_then(|n| n.try_into().ok())
+        .context("Corrupt key file: nonce")?;
+    let mut ciphertext = BASE64.decode(&file.ciphertext).context("Corrupt key file: ciphertext")?;
+
+    let plaintext = cipher(passphrase, &salt, file.iterations)?
+        .open_in_place(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut ciphertext)
+        .map_err(|_| anyhow!("Could not decrypt the API key: wrong passphrase?"))?;
+    String::from_utf8(plaintext.to_vec()).context("Corrupt key file: the key is not text")
+}
+
+fn cipher(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey> {
+    let iterations = NonZeroU32::new(iterations).context("Corrupt key file: zero iterations")?;
+    let mut key = [0u8; 32];
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key);
+    let key = UnboundKey::new(&AES_256_GCM, &key).map_err(|_| anyhow!("Failed to derive the encryption key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+/// A short name for `key` that doesn't reveal it, for file names and logs.
+pub fn fingerprint(key: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, key.as_bytes());
+    digest.as_ref()[..8].iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Run `command` through the shell and return the first line it prints.
+pub fn run_key_command(command: &str) -> Result<String> {
+    #[cfg(unix)]
+    let output = Command::new("sh").arg("-c").arg(command).output();
+    #[cfg(not(unix))]
+    let output = Command::new("cmd").arg("/C").arg(command).output();
+
+    let output = output.with_context(|| format!("Failed to run api_key_command `{command}`"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("api_key_command `{command}` failed ({}): {}", output.status, stderr.trim());
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.lines().next().map(str::trim) {
+        Some(key) if !key.is_empty() => Ok(key.to_string()),
+        _ => bail!("api_key_command `{command}` printed nothing"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypted_key_round_trips() {
+        let contents = encrypt_with("my-secret-key", "hunter2", 1_000).unwrap();
+        assert!(!contents.contains("my-secret-key"));
+        assert_eq!(decrypt(&contents, "hunter2").unwrap(), "my-secret-key");
+        assert!(decrypt(&contents, "hunter3").unwrap_err().to_string().contains("wrong passphrase"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn key_command_uses_first_line() {
+        assert_eq!(run_key_command("printf 'abc\\nsecond line\\n'").unwrap(), "abc");
+        assert!(run_key_command("exit 3").is_err());
+        assert!(run_key_command("true").is_err());
+    }
+}
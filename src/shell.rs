@@ -1,45 +1,352 @@
-use clap::Parser;
+use anyhow::{bail, Context as _, Result};
+use clap::{CommandFactory, FromArgMatches, Subcommand, ValueEnum};
+use coinpaprika_cli::client::CassetteMode;
+use coinpaprika_cli::config::{self, Setting, Settings, Source};
+use coinpaprika_cli::{index, ApiClient};
+use rustyline::completion::{Completer, Pair};
+use rustyline::config::Config;
 use rustyline::error::ReadlineError;
-use rustyline::DefaultEditor;
+use rustyline::highlight::{CmdKind, Highlighter};
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{ColorMode, CompletionType, Context, Editor, Helper};
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::time::Duration;
 
-pub async fn run_shell() {
+use crate::commands::{self, CommandOutput};
+use crate::completion::{Engine, Role};
+use crate::output::{self, OutputFormat};
+use crate::{Cli, Commands};
+
+/// How long startup waits for ID lists that aren't cached yet.
+const ID_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the session reuses a response before asking the API again.
+const RESPONSE_MAX_AGE: Duration = Duration::from_secs(60);
+
+/// Lines kept in [`config::SHELL_HISTORY_FILE`].
+const HISTORY_SIZE: usize = 1000;
+
+/// Commands the shell handles itself.
+#[derive(Subcommand)]
+enum BuiltIn {
+    /// Leave the shell
+    Exit,
+    #[command(hide = true)]
+    Quit,
+    /// Change a default for the rest of the session
+    #[command(subcommand, disable_help_subcommand = true)]
+    Set(SetCommand),
+    /// Switch to a config profile, with its key and defaults
+    Use { profile: String },
+    /// Show the last result again, e.g. in another format
+    Last { format: Option<OutputFormat> },
+    /// Write the last result to FILE: CSV for .csv, JSON otherwise
+    Save { file: PathBuf },
+}
+
+#[derive(Subcommand)]
+enum SetCommand {
+    /// Output format for commands that don't pass --output
+    Output { format: OutputFormat },
+    /// Quote currencies for commands that don't pass --quotes, e.g. EUR or USD,BTC
+    Quotes { quotes: String },
+}
+
+/// The CLI with the built-ins added, for parsing lines and completing them.
+fn shell_command(settings: &Settings) -> clap::Command {
+    BuiltIn::augment_subcommands(crate::cli_command(settings))
+}
+
+/// Tab completion, hints and highlighting from [`Engine`].
+struct ShellHelper {
+    engine: Engine,
+}
+
+impl ShellHelper {
+    /// The words before the one at `pos`, that word's start, and the part of
+    /// it before `pos`.
+    fn split(line: &str, pos: usize) -> (Vec<String>, usize, &str) {
+        let start = line[..pos]
+            .char_indices()
+            .rfind(|(_, c)| c.is_whitespace())
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        (split_args(&line[..start]), start, &line[start..pos])
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (words, start, current) = Self::split(line, pos);
+        let candidates = self
+            .engine
+            .complete(&words, current)
+            .into_iter()
+            .map(|c| Pair { display: c.value.clone(), replacement: c.value })
+            .collect();
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+
+    fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<String> {
+        if pos < line.len() {
+            return None;
+        }
+        let (words, _, current) = Self::split(line, pos);
+        self.engine.hint(&words, current)
+    }
+}
+
+impl Highlighter for ShellHelper {
+    fn highlight<'l>(&self, line: &'l str, pos: usize) -> Cow<'l, str> {
+        let words: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        let Some((last, before)) = words.split_last() else {
+            return Cow::Borrowed(line);
+        };
+        let mut roles = self.engine.roles(&words);
+        // A word still being typed isn't wrong while it can be completed.
+        let typing = pos == line.len() && !line.ends_with(char::is_whitespace);
+        if let Some(role @ (Role::UnknownCommand | Role::UnknownFlag)) = roles.last_mut() {
+            if typing && !self.engine.complete(before, last).is_empty() {
+                *role = Role::Value;
+            }
+        }
+
+        // Only colour codes are added, so the line keeps its width.
+        let mut out = String::with_capacity(line.len() * 2);
+        let mut rest = line;
+        for (word, role) in words.iter().zip(roles) {
+            let at = rest.find(word.as_str()).unwrap_or(0);
+            out.push_str(&rest[..at]);
+            let colour = match role {
+                Role::Command => "\x1b[1;32m",
+                Role::Flag => "\x1b[36m",
+                Role::UnknownCommand | Role::UnknownFlag => "\x1b[31m",
+                Role::Value => "",
+            };
+            if colour.is_empty() {
+                out.push_str(word);
+            } else {
+                out.push_str(&format!("{colour}{word}\x1b[0m"));
+            }
+            rest = &rest[at + word.len()..];
+        }
+        out.push_str(rest);
+        Cow::Owned(out)
+    }
+
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(format!("\x1b[2m{hint}\x1b[0m"))
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize, _kind: CmdKind) -> bool {
+        true
+    }
+}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+/// What the shell keeps from line to line.
+struct Session {
+    client: ApiClient,
+    settings: Settings,
+    cassette: Option<CassetteMode>,
+    /// The last result shown, for `last` and `save`.
+    last: Option<CommandOutput>,
+}
+
+impl Session {
+    fn prompt(&self) -> String {
+        match self.settings.profile_name() {
+            Some(profile) => format!("coinpaprika ({profile})> "),
+            None => "coinpaprika> ".to_string(),
+        }
+    }
+
+    /// Run one line; `false` means leave the shell.
+    async fn run_line(&mut self, args: Vec<String>) -> Result<bool> {
+        let mut argv = vec!["coinpaprika-cli".to_string()];
+        argv.extend(args);
+        // A line's own --profile brings that profile's defaults.
+        let own_profile = argv.iter().any(|a| a == "--profile" || a.starts_with("--profile="));
+        let defaults = if own_profile { crate::defaults_for(&argv) } else { self.settings.clone() };
+        let matches = match shell_command(&defaults).try_get_matches_from(&argv) {
+            Ok(matches) => matches,
+            Err(e) => {
+                eprintln!("{e}");
+                return Ok(true);
+            }
+        };
+        if matches.subcommand_name().is_some_and(BuiltIn::has_subcommand) {
+            let format = *matches.get_one::<OutputFormat>("output").expect("--output has a default");
+            return self.built_in(BuiltIn::from_arg_matches(&matches)?, format, matches.get_flag("raw"));
+        }
+        self.run_command(Cli::from_arg_matches(&matches)?).await?;
+        Ok(true)
+    }
+
+    /// `format` and `raw` are the session's, or the line's `--output` and `--raw`.
+    fn built_in(&mut self, command: BuiltIn, format: OutputFormat, raw: bool) -> Result<bool> {
+        match command {
+            BuiltIn::Exit | BuiltIn::Quit => return Ok(false),
+            BuiltIn::Set(SetCommand::Output { format }) => {
+                let value = format.to_possible_value().map(|v| v.get_name().to_string()).unwrap_or_default();
+                self.settings.output = Some(Setting { value, source: Source::Shell });
+            }
+            BuiltIn::Set(SetCommand::Quotes { quotes }) => {
+                self.settings.quotes = Some(Setting { value: quotes, source: Source::Shell });
+            }
+            BuiltIn::Use { profile } => self.load(Some(&profile))?,
+            BuiltIn::Last { format: shown } => output::render(self.last()?, shown.unwrap_or(format), raw)?,
+            BuiltIn::Save { file } => {
+                let csv = file.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+                let format = if csv { OutputFormat::Csv } else { OutputFormat::Json };
+                let (_, contents) = output::to_file(self.last()?, format, raw)?;
+                std::fs::write(&file, contents).with_context(|| format!("Failed to write {}", file.display()))?;
+                println!("Saved to {}", file.display());
+            }
+        }
+        Ok(true)
+    }
+
+    fn last(&self) -> Result<&CommandOutput> {
+        self.last.as_ref().context("Nothing to show yet; run a command first.")
+    }
+
+    /// Run a CLI command on the session's client. Lines that pick another
+    /// key, host or cassette, and commands with a loop of their own, run
+    /// on their own as they would outside the shell.
+    async fn run_command(&mut self, cli: Cli) -> Result<()> {
+        let own_client = cli.profile.is_some() || cli.api_key.is_some() || cli.base_url.is_some() || cli.record.is_some() || cli.replay.is_some();
+        match &cli.command {
+            Commands::Shell => bail!("Already in the shell."),
+            Commands::Onboard { .. } => {
+                crate::run(cli).await?;
+                return self.reload();
+            }
+            Commands::Completions { .. }
+            | Commands::Complete { .. }
+            | Commands::MockServer(_)
+            | Commands::Batch(_)
+            | Commands::Exec(_)
+            | Commands::Script(_)
+            | Commands::External(_) => return crate::run(cli).await,
+            Commands::Status(args) if args.watch => return crate::run(cli).await,
+            _ if own_client => return crate::run(cli).await,
+            _ => {}
+        }
+
+        output::set_table_style(cli.table_style);
+        let exit_status: Option<fn(&CommandOutput) -> Result<()>> = match cli.command {
+            Commands::Status(_) => Some(commands::status::exit_status),
+            Commands::Doctor => Some(commands::doctor::exit_status),
+            _ => None,
+        };
+        let changes_config = matches!(cli.command, Commands::Config(_));
+        let result = crate::execute(cli.command, &self.client, &self.settings, cli.strict).await;
+        if changes_config {
+            self.reload()?;
+        }
+        let result = result?;
+        output::render(&result, cli.output, cli.raw)?;
+        let status = exit_status.map_or(Ok(()), |check| check(&result));
+        self.last = Some(result);
+        status
+    }
+
+    /// Pick up config changes, keeping the profile if one was chosen.
+    fn reload(&mut self) -> Result<()> {
+        let chosen = self.settings.profile.clone().filter(|p| matches!(p.source, Source::Flag(_)));
+        self.load(chosen.as_ref().map(|p| p.value.as_str()))
+    }
+
+    /// Settings for `profile` and a client for them. Flags the shell was
+    /// started with and values from `set` still apply.
+    fn load(&mut self, profile: Option<&str>) -> Result<()> {
+        let flag = |setting: &Option<Setting>| setting.clone().filter(|s| matches!(s.source, Source::Flag(_))).map(|s| s.value);
+        let mut settings = Settings::resolve(profile, flag(&self.settings.api_key).as_deref(), flag(&self.settings.base_url).as_deref())?;
+        settings.unlock(commands::config::ask_passphrase)?;
+        for (new, old) in [(&mut settings.output, &self.settings.output), (&mut settings.quotes, &self.settings.quotes)] {
+            if old.as_ref().is_some_and(|s| s.source == Source::Shell) {
+                new.clone_from(old);
+            }
+        }
+        self.client = crate::client_for(&settings, self.cassette.clone()).with_response_cache_for(RESPONSE_MAX_AGE);
+        self.settings = settings;
+        Ok(())
+    }
+}
+
+fn editor(base_url: &str) -> rustyline::Result<Editor<ShellHelper, DefaultHistory>> {
+    let color_mode = if std::env::var_os("NO_COLOR").is_some() { ColorMode::Disabled } else { ColorMode::Enabled };
+    let config = Config::builder()
+        .max_history_size(HISTORY_SIZE)?
+        .history_ignore_space(true)
+        .completion_type(CompletionType::List)
+        .color_mode(color_mode)
+        .build();
+    let mut rl = Editor::with_config(config)?;
+    let mut engine = Engine::with_command(BuiltIn::augment_subcommands(Cli::command()));
+    engine.set_base_url(base_url);
+    rl.set_helper(Some(ShellHelper { engine }));
+    Ok(rl)
+}
+
+/// Lines that may hold an API key, which history leaves out.
+fn has_key(args: &[String]) -> bool {
+    args.iter().any(|a| a.starts_with("--api-key") || a.starts_with("--key"))
+        || matches!(args, [config, set_key, ..] if config == "config" && set_key == "set-key")
+}
+
+pub async fn run_shell(client: ApiClient, settings: Settings, cassette: Option<CassetteMode>) {
     println!("coinpaprika-cli interactive shell — type commands without 'coinpaprika-cli' prefix");
-    println!("Type 'exit' or 'quit' to leave. Ctrl+D also exits.\n");
+    println!("Type 'exit' or 'quit' to leave. Ctrl+D also exits. Tab completes commands, flags and IDs.");
+    println!("Built-ins: set output|quotes VALUE, use PROFILE, last [FORMAT], save FILE.\n");
 
-    let mut rl = match DefaultEditor::new() {
+    let mut session = Session { client: client.with_response_cache_for(RESPONSE_MAX_AGE), settings, cassette, last: None };
+    // Best effort: without the lists, IDs just don't complete.
+    let _ = tokio::time::timeout(ID_FETCH_TIMEOUT, index::fill_id_caches(&session.client)).await;
+
+    let mut rl = match editor(session.client.base_url()) {
         Ok(rl) => rl,
         Err(e) => {
             eprintln!("Failed to initialize shell: {e}");
             return;
         }
     };
+    let history = config::state_dir().map(|dir| dir.join(config::SHELL_HISTORY_FILE)).ok();
+    if let Some(path) = &history {
+        // Missing on first use.
+        let _ = rl.load_history(path);
+    }
 
     loop {
-        match rl.readline("coinpaprika> ") {
+        match rl.readline(&session.prompt()) {
             Ok(line) => {
-                let line = line.trim();
-                if line.is_empty() {
+                let args = split_args(&line);
+                if args.is_empty() {
                     continue;
                 }
-                if line == "exit" || line == "quit" {
-                    break;
+                if !has_key(&args) {
+                    let _ = rl.add_history_entry(line.trim_end());
                 }
-
-                let _ = rl.add_history_entry(line);
-
-                let args = split_args(line);
-                let mut full_args = vec!["coinpaprika-cli".to_string()];
-                full_args.extend(args);
-
-                match crate::Cli::try_parse_from(&full_args) {
-                    Ok(cli) => {
-                        if let Err(e) = crate::run(cli).await {
-                            eprintln!("Error: {e}");
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("{e}");
-                    }
+                match session.run_line(args).await {
+                    Ok(true) => {}
+                    Ok(false) => break,
+                    Err(e) => eprintln!("Error: {e}"),
+                }
+                // `use` may have switched to another API.
+                if let Some(helper) = rl.helper_mut() {
+                    helper.engine.set_base_url(session.client.base_url());
                 }
             }
             Err(ReadlineError::Interrupted) => {
@@ -56,9 +363,18 @@ pub async fn run_shell() {
             }
         }
     }
+
+    if let Some(path) = &history {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        if let Err(e) = rl.save_history(path) {
+            eprintln!("Couldn't save shell history to {}: {e}", path.display());
+        }
+    }
 }
 
-fn split_args(input: &str) -> Vec<String> {
+pub(crate) fn split_args(input: &str) -> Vec<String> {
     let mut args = Vec::new();
     let mut current = String::new();
     let mut in_quotes = false;
@@ -90,3 +406,41 @@ fn split_args(input: &str) -> Vec<String> {
 
     args
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(line: &str) -> Vec<String> {
+        split_args(line)
+    }
+
+    #[test]
+    fn built_ins_complete_and_parse() {
+        let engine = Engine::with_command(BuiltIn::augment_subcommands(Cli::command()));
+        let values = |line: &str, current: &str| -> Vec<String> { engine.complete(&words(line), current).into_iter().map(|c| c.value).collect() };
+        assert!(values("", "se").contains(&"set".to_string()));
+        assert_eq!(values("set", ""), ["output", "quotes"]);
+        assert_eq!(values("set output", ""), ["table", "json", "csv"]);
+
+        let settings = Settings { output: Some(Setting { value: "csv".into(), source: Source::Shell }), ..Settings::default() };
+        let matches = shell_command(&settings).try_get_matches_from(words("coinpaprika-cli last --raw")).unwrap();
+        assert!(matches!(BuiltIn::from_arg_matches(&matches), Ok(BuiltIn::Last { format: None })));
+        assert!(matches!(matches.get_one::<OutputFormat>("output"), Some(OutputFormat::Csv)) && matches.get_flag("raw"));
+    }
+
+    #[test]
+    fn split_handles_wide_spaces() {
+        for line in ["ticker\u{3000}bt", "ticker\u{a0}bt"] {
+            let (words, start, current) = ShellHelper::split(line, line.len());
+            assert_eq!((words, &line[start..], current), (vec!["ticker".to_string()], "bt", "bt"));
+        }
+    }
+
+    #[test]
+    fn history_skips_keys() {
+        assert!(has_key(&words("config set-key abc")));
+        assert!(has_key(&words("ticker btc-bitcoin --api-key=abc")));
+        assert!(!has_key(&words("ticker btc-bitcoin --quotes EUR")));
+    }
+}
@@ -0,0 +1,18 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct KeyInfo {
+    pub plan: Option<String>,
+    pub usage: Option<serde_json::Value>,
+    pub message: Option<String>,
+}
+
+/// One coin ID change from `/changelog/ids`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ChangelogEntry {
+    pub currency_id: Option<String>,
+    pub old_id: String,
+    pub new_id: String,
+    pub changed_at: Option<String>,
+}
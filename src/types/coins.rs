@@ -0,0 +1,154 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct CoinListItem {
+    pub id: String,
+    pub name: String,
+    pub symbol: String,
+    pub rank: Option<i64>,
+    pub is_new: Option<bool>,
+    pub is_active: Option<bool>,
+    #[serde(rename = "type")]
+    pub coin_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct CoinDetail {
+    pub id: String,
+    pub name: String,
+    pub symbol: String,
+    pub rank: Option<i64>,
+    pub is_new: Option<bool>,
+    pub is_active: Option<bool>,
+    #[serde(rename = "type")]
+    pub coin_type: Option<String>,
+    pub logo: Option<String>,
+    pub description: Option<String>,
+    pub open_source: Option<bool>,
+    pub started_at: Option<String>,
+    pub development_status: Option<String>,
+    pub hardware_wallet: Option<bool>,
+    pub proof_type: Option<String>,
+    pub org_structure: Option<String>,
+    pub hash_algorithm: Option<String>,
+    pub tags: Option<Vec<CoinTag>>,
+    pub team: Option<Vec<TeamMember>>,
+    pub links: Option<HashMap<String, serde_json::Value>>,
+    pub whitepaper: Option<Whitepaper>,
+    pub first_data_at: Option<String>,
+    pub last_data_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct CoinTag {
+    pub id: String,
+    pub name: String,
+    pub coin_counter: Option<i64>,
+    pub ico_counter: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct TeamMember {
+    pub id: String,
+    pub name: String,
+    pub position: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct Whitepaper {
+    pub link: Option<String>,
+    pub thumbnail: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct CoinEvent {
+    pub id: Option<String>,
+    pub date: Option<String>,
+    pub date_to: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub is_conference: Option<bool>,
+    pub link: Option<String>,
+    pub proof_image_link: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct CoinExchange {
+    pub id: String,
+    pub name: String,
+    pub adjusted_volume_24h_share: Option<f64>,
+    pub fiats: Option<Vec<FiatCurrency>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct FiatCurrency {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct CoinMarket {
+    pub exchange_id: Option<String>,
+    pub exchange_name: Option<String>,
+    pub pair: Option<String>,
+    pub base_currency_id: Option<String>,
+    pub base_currency_name: Option<String>,
+    pub quote_currency_id: Option<String>,
+    pub quote_currency_name: Option<String>,
+    pub market_url: Option<String>,
+    pub category: Option<String>,
+    pub fee_type: Option<String>,
+    pub outlier: Option<bool>,
+    pub adjusted_volume_24h_share: Option<f64>,
+    pub quotes: Option<HashMap<String, MarketQuote>>,
+    pub trust_score: Option<String>,
+    pub last_updated: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct MarketQuote {
+    pub price: Option<f64>,
+    pub volume_24h: Option<f64>,
+}
+
+/// One row of `/coins/mappings`: a coin's ID on other data providers.
+///
+/// The API mixes string and numeric IDs (`"coinmarketcap": 1`) and adds
+/// providers over time, so every provider is kept as a string in `providers`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CoinMapping {
+    pub coinpaprika: String,
+    #[serde(flatten)]
+    pub providers: BTreeMap<String, String>,
+}
+
+impl CoinMapping {
+    /// This coin's ID on `provider`, including `coinpaprika` itself.
+    pub fn id_for(&self, provider: &str) -> Option<&str> {
+        if provider == "coinpaprika" {
+            return Some(&self.coinpaprika);
+        }
+        self.providers.get(provider).map(String::as_str)
+    }
+}
+
+impl<'de> Deserialize<'de> for CoinMapping {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut raw = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+        let coinpaprika = match raw.remove("coinpaprika") {
+            Some(serde_json::Value::String(id)) => id,
+            _ => return Err(serde::de::Error::missing_field("coinpaprika")),
+        };
+        let providers = raw
+            .into_iter()
+            .filter_map(|(provider, value)| match value {
+                serde_json::Value::String(s) if !s.is_empty() => Some((provider, s)),
+                serde_json::Value::Number(n) => Some((provider, n.to_string())),
+                _ => None,
+            })
+            .collect();
+        Ok(Self { coinpaprika, providers })
+    }
+}
@@ -0,0 +1,51 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct Contract {
+    pub address: Option<String>,
+    #[serde(rename = "type")]
+    pub contract_type: Option<String>,
+    pub id: Option<String>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ContractTicker {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub rank: Option<i64>,
+    pub circulating_supply: Option<f64>,
+    pub total_supply: Option<f64>,
+    pub max_supply: Option<f64>,
+    pub beta_value: Option<f64>,
+    pub first_data_at: Option<String>,
+    pub last_updated: Option<String>,
+    pub quotes: Option<HashMap<String, ContractQuote>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ContractQuote {
+    pub price: Option<f64>,
+    pub volume_24h: Option<f64>,
+    pub volume_24h_change_24h: Option<f64>,
+    pub market_cap: Option<f64>,
+    pub market_cap_change_24h: Option<f64>,
+    pub percent_change_24h: Option<f64>,
+    pub percent_change_7d: Option<f64>,
+    pub percent_change_30d: Option<f64>,
+    pub percent_change_1y: Option<f64>,
+    pub ath_price: Option<f64>,
+    pub ath_date: Option<String>,
+    pub percent_from_price_ath: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ContractHistoryPoint {
+    pub timestamp: Option<String>,
+    pub price: Option<f64>,
+    pub volume_24h: Option<f64>,
+    pub market_cap: Option<f64>,
+}
@@ -0,0 +1,14 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ConvertResult {
+    pub base_currency_id: Option<String>,
+    pub base_currency_name: Option<String>,
+    pub base_price_last_updated: Option<String>,
+    pub quote_currency_id: Option<String>,
+    pub quote_currency_name: Option<String>,
+    pub quote_price_last_updated: Option<String>,
+    pub amount: Option<f64>,
+    pub price: Option<f64>,
+}
@@ -0,0 +1,57 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct Exchange {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub active: Option<bool>,
+    pub website_status: Option<bool>,
+    pub api_status: Option<bool>,
+    pub message: Option<String>,
+    pub links: Option<HashMap<String, serde_json::Value>>,
+    pub markets_data_fetched: Option<bool>,
+    pub adjusted_rank: Option<i64>,
+    pub reported_rank: Option<i64>,
+    pub currencies: Option<i64>,
+    pub markets: Option<i64>,
+    pub fiats: Option<Vec<serde_json::Value>>,
+    pub quotes: Option<HashMap<String, ExchangeQuote>>,
+    pub last_updated: Option<String>,
+    pub confidence_score: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ExchangeQuote {
+    pub reported_volume_24h: Option<f64>,
+    pub adjusted_volume_24h: Option<f64>,
+    pub reported_volume_7d: Option<f64>,
+    pub adjusted_volume_7d: Option<f64>,
+    pub reported_volume_30d: Option<f64>,
+    pub adjusted_volume_30d: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ExchangeMarket {
+    pub pair: Option<String>,
+    pub base_currency_id: Option<String>,
+    pub base_currency_name: Option<String>,
+    pub quote_currency_id: Option<String>,
+    pub quote_currency_name: Option<String>,
+    pub market_url: Option<String>,
+    pub category: Option<String>,
+    pub fee_type: Option<String>,
+    pub outlier: Option<bool>,
+    pub reported_volume_24h_share: Option<f64>,
+    pub quotes: Option<HashMap<String, ExchangeMarketQuote>>,
+    pub trust_score: Option<String>,
+    pub last_updated: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ExchangeMarketQuote {
+    pub price: Option<f64>,
+    pub volume_24h: Option<f64>,
+}
@@ -0,0 +1,19 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GlobalData {
+    pub market_cap_usd: Option<f64>,
+    pub volume_24h_usd: Option<f64>,
+    pub bitcoin_dominance_percentage: Option<f64>,
+    pub cryptocurrencies_number: Option<i64>,
+    pub market_cap_ath_value: Option<f64>,
+    pub market_cap_ath_date: Option<String>,
+    pub volume_24h_ath_value: Option<f64>,
+    pub volume_24h_ath_date: Option<String>,
+    pub volume_24h_percent_from_ath: Option<f64>,
+    pub volume_24h_percent_to_ath: Option<f64>,
+    pub market_cap_change_24h: Option<f64>,
+    pub volume_24h_change_24h: Option<f64>,
+    pub last_updated: Option<i64>,
+}
@@ -0,0 +1,32 @@
+//! Response types returned by the CoinPaprika API.
+//!
+//! Field names mirror the API's JSON so that `--output json` stays a faithful
+//! passthrough. Most fields are optional because the API omits them freely.
+
+/// Version of the JSON shapes produced by `--output json`, reported as
+/// `_meta.schema_version`. Bumped on any breaking change to these types.
+pub const SCHEMA_VERSION: &str = "1";
+
+pub mod global;
+pub mod coins;
+pub mod tickers;
+pub mod ohlcv;
+pub mod exchanges;
+pub mod tags;
+pub mod people;
+pub mod search;
+pub mod convert;
+pub mod contracts;
+pub mod api_management;
+
+pub use api_management::{ChangelogEntry, KeyInfo};
+pub use coins::{CoinDetail, CoinMapping, CoinEvent, CoinExchange, CoinListItem, CoinMarket, CoinTag, FiatCurrency, MarketQuote, TeamMember, Whitepaper};
+pub use contracts::{Contract, ContractHistoryPoint, ContractQuote, ContractTicker};
+pub use convert::ConvertResult;
+pub use exchanges::{Exchange, ExchangeMarket, ExchangeMarketQuote, ExchangeQuote};
+pub use global::GlobalData;
+pub use ohlcv::OhlcvData;
+pub use people::{Person, Position};
+pub use search::{SearchCurrency, SearchExchange, SearchPerson, SearchResult, SearchTag};
+pub use tags::Tag;
+pub use tickers::{Ticker, TickerHistoryPoint, TickerQuote};
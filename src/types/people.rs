@@ -0,0 +1,20 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct Person {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub teams_count: Option<i64>,
+    pub links: Option<HashMap<String, serde_json::Value>>,
+    pub positions: Option<Vec<Position>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct Position {
+    pub coin_id: Option<String>,
+    pub coin_name: Option<String>,
+    pub position: Option<String>,
+}
@@ -0,0 +1,43 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct SearchResult {
+    pub currencies: Option<Vec<SearchCurrency>>,
+    pub exchanges: Option<Vec<SearchExchange>>,
+    pub icos: Option<Vec<serde_json::Value>>,
+    pub people: Option<Vec<SearchPerson>>,
+    pub tags: Option<Vec<SearchTag>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct SearchCurrency {
+    pub id: String,
+    pub name: String,
+    pub symbol: String,
+    pub rank: Option<i64>,
+    pub is_active: Option<bool>,
+    #[serde(rename = "type")]
+    pub coin_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct SearchExchange {
+    pub id: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct SearchPerson {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub teams_count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct SearchTag {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub coin_counter: Option<i64>,
+    pub ico_counter: Option<i64>,
+}
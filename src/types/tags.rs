@@ -0,0 +1,14 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub tag_type: Option<String>,
+    pub coin_counter: Option<i64>,
+    pub ico_counter: Option<i64>,
+    pub coins: Option<Vec<String>>,
+}
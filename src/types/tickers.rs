@@ -0,0 +1,47 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct Ticker {
+    pub id: String,
+    pub name: String,
+    pub symbol: String,
+    pub rank: Option<i64>,
+    pub circulating_supply: Option<f64>,
+    pub total_supply: Option<f64>,
+    pub max_supply: Option<f64>,
+    pub beta_value: Option<f64>,
+    pub first_data_at: Option<String>,
+    pub last_updated: Option<String>,
+    pub quotes: Option<HashMap<String, TickerQuote>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct TickerQuote {
+    pub price: Option<f64>,
+    pub volume_24h: Option<f64>,
+    pub volume_24h_change_24h: Option<f64>,
+    pub market_cap: Option<f64>,
+    pub market_cap_change_24h: Option<f64>,
+    pub percent_change_15m: Option<f64>,
+    pub percent_change_30m: Option<f64>,
+    pub percent_change_1h: Option<f64>,
+    pub percent_change_6h: Option<f64>,
+    pub percent_change_12h: Option<f64>,
+    pub percent_change_24h: Option<f64>,
+    pub percent_change_7d: Option<f64>,
+    pub percent_change_30d: Option<f64>,
+    pub percent_change_1y: Option<f64>,
+    pub ath_price: Option<f64>,
+    pub ath_date: Option<String>,
+    pub percent_from_price_ath: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct TickerHistoryPoint {
+    pub timestamp: Option<String>,
+    pub price: Option<f64>,
+    pub volume_24h: Option<f64>,
+    pub market_cap: Option<f64>,
+}
@@ -0,0 +1,120 @@
+//! Which endpoints were called recently, with which key and how they
+//! answered, kept in the state directory so `doctor` can compare them with
+//! the plan.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::{config, secret};
+
+/// The log's file name in the state directory.
+pub const USAGE_FILE: &str = "endpoints.json";
+
+/// Entries unused for this long are dropped.
+const KEEP_FOR_DAYS: i64 = 90;
+
+/// Path segments that are part of an endpoint rather than an ID.
+const FIXED_SEGMENTS: &[&str] = &[
+    "coins", "contracts", "events", "exchanges", "global", "historical", "ids", "info", "key", "latest", "mappings",
+    "markets", "ohlcv", "people", "price-converter", "search", "tags", "tickers", "today", "changelog",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointUse {
+    /// [`key_label`] of the key that last called it; empty in logs written
+    /// before keys were recorded.
+    #[serde(default)]
+    pub key: String,
+    pub last_used: DateTime<Utc>,
+    pub last_status: u16,
+    pub calls: u64,
+}
+
+/// The endpoint a path belongs to, with IDs replaced:
+/// `/tickers/btc-bitcoin/historical` is `/tickers/{id}/historical`.
+pub fn endpoint(path: &str) -> String {
+    let path = path.split('?').next().unwrap_or_default().trim_end_matches('/');
+    path.split('/')
+        .skip(1)
+        .map(|segment| if FIXED_SEGMENTS.contains(&segment) { segment } else { "{id}" })
+        .fold(String::new(), |mut out, segment| {
+            out.push('/');
+            out.push_str(segment);
+            out
+        })
+}
+
+/// How the log names a key: its fingerprint, or `free` without one.
+pub fn key_label(api_key: Option<&str>) -> String {
+    api_key.map_or_else(|| "free".to_string(), secret::fingerprint)
+}
+
+fn usage_path() -> Result<PathBuf> {
+    Ok(config::state_dir()?.join(USAGE_FILE))
+}
+
+/// The log, by endpoint. Missing or unreadable logs are empty.
+pub fn load() -> BTreeMap<String, EndpointUse> {
+    usage_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Endpoints `key` (a [`key_label`]) used within `max_age`, most recent first.
+pub fn recent(max_age: Duration, key: &str) -> Vec<(String, EndpointUse)> {
+    let since = Utc::now() - max_age;
+    let mut recent: Vec<_> = load().into_iter().filter(|(_, u)| u.key == key && u.last_used >= since).collect();
+    recent.sort_by_key(|(_, u)| std::cmp::Reverse(u.last_used));
+    recent
+}
+
+/// Add a process's calls with `key` (endpoint → last status and count) to
+/// the log. An endpoint last called with another key starts over.
+pub fn record(calls: &BTreeMap<String, (u16, u64)>, key: &str) -> Result<()> {
+    if calls.is_empty() {
+        return Ok(());
+    }
+    let now = Utc::now();
+    let mut log = load();
+    log.retain(|_, u| now - u.last_used < Duration::days(KEEP_FOR_DAYS));
+    for (endpoint, &(status, count)) in calls {
+        let fresh = EndpointUse { key: key.to_string(), last_used: now, last_status: status, calls: 0 };
+        let entry = log.entry(endpoint.clone()).or_insert_with(|| fresh.clone());
+        if entry.key != key {
+            *entry = fresh;
+        }
+        entry.last_used = now;
+        entry.last_status = status;
+        entry.calls += count;
+    }
+
+    let path = usage_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Write then rename, so concurrent runs never leave half a file.
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&tmp, serde_json::to_string_pretty(&log)?)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_replaced() {
+        assert_eq!(endpoint("/tickers/btc-bitcoin/historical"), "/tickers/{id}/historical");
+        assert_eq!(endpoint("/coins/eth-ethereum/ohlcv/latest/"), "/coins/{id}/ohlcv/latest");
+        assert_eq!(endpoint("/contracts/eth-ethereum/0xdac1/historical"), "/contracts/{id}/{id}/historical");
+        assert_eq!(endpoint("/key/info?x=1"), "/key/info");
+        assert_eq!(endpoint("/global"), "/global");
+    }
+}